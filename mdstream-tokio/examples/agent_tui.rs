@@ -9,6 +9,7 @@ use mdstream::BlockKind;
 use mdstream::DocumentState;
 use mdstream::MdStream;
 use mdstream::Options;
+use mdstream::render::wrap_block_display;
 use mdstream_tokio::BackpressurePolicy;
 use mdstream_tokio::CoalescePreset;
 use mdstream_tokio::CoalescingReceiver;
@@ -29,7 +30,6 @@ use ratatui::widgets::Paragraph;
 use std::io;
 use std::time::Duration;
 use tokio::sync::mpsc;
-use unicode_width::UnicodeWidthChar;
 
 #[derive(Debug)]
 struct App {
@@ -453,53 +453,12 @@ fn render_block(kind: BlockKind, text: &str, width: u16, pending: bool) -> Vec<S
     };
     out.push(header);
 
-    match kind {
-        BlockKind::CodeFence | BlockKind::Table => {
-            for line in text.lines() {
-                out.push(line.to_string());
-            }
-        }
-        _ => {
-            for line in text.lines() {
-                out.extend(wrap_chars(line, width));
-            }
-        }
-    }
+    out.extend(wrap_block_display(kind, text, width as usize));
 
     out.push(String::new());
     out
 }
 
-fn wrap_chars(s: &str, width: u16) -> Vec<String> {
-    let width = width as usize;
-    if width == 0 {
-        return vec![];
-    }
-    if s.is_empty() {
-        return vec![String::new()];
-    }
-
-    let mut out = Vec::new();
-    let mut cur = String::new();
-    let mut cur_w = 0usize;
-
-    for ch in s.chars() {
-        let w = UnicodeWidthChar::width(ch).unwrap_or(0).max(1);
-        if cur_w + w > width && !cur.is_empty() {
-            out.push(std::mem::take(&mut cur));
-            cur_w = 0;
-        }
-        cur.push(ch);
-        cur_w += w;
-    }
-
-    if !cur.is_empty() {
-        out.push(cur);
-    }
-
-    out
-}
-
 fn max_scroll(content_h: u16, viewport_h: u16) -> u16 {
     content_h.saturating_sub(viewport_h)
 }
@@ -542,7 +501,7 @@ Now we stream a *large* code block to show pending truncation:
         tokio::time::sleep(Duration::from_millis(10)).await;
     }
 
-    let _ = tx.flush().await;
+    let _ = tx.finish().await;
 }
 
 fn chunk_by(s: &str, n: usize) -> Vec<String> {
@@ -4,13 +4,17 @@
 //! This crate provides small helpers for async producers:
 //!
 //! - Coalesce tiny deltas into larger chunks (newline-gated and/or time-window flush).
-//! - Optionally run an actor task that owns `MdStream` and emits owned `Update`s.
+//! - Optionally run an actor task that owns `MdStream` and emits owned `Update`s (or
+//!   `ActorEvent`s that flag the final `finalize` update).
+//! - Drive an `MdStream` straight from an `AsyncRead` (process stdout, socket), UTF-8 safe.
+//! - Merge those `Update`s on the consumer side (`UpdateCoalescingReceiver`).
 //!
 //! For a full TUI example, see `cargo run -p mdstream-tokio --example agent_tui`.
 
 use mdstream::MdStream;
 use mdstream::Update;
 use std::time::Duration;
+use tokio::io::{AsyncRead, AsyncReadExt};
 use tokio::sync::mpsc;
 use tokio::time::Instant;
 
@@ -20,8 +24,33 @@ pub struct CoalesceOptions {
     pub flush_on_newline: bool,
     /// Flush if no flush happened for this duration (progress guarantee).
     pub max_delay: Duration,
+    /// Restart the `max_delay` deadline on every delta (debounce instead of throttle).
+    ///
+    /// Text then flushes only after `max_delay` without new deltas. `max_bytes` (and the other
+    /// limits) still flush a continuous stream.
+    pub debounce: bool,
     /// Flush when buffered bytes reach this limit.
     pub max_bytes: usize,
+    /// Never return a chunk longer than this many bytes.
+    ///
+    /// `max_bytes` is checked after a whole message is appended, so one large message can exceed
+    /// it. With a hard cap, oversized text is split at a char boundary and emitted as several
+    /// `MaxBytes` chunks. A cap smaller than one character still returns that whole character.
+    pub hard_max_bytes: Option<usize>,
+    /// Flush after merging this many input messages (bounds staleness on a slow trickle).
+    pub max_messages: Option<usize>,
+    /// Report producer silence without closing the channel.
+    ///
+    /// If the buffer is empty and no delta arrived for this long, `recv_with_meta` returns an
+    /// empty chunk with `FlushReason::Idle`, once per idle period.
+    pub idle_timeout: Option<Duration>,
+    /// Let the actor spawn functions emit an update once no delta arrived and no update was emitted
+    /// for this long, and again every such period while the producer stays silent.
+    ///
+    /// A heartbeat update commits nothing and repeats the last pending block, so a UI that redraws
+    /// on every update keeps ticking (e.g. a spinner) during producer silence. Deltas that are
+    /// still being merged into a chunk postpone it. `CoalescingReceiver` itself ignores this.
+    pub heartbeat: Option<Duration>,
 }
 
 impl Default for CoalesceOptions {
@@ -29,8 +58,52 @@ impl Default for CoalesceOptions {
         Self {
             flush_on_newline: true,
             max_delay: Duration::from_millis(60),
+            debounce: false,
             max_bytes: 8 * 1024,
+            hard_max_bytes: None,
+            max_messages: None,
+            idle_timeout: None,
+            heartbeat: None,
+        }
+    }
+}
+
+impl CoalesceOptions {
+    /// `default()` with overrides from the environment, for trying settings without a rebuild.
+    ///
+    /// Reads `MDSTREAM_COALESCE_MAX_DELAY_MS` (milliseconds), `MDSTREAM_COALESCE_MAX_BYTES` and
+    /// `MDSTREAM_COALESCE_FLUSH_ON_NEWLINE` (`1`/`true`/`yes`/`on` or `0`/`false`/`no`/`off`).
+    /// Unset or unparsable variables keep their default.
+    pub fn from_env() -> Self {
+        Self::from_lookup(|name| std::env::var(name).ok())
+    }
+
+    fn from_lookup(lookup: impl Fn(&str) -> Option<String>) -> Self {
+        let mut opts = Self::default();
+        if let Some(ms) =
+            lookup("MDSTREAM_COALESCE_MAX_DELAY_MS").and_then(|v| v.trim().parse().ok())
+        {
+            opts.max_delay = Duration::from_millis(ms);
+        }
+        if let Some(bytes) =
+            lookup("MDSTREAM_COALESCE_MAX_BYTES").and_then(|v| v.trim().parse().ok())
+        {
+            opts.max_bytes = bytes;
+        }
+        if let Some(flag) =
+            lookup("MDSTREAM_COALESCE_FLUSH_ON_NEWLINE").and_then(|v| parse_flag(&v))
+        {
+            opts.flush_on_newline = flag;
         }
+        opts
+    }
+}
+
+fn parse_flag(value: &str) -> Option<bool> {
+    match value.trim().to_ascii_lowercase().as_str() {
+        "1" | "true" | "yes" | "on" => Some(true),
+        "0" | "false" | "no" | "off" => Some(false),
+        _ => None,
     }
 }
 
@@ -63,17 +136,32 @@ impl CoalescePreset {
             CoalescePreset::Balanced => CoalesceOptions {
                 flush_on_newline: true,
                 max_delay: Duration::from_millis(80),
+                debounce: false,
                 max_bytes: 16 * 1024,
+                hard_max_bytes: None,
+                max_messages: None,
+                idle_timeout: None,
+                heartbeat: None,
             },
             CoalescePreset::Fast => CoalesceOptions {
                 flush_on_newline: true,
                 max_delay: Duration::from_millis(30),
+                debounce: false,
                 max_bytes: 4 * 1024,
+                hard_max_bytes: None,
+                max_messages: None,
+                idle_timeout: None,
+                heartbeat: None,
             },
             CoalescePreset::TimeOnly => CoalesceOptions {
                 flush_on_newline: false,
                 max_delay: Duration::from_millis(60),
+                debounce: false,
                 max_bytes: 4 * 1024,
+                hard_max_bytes: None,
+                max_messages: None,
+                idle_timeout: None,
+                heartbeat: None,
             },
         }
     }
@@ -106,7 +194,14 @@ pub enum BackpressurePolicy {
     /// - deltas are very high-frequency (LLM token streams)
     /// - you still want to preserve content, but avoid stalling producers on every small chunk
     ///
-    /// Trade-off: memory is bounded by `local_max_bytes`; flushing becomes “chunky” under load.
+    /// Trade-off: flushing becomes “chunky” under load. Once the buffer reaches
+    /// `local_max_bytes * local_escalation_factor` (see [`DeltaSender::set_local_escalation_factor`])
+    /// the send awaits capacity like `Block`, so memory stays bounded and nothing is lost. With
+    /// escalation disabled ([`DeltaSender::set_local_escalation`]) that size is a hard cap and the
+    /// send fails with [`SendError::BufferFull`] instead.
+    ///
+    /// The local buffer is not flushed on drop (dropping cannot await): call
+    /// [`DeltaSender::finish`] (or [`DeltaSender::flush`]) when the producer is done.
     CoalesceLocal,
 }
 
@@ -117,9 +212,48 @@ pub enum SendOutcome {
     Buffered,
 }
 
+/// Why a `DeltaSender` could not take a delta.
+///
+/// Except for `Closed`, the rejected delta is not buffered; the caller may retry it later.
 #[derive(Clone, Copy, Debug, PartialEq, Eq)]
+#[non_exhaustive]
 pub enum SendError {
+    /// The receiving side was dropped.
     Closed,
+    /// [`DeltaSender::try_send`] would have had to await channel capacity.
+    WouldBlock,
+    /// `CoalesceLocal` reached its size cap with escalation disabled and the channel is full.
+    BufferFull,
+}
+
+impl std::fmt::Display for SendError {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.write_str(match self {
+            SendError::Closed => "channel closed",
+            SendError::WouldBlock => "send would block",
+            SendError::BufferFull => "local buffer full",
+        })
+    }
+}
+
+impl std::error::Error for SendError {}
+
+/// Counters kept by a `DeltaSender`.
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq)]
+pub struct SendStats {
+    /// Messages handed to the channel (including local-buffer flushes).
+    pub sent: u64,
+    /// Deltas dropped under `BackpressurePolicy::DropNew`.
+    pub dropped: u64,
+    /// Deltas kept in the local buffer under `BackpressurePolicy::CoalesceLocal`.
+    pub buffered: u64,
+    /// `CoalesceLocal` sends that outgrew the escalation threshold and awaited capacity.
+    pub escalated: u64,
+    /// Total time spent awaiting channel capacity, in nanoseconds.
+    ///
+    /// Only sends that found the channel full are timed. A growing value means the consumer
+    /// (e.g. the UI) is the bottleneck rather than the producer.
+    pub blocked_nanos: u64,
 }
 
 /// Producer-side helper for bounded channels.
@@ -132,6 +266,9 @@ pub struct DeltaSender {
     policy: BackpressurePolicy,
     local_buf: String,
     local_max_bytes: usize,
+    local_escalation_factor: usize,
+    local_escalation: bool,
+    stats: SendStats,
 }
 
 impl DeltaSender {
@@ -141,6 +278,9 @@ impl DeltaSender {
             policy,
             local_buf: String::new(),
             local_max_bytes: 16 * 1024,
+            local_escalation_factor: 4,
+            local_escalation: true,
+            stats: SendStats::default(),
         }
     }
 
@@ -148,6 +288,18 @@ impl DeltaSender {
         self.local_max_bytes = max.max(1);
     }
 
+    /// Under `CoalesceLocal`, await capacity once the local buffer holds `factor` times
+    /// `local_max_bytes` (default 4) instead of buffering further.
+    pub fn set_local_escalation_factor(&mut self, factor: usize) {
+        self.local_escalation_factor = factor.max(1);
+    }
+
+    /// With escalation disabled, `CoalesceLocal` sends that would reach the escalation threshold
+    /// fail with [`SendError::BufferFull`] while the channel stays full, instead of awaiting.
+    pub fn set_local_escalation(&mut self, enabled: bool) {
+        self.local_escalation = enabled;
+    }
+
     pub fn policy(&self) -> BackpressurePolicy {
         self.policy
     }
@@ -156,31 +308,87 @@ impl DeltaSender {
         self.policy = policy;
     }
 
+    pub fn stats(&self) -> SendStats {
+        self.stats
+    }
+
+    pub fn reset_stats(&mut self) {
+        self.stats = SendStats::default();
+    }
+
     pub async fn send(&mut self, delta: &str) -> Result<SendOutcome, SendError> {
-        match self.policy {
+        let outcome = match self.policy {
             BackpressurePolicy::Block => self.send_block(delta).await,
             BackpressurePolicy::DropNew => self.send_drop_new(delta),
             BackpressurePolicy::CoalesceLocal => self.send_coalesce_local(delta).await,
+        }?;
+        self.record_outcome(outcome);
+        Ok(outcome)
+    }
+
+    /// Non-async `send` for callers that cannot await (e.g. sync callbacks).
+    ///
+    /// Where `send` would await capacity (`Block` on a full channel, `CoalesceLocal` at its
+    /// escalation threshold), this fails with [`SendError::WouldBlock`] and keeps nothing.
+    pub fn try_send(&mut self, delta: &str) -> Result<SendOutcome, SendError> {
+        let outcome = match self.policy {
+            BackpressurePolicy::Block => match self.tx.try_send(delta.to_string()) {
+                Ok(()) => Ok(SendOutcome::Sent),
+                Err(mpsc::error::TrySendError::Full(_)) => Err(SendError::WouldBlock),
+                Err(mpsc::error::TrySendError::Closed(_)) => Err(SendError::Closed),
+            },
+            BackpressurePolicy::DropNew => self.send_drop_new(delta),
+            BackpressurePolicy::CoalesceLocal => self.try_coalesce_local(delta),
+        }?;
+        self.record_outcome(outcome);
+        Ok(outcome)
+    }
+
+    fn record_outcome(&mut self, outcome: SendOutcome) {
+        match outcome {
+            SendOutcome::Sent => self.stats.sent += 1,
+            SendOutcome::Dropped => self.stats.dropped += 1,
+            SendOutcome::Buffered => self.stats.buffered += 1,
         }
     }
 
+    /// Flush any locally buffered text and consume the sender (closing this side of the channel).
+    pub async fn finish(mut self) -> Result<(), SendError> {
+        self.flush().await?;
+        Ok(())
+    }
+
     pub async fn flush(&mut self) -> Result<SendOutcome, SendError> {
         if self.local_buf.is_empty() {
             return Ok(SendOutcome::Sent);
         }
         let buf = std::mem::take(&mut self.local_buf);
-        self.tx.send(buf).await.map_err(|_| SendError::Closed)?;
+        self.send_awaiting(buf).await?;
+        self.stats.sent += 1;
         Ok(SendOutcome::Sent)
     }
 
     async fn send_block(&mut self, delta: &str) -> Result<SendOutcome, SendError> {
-        self.tx
-            .send(delta.to_string())
-            .await
-            .map_err(|_| SendError::Closed)?;
+        self.send_awaiting(delta.to_string()).await?;
         Ok(SendOutcome::Sent)
     }
 
+    /// Send, awaiting capacity if needed. Only the awaited part counts as blocked time.
+    async fn send_awaiting(&mut self, msg: String) -> Result<(), SendError> {
+        let msg = match self.tx.try_send(msg) {
+            Ok(()) => return Ok(()),
+            Err(tokio::sync::mpsc::error::TrySendError::Full(msg)) => msg,
+            Err(tokio::sync::mpsc::error::TrySendError::Closed(_)) => {
+                return Err(SendError::Closed);
+            }
+        };
+        let started = Instant::now();
+        let res = self.tx.send(msg).await.map_err(|_| SendError::Closed);
+        let nanos = u64::try_from(started.elapsed().as_nanos()).unwrap_or(u64::MAX);
+        self.stats.blocked_nanos = self.stats.blocked_nanos.saturating_add(nanos);
+        res
+    }
+
     fn send_drop_new(&mut self, delta: &str) -> Result<SendOutcome, SendError> {
         match self.tx.try_send(delta.to_string()) {
             Ok(()) => Ok(SendOutcome::Sent),
@@ -190,25 +398,64 @@ impl DeltaSender {
     }
 
     async fn send_coalesce_local(&mut self, delta: &str) -> Result<SendOutcome, SendError> {
-        self.local_buf.push_str(delta);
+        match self.try_coalesce_local(delta) {
+            Err(SendError::WouldBlock) if self.local_escalation => {
+                // The consumer has not drained for a while: stop growing and wait for it.
+                let mut buf = std::mem::take(&mut self.local_buf);
+                buf.push_str(delta);
+                self.send_awaiting(buf).await?;
+                self.stats.escalated += 1;
+                Ok(SendOutcome::Sent)
+            }
+            Err(SendError::WouldBlock) => Err(SendError::BufferFull),
+            res => res,
+        }
+    }
 
-        let should_try_flush =
-            self.local_buf.len() >= self.local_max_bytes || self.local_buf.contains('\n');
+    /// `CoalesceLocal` without awaiting: `WouldBlock` once `delta` would bring the buffer to the
+    /// escalation threshold and the channel is still full (`delta` is not buffered then).
+    fn try_coalesce_local(&mut self, delta: &str) -> Result<SendOutcome, SendError> {
+        let escalate_at = self
+            .local_max_bytes
+            .saturating_mul(self.local_escalation_factor);
+        let at_cap = self.local_buf.len() + delta.len() >= escalate_at;
+        let should_try_flush = at_cap
+            || self.local_buf.len() + delta.len() >= self.local_max_bytes
+            || delta.contains('\n')
+            || self.local_buf.contains('\n');
+        if !should_try_flush {
+            self.local_buf.push_str(delta);
+            return Ok(SendOutcome::Buffered);
+        }
 
-        if should_try_flush {
-            match self.tx.try_send(std::mem::take(&mut self.local_buf)) {
-                Ok(()) => return Ok(SendOutcome::Sent),
-                Err(tokio::sync::mpsc::error::TrySendError::Full(s)) => {
-                    self.local_buf = s;
-                    return Ok(SendOutcome::Buffered);
-                }
-                Err(tokio::sync::mpsc::error::TrySendError::Closed(_)) => {
-                    return Err(SendError::Closed);
-                }
+        let restore_len = self.local_buf.len();
+        self.local_buf.push_str(delta);
+        match self.tx.try_send(std::mem::take(&mut self.local_buf)) {
+            Ok(()) => Ok(SendOutcome::Sent),
+            Err(mpsc::error::TrySendError::Full(mut s)) if at_cap => {
+                s.truncate(restore_len);
+                self.local_buf = s;
+                Err(SendError::WouldBlock)
+            }
+            Err(mpsc::error::TrySendError::Full(s)) => {
+                self.local_buf = s;
+                Ok(SendOutcome::Buffered)
             }
+            Err(mpsc::error::TrySendError::Closed(_)) => Err(SendError::Closed),
         }
+    }
+}
 
-        Ok(SendOutcome::Buffered)
+impl Drop for DeltaSender {
+    fn drop(&mut self) {
+        // Catch forgotten `finish()`/`flush()` in debug builds; a closed channel loses data anyway.
+        if !std::thread::panicking() {
+            debug_assert!(
+                self.local_buf.is_empty() || self.tx.is_closed(),
+                "DeltaSender dropped with {} unflushed bytes; call `finish().await` first",
+                self.local_buf.len()
+            );
+        }
     }
 }
 
@@ -218,6 +465,12 @@ pub enum FlushReason {
     MaxDelay,
     MaxBytes,
     ChannelClosed,
+    /// `CoalesceOptions::max_messages` input messages were merged.
+    MaxMessages,
+    /// `UpdateCoalesceOptions::max_updates` updates were merged.
+    MaxUpdates,
+    /// No delta arrived within `CoalesceOptions::idle_timeout` (the chunk text is empty).
+    Idle,
 }
 
 #[derive(Clone, Debug, PartialEq, Eq)]
@@ -228,6 +481,15 @@ pub struct CoalescedChunk {
     pub merged_messages: usize,
 }
 
+/// A borrowed `CoalescedChunk`, lent to the closure passed to `CoalescingReceiver::recv_with`.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub struct CoalescedChunkRef<'a> {
+    pub text: &'a str,
+    pub reason: FlushReason,
+    /// Number of input messages merged into this output chunk.
+    pub merged_messages: usize,
+}
+
 #[derive(Clone, Copy, Debug, Default, PartialEq, Eq)]
 pub struct CoalesceStats {
     pub total_in_messages: u64,
@@ -236,15 +498,45 @@ pub struct CoalesceStats {
     pub last_reason: Option<FlushReason>,
     pub last_merged_messages: usize,
     pub last_bytes: usize,
+    /// Largest internal buffer observed, in bytes (useful for tuning `max_bytes`).
+    pub high_water: usize,
 }
 
+impl CoalesceStats {
+    /// Input messages per output chunk (`0.0` before the first chunk).
+    pub fn compression_ratio(&self) -> f64 {
+        if self.total_out_chunks == 0 {
+            return 0.0;
+        }
+        self.total_in_messages as f64 / self.total_out_chunks as f64
+    }
+
+    /// Average output chunk size in bytes (`0.0` before the first chunk).
+    pub fn avg_chunk_bytes(&self) -> f64 {
+        if self.total_out_chunks == 0 {
+            return 0.0;
+        }
+        self.total_out_bytes as f64 / self.total_out_chunks as f64
+    }
+}
+
+type FlushHook = Box<dyn FnMut(&CoalescedChunkRef<'_>) + Send>;
+
 /// A receiver wrapper that merges high-frequency deltas into fewer, larger chunks.
 pub struct CoalescingReceiver {
     rx: mpsc::Receiver<String>,
     opts: CoalesceOptions,
     buf: String,
+    /// Text past `hard_max_bytes`, emitted by the following flushes.
+    overflow: String,
     deadline: Option<Instant>,
+    /// Input messages merged into `buf` since the last flush. Kept here (not in `next_flush`) so
+    /// a cancelled `recv` does not lose the count.
+    merged_messages: usize,
     stats: CoalesceStats,
+    last_activity: Instant,
+    idle_reported: bool,
+    on_flush: Option<FlushHook>,
 }
 
 impl CoalescingReceiver {
@@ -253,11 +545,25 @@ impl CoalescingReceiver {
             rx,
             opts,
             buf: String::new(),
+            overflow: String::new(),
             deadline: None,
+            merged_messages: 0,
             stats: CoalesceStats::default(),
+            last_activity: Instant::now(),
+            idle_reported: false,
+            on_flush: None,
         }
     }
 
+    /// Call `f` for every chunk right before `recv`/`recv_with_meta`/`recv_with` return it.
+    ///
+    /// This includes the final `ChannelClosed` flush and `Idle` notices. Stats are updated before
+    /// `f` runs, so they already count the chunk (`Idle` notices are never counted). Replaces
+    /// any previous callback.
+    pub fn on_flush(&mut self, f: impl FnMut(&CoalescedChunkRef<'_>) + Send + 'static) {
+        self.on_flush = Some(Box::new(f));
+    }
+
     pub fn set_options(&mut self, opts: CoalesceOptions) {
         self.opts = opts;
         // Keep any buffered text; refresh the deadline based on the new policy.
@@ -274,42 +580,100 @@ impl CoalescingReceiver {
         self.stats
     }
 
+    /// Bytes buffered inside the receiver, not yet returned by `recv`.
+    ///
+    /// This is at most about one chunk: a slow consumer backs up the underlying channel instead,
+    /// whose capacity bounds the remaining memory.
+    pub fn pending_bytes(&self) -> usize {
+        self.buf.len() + self.overflow.len()
+    }
+
+    /// Text buffered for the next chunk, possibly empty, e.g. for a faint "incoming" preview.
+    ///
+    /// Text is buffered while a `recv` call waits for its flush condition. Since `recv` is
+    /// cancel-safe, a render tick racing it in `tokio::select!` can read what has arrived so far;
+    /// the next `recv` continues the same chunk and delivers this text first. Text past `hard_max_bytes`, waiting for a later flush, is
+    /// not included (`pending_bytes` counts it).
+    pub fn buffered(&self) -> &str {
+        &self.buf
+    }
+
+    /// Clear accumulated stats (e.g. at message boundaries). Buffered text is kept.
+    pub fn reset_stats(&mut self) {
+        self.stats = CoalesceStats::default();
+    }
+
     /// Receive the next coalesced chunk.
     ///
     /// - Returns `None` when the underlying channel is closed and the internal buffer is empty.
     /// - Returns a final buffered chunk before finishing, if any.
+    /// - Returns an empty string for idle periods when `CoalesceOptions::idle_timeout` is set.
+    ///
+    /// Cancel-safe (as are `recv_with_meta` and `recv_with`): if the future is dropped before it
+    /// completes, the buffered text, its merge count and the `max_delay` deadline are kept, and the
+    /// next call continues the same chunk.
     pub async fn recv(&mut self) -> Option<String> {
         self.recv_with_meta().await.map(|c| c.text)
     }
 
     pub async fn recv_with_meta(&mut self) -> Option<CoalescedChunk> {
-        let mut merged_messages = 0usize;
+        let (reason, merged_messages) = self.next_flush().await?;
+        self.notify_flush(reason, merged_messages);
+        Some(CoalescedChunk {
+            text: self.take_buf(),
+            reason,
+            merged_messages,
+        })
+    }
+
+    /// Like `recv_with_meta`, but lends the chunk to `f` instead of allocating a `String`.
+    ///
+    /// The text borrows the internal buffer, which is cleared (keeping its capacity) after `f`
+    /// returns. Handy for feeding `MdStream::append` directly.
+    pub async fn recv_with<R>(&mut self, f: impl FnOnce(&CoalescedChunkRef<'_>) -> R) -> Option<R> {
+        let (reason, merged_messages) = self.next_flush().await?;
+        self.notify_flush(reason, merged_messages);
+        let out = f(&CoalescedChunkRef {
+            text: &self.buf,
+            reason,
+            merged_messages,
+        });
+        self.buf.clear();
+        std::mem::swap(&mut self.buf, &mut self.overflow);
+        self.rearm_deadline();
+        Some(out)
+    }
 
-        if self.buf.is_empty() {
-            let first = self.rx.recv().await?;
+    /// Wait until the buffered text should be flushed; returns the reason and merged count.
+    ///
+    /// Stats are recorded here for every flush except idle notices (whose buffer is empty). All
+    /// progress lives in `self` and is only updated between awaits, so dropping the future (e.g.
+    /// a `recv` losing a `tokio::select!` race) loses nothing: the next call resumes the same chunk.
+    async fn next_flush(&mut self) -> Option<(FlushReason, usize)> {
+        if self.buf.is_empty() && self.merged_messages == 0 {
+            let first = match self.opts.idle_timeout {
+                Some(idle) if !self.idle_reported => {
+                    let idle_deadline = self.last_activity + idle;
+                    match tokio::time::timeout_at(idle_deadline, self.rx.recv()).await {
+                        Ok(next) => next?,
+                        Err(_) => {
+                            // Report each idle period once; the next delta re-arms it.
+                            self.idle_reported = true;
+                            return Some((FlushReason::Idle, 0));
+                        }
+                    }
+                }
+                _ => self.rx.recv().await?,
+            };
+            self.note_activity();
             self.buf.push_str(&first);
-            merged_messages += 1;
+            self.merged_messages += 1;
             self.deadline = Some(Instant::now() + self.opts.max_delay);
         }
 
         loop {
-            if let Some(reason) = self.should_flush_reason() {
-                let text = self.take_buf();
-                self.stats.total_in_messages = self
-                    .stats
-                    .total_in_messages
-                    .saturating_add(merged_messages as u64);
-                self.stats.total_out_chunks = self.stats.total_out_chunks.saturating_add(1);
-                self.stats.total_out_bytes =
-                    self.stats.total_out_bytes.saturating_add(text.len() as u64);
-                self.stats.last_reason = Some(reason);
-                self.stats.last_merged_messages = merged_messages;
-                self.stats.last_bytes = text.len();
-                return Some(CoalescedChunk {
-                    text,
-                    reason,
-                    merged_messages,
-                });
+            if let Some(reason) = self.should_flush_reason(self.merged_messages) {
+                return Some(self.finish_flush(reason));
             }
 
             let Some(deadline) = self.deadline else {
@@ -320,102 +684,449 @@ impl CoalescingReceiver {
             let next = tokio::time::timeout_at(deadline, self.rx.recv()).await;
             match next {
                 Ok(Some(s)) => {
+                    self.note_activity();
                     self.buf.push_str(&s);
-                    merged_messages += 1;
+                    self.merged_messages += 1;
+                    if self.opts.debounce {
+                        self.deadline = Some(Instant::now() + self.opts.max_delay);
+                    }
                 }
                 Ok(None) => {
                     // Channel closed: flush remaining buffer once.
                     if self.buf.is_empty() {
+                        self.merged_messages = 0;
                         return None;
                     }
-                    let reason = FlushReason::ChannelClosed;
-                    let text = self.take_buf();
-                    self.stats.total_in_messages = self
-                        .stats
-                        .total_in_messages
-                        .saturating_add(merged_messages as u64);
-                    self.stats.total_out_chunks = self.stats.total_out_chunks.saturating_add(1);
-                    self.stats.total_out_bytes =
-                        self.stats.total_out_bytes.saturating_add(text.len() as u64);
-                    self.stats.last_reason = Some(reason);
-                    self.stats.last_merged_messages = merged_messages;
-                    self.stats.last_bytes = text.len();
-                    return Some(CoalescedChunk {
-                        text,
-                        reason,
-                        merged_messages,
-                    });
+                    return Some(self.finish_flush(FlushReason::ChannelClosed));
                 }
                 Err(_) => {
                     // Timeout: flush for progress.
-                    let reason = FlushReason::MaxDelay;
-                    let text = self.take_buf();
-                    self.stats.total_in_messages = self
-                        .stats
-                        .total_in_messages
-                        .saturating_add(merged_messages as u64);
-                    self.stats.total_out_chunks = self.stats.total_out_chunks.saturating_add(1);
-                    self.stats.total_out_bytes =
-                        self.stats.total_out_bytes.saturating_add(text.len() as u64);
-                    self.stats.last_reason = Some(reason);
-                    self.stats.last_merged_messages = merged_messages;
-                    self.stats.last_bytes = text.len();
-                    return Some(CoalescedChunk {
-                        text,
-                        reason,
-                        merged_messages,
-                    });
+                    return Some(self.finish_flush(FlushReason::MaxDelay));
                 }
             }
         }
     }
 
-    fn should_flush_reason(&self) -> Option<FlushReason> {
-        if self.buf.len() >= self.opts.max_bytes {
+    /// Split off oversized text, record the flush and start counting the next chunk.
+    fn finish_flush(&mut self, reason: FlushReason) -> (FlushReason, usize) {
+        let reason = self.split_oversized(reason);
+        let merged_messages = std::mem::take(&mut self.merged_messages);
+        self.record_flush(reason, merged_messages);
+        (reason, merged_messages)
+    }
+
+    fn record_flush(&mut self, reason: FlushReason, merged_messages: usize) {
+        let bytes = self.buf.len();
+        self.stats.total_in_messages = self
+            .stats
+            .total_in_messages
+            .saturating_add(merged_messages as u64);
+        self.stats.total_out_chunks = self.stats.total_out_chunks.saturating_add(1);
+        self.stats.total_out_bytes = self.stats.total_out_bytes.saturating_add(bytes as u64);
+        self.stats.last_reason = Some(reason);
+        self.stats.last_merged_messages = merged_messages;
+        self.stats.last_bytes = bytes;
+        self.stats.high_water = self.stats.high_water.max(bytes);
+    }
+
+    fn notify_flush(&mut self, reason: FlushReason, merged_messages: usize) {
+        if let Some(hook) = self.on_flush.as_mut() {
+            hook(&CoalescedChunkRef {
+                text: &self.buf,
+                reason,
+                merged_messages,
+            });
+        }
+    }
+
+    fn should_flush_reason(&self, merged_messages: usize) -> Option<FlushReason> {
+        let over_cap = self
+            .opts
+            .hard_max_bytes
+            .is_some_and(|cap| self.buf.len() > cap);
+        if over_cap || self.buf.len() >= self.opts.max_bytes {
             return Some(FlushReason::MaxBytes);
         }
         if self.opts.flush_on_newline && self.buf.contains('\n') {
             return Some(FlushReason::Newline);
         }
+        if self
+            .opts
+            .max_messages
+            .is_some_and(|max| merged_messages >= max.max(1))
+        {
+            return Some(FlushReason::MaxMessages);
+        }
         None
     }
 
+    fn note_activity(&mut self) {
+        self.last_activity = Instant::now();
+        self.idle_reported = false;
+    }
+
     fn take_buf(&mut self) -> String {
-        self.deadline = None;
-        std::mem::take(&mut self.buf)
+        let out = std::mem::replace(&mut self.buf, std::mem::take(&mut self.overflow));
+        self.rearm_deadline();
+        out
+    }
+
+    /// Start the `max_delay` window for carried-over overflow text (none when empty).
+    fn rearm_deadline(&mut self) {
+        self.deadline = (!self.buf.is_empty()).then(|| Instant::now() + self.opts.max_delay);
+    }
+
+    /// Move text past `hard_max_bytes` to `overflow`; an oversized chunk is reported as `MaxBytes`.
+    fn split_oversized(&mut self, reason: FlushReason) -> FlushReason {
+        let Some(cap) = self.opts.hard_max_bytes else {
+            return reason;
+        };
+        if self.buf.len() <= cap {
+            return reason;
+        }
+        self.stats.high_water = self.stats.high_water.max(self.buf.len());
+        let mut cut = cap;
+        while !self.buf.is_char_boundary(cut) {
+            cut -= 1;
+        }
+        if cut == 0 {
+            cut = self.buf.chars().next().map_or(0, char::len_utf8);
+        }
+        let rest = self.buf.split_off(cut);
+        self.overflow.insert_str(0, &rest);
+        FlushReason::MaxBytes
+    }
+}
+
+#[derive(Clone, Copy, Debug)]
+pub struct UpdateCoalesceOptions {
+    /// Flush if no flush happened for this duration (progress guarantee).
+    pub max_delay: Duration,
+    /// Flush once this many updates were merged.
+    pub max_updates: usize,
+}
+
+impl Default for UpdateCoalesceOptions {
+    fn default() -> Self {
+        Self {
+            max_delay: Duration::from_millis(60),
+            max_updates: 64,
+        }
+    }
+}
+
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct CoalescedUpdate {
+    pub update: Update,
+    pub reason: FlushReason,
+    /// Number of input updates merged into this output update.
+    pub merged_updates: usize,
+}
+
+/// The `Update`-level analog of [`CoalescingReceiver`].
+///
+/// Merges updates from an `MdStream` producer (e.g. [`spawn_mdstream_actor`]) with
+/// `Update::merge`, so applying the merged update is equivalent to applying each input in order.
+pub struct UpdateCoalescingReceiver {
+    rx: mpsc::Receiver<Update>,
+    opts: UpdateCoalesceOptions,
+}
+
+impl UpdateCoalescingReceiver {
+    pub fn new(rx: mpsc::Receiver<Update>, opts: UpdateCoalesceOptions) -> Self {
+        Self { rx, opts }
+    }
+
+    pub fn set_options(&mut self, opts: UpdateCoalesceOptions) {
+        self.opts = opts;
+    }
+
+    pub fn options(&self) -> UpdateCoalesceOptions {
+        self.opts
+    }
+
+    /// Receive the next merged update.
+    ///
+    /// Returns `None` once the underlying channel is closed and everything was delivered.
+    pub async fn recv(&mut self) -> Option<Update> {
+        self.recv_with_meta().await.map(|c| c.update)
+    }
+
+    pub async fn recv_with_meta(&mut self) -> Option<CoalescedUpdate> {
+        let mut update = self.rx.recv().await?;
+        let mut merged_updates = 1usize;
+        let deadline = Instant::now() + self.opts.max_delay;
+
+        let reason = loop {
+            if merged_updates >= self.opts.max_updates.max(1) {
+                break FlushReason::MaxUpdates;
+            }
+            match tokio::time::timeout_at(deadline, self.rx.recv()).await {
+                Ok(Some(next)) => {
+                    update.merge(next);
+                    merged_updates += 1;
+                }
+                Ok(None) => break FlushReason::ChannelClosed,
+                Err(_) => break FlushReason::MaxDelay,
+            }
+        };
+
+        Some(CoalescedUpdate {
+            update,
+            reason,
+            merged_updates,
+        })
+    }
+}
+
+/// What [`spawn_mdstream_actor_events`] emits.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub enum ActorEvent {
+    /// Result of appending one coalesced chunk.
+    Update(Update),
+    /// Result of `finalize` once the input channel closed; always the last event.
+    ///
+    /// Streaming is complete at this point (e.g. stop a spinner).
+    Finalized(Update),
+}
+
+impl ActorEvent {
+    pub fn is_finalized(&self) -> bool {
+        matches!(self, ActorEvent::Finalized(_))
+    }
+
+    pub fn update(&self) -> &Update {
+        match self {
+            ActorEvent::Update(u) | ActorEvent::Finalized(u) => u,
+        }
+    }
+
+    pub fn into_update(self) -> Update {
+        match self {
+            ActorEvent::Update(u) | ActorEvent::Finalized(u) => u,
+        }
     }
 }
 
 /// Spawn a task that owns `MdStream` and emits owned `Update`s.
 ///
 /// This is useful when your consumer cannot keep `MdStream` on the UI thread, or when you want to
-/// isolate parsing work from rendering.
+/// isolate parsing work from rendering. The last update comes from `finalize`; use
+/// [`spawn_mdstream_actor_events`] to tell it apart.
 pub fn spawn_mdstream_actor(
-    mut stream: MdStream,
+    stream: MdStream,
     rx: mpsc::Receiver<String>,
     opts: CoalesceOptions,
 ) -> mpsc::Receiver<Update> {
-    let (tx_out, rx_out) = mpsc::channel::<Update>(64);
+    spawn_actor(stream, rx, opts, |update, _| update)
+}
+
+/// Like [`spawn_mdstream_actor`], but marks the `finalize` update as [`ActorEvent::Finalized`].
+pub fn spawn_mdstream_actor_events(
+    stream: MdStream,
+    rx: mpsc::Receiver<String>,
+    opts: CoalesceOptions,
+) -> mpsc::Receiver<ActorEvent> {
+    spawn_actor(stream, rx, opts, |update, finalized| {
+        if finalized {
+            ActorEvent::Finalized(update)
+        } else {
+            ActorEvent::Update(update)
+        }
+    })
+}
+
+fn spawn_actor<T: Send + 'static>(
+    mut stream: MdStream,
+    rx: mpsc::Receiver<String>,
+    opts: CoalesceOptions,
+    wrap: fn(Update, bool) -> T,
+) -> mpsc::Receiver<T> {
+    let (tx_out, rx_out) = mpsc::channel::<T>(64);
 
     tokio::spawn(async move {
+        let heartbeat = opts.heartbeat;
         let mut rx = CoalescingReceiver::new(rx, opts);
-        while let Some(chunk) = rx.recv().await {
+        let mut last_pending = None;
+        let mut last_emit = Instant::now();
+        loop {
+            let next = match heartbeat {
+                // `recv` is cancel-safe: a chunk being merged when the timer fires is resumed.
+                Some(every) => {
+                    let due = last_emit.max(rx.last_activity) + every;
+                    match tokio::time::timeout_at(due, rx.recv()).await {
+                        Ok(next) => next,
+                        Err(_) if rx.last_activity + every > Instant::now() => {
+                            // A delta arrived meanwhile: not silent yet.
+                            continue;
+                        }
+                        Err(_) => {
+                            let mut u = Update::empty();
+                            u.pending.clone_from(&last_pending);
+                            if tx_out.send(wrap(u, false)).await.is_err() {
+                                return;
+                            }
+                            last_emit = Instant::now();
+                            continue;
+                        }
+                    }
+                }
+                None => rx.recv().await,
+            };
+            let Some(chunk) = next else {
+                break;
+            };
+            if chunk.is_empty() {
+                // Idle sentinel (see `CoalesceOptions::idle_timeout`): nothing to parse.
+                continue;
+            }
             let u = stream.append(&chunk);
-            if tx_out.send(u).await.is_err() {
+            if heartbeat.is_some() {
+                last_pending.clone_from(&u.pending);
+            }
+            if tx_out.send(wrap(u, false)).await.is_err() {
                 return;
             }
+            last_emit = Instant::now();
         }
         let u = stream.finalize();
-        let _ = tx_out.send(u).await;
+        let _ = tx_out.send(wrap(u, true)).await;
     });
 
     rx_out
 }
 
+/// Spawn a task that reads `reader` to the end and drives `stream` with what it reads.
+///
+/// Reads are decoded as UTF-8 with a character split across two reads held back until it is
+/// complete; invalid bytes become `U+FFFD`. The text is coalesced with `opts` and parsed as in
+/// [`spawn_mdstream_actor`], with a final `finalize` update once the reader hits EOF. A read error
+/// ends the input the same way.
+pub fn spawn_mdstream_from_reader<R>(
+    mut reader: R,
+    stream: MdStream,
+    opts: CoalesceOptions,
+) -> mpsc::Receiver<Update>
+where
+    R: AsyncRead + Unpin + Send + 'static,
+{
+    let (tx, rx) = mpsc::channel::<String>(64);
+
+    tokio::spawn(async move {
+        let mut buf = vec![0u8; 8 * 1024];
+        let mut decoder = Utf8Decoder::default();
+        loop {
+            let n = match reader.read(&mut buf).await {
+                Ok(0) | Err(_) => break,
+                Ok(n) => n,
+            };
+            let text = decoder.push(&buf[..n]);
+            if !text.is_empty() && tx.send(text).await.is_err() {
+                return;
+            }
+        }
+        let rest = decoder.finish();
+        if !rest.is_empty() {
+            let _ = tx.send(rest).await;
+        }
+    });
+
+    spawn_mdstream_actor(stream, rx, opts)
+}
+
+/// Incremental UTF-8 decoding of arbitrarily split byte chunks.
+#[derive(Debug, Default)]
+struct Utf8Decoder {
+    /// Start of a multi-byte sequence cut off by the end of the last chunk.
+    partial: Vec<u8>,
+}
+
+impl Utf8Decoder {
+    fn push(&mut self, bytes: &[u8]) -> String {
+        self.partial.extend_from_slice(bytes);
+        let mut out = String::with_capacity(self.partial.len());
+        let mut rest = self.partial.as_slice();
+        loop {
+            match std::str::from_utf8(rest) {
+                Ok(valid) => {
+                    out.push_str(valid);
+                    rest = &[];
+                    break;
+                }
+                Err(e) => {
+                    let (valid, after) = rest.split_at(e.valid_up_to());
+                    out.push_str(std::str::from_utf8(valid).expect("validated prefix"));
+                    match e.error_len() {
+                        // Incomplete sequence at the end: wait for the next chunk.
+                        None => {
+                            rest = after;
+                            break;
+                        }
+                        Some(len) => {
+                            out.push(char::REPLACEMENT_CHARACTER);
+                            rest = &after[len..];
+                        }
+                    }
+                }
+            }
+        }
+        let keep = rest.len();
+        let drop_len = self.partial.len() - keep;
+        self.partial.drain(..drop_len);
+        out
+    }
+
+    /// Flush what is left at EOF; an unfinished sequence becomes `U+FFFD`.
+    fn finish(&mut self) -> String {
+        let out = String::from_utf8_lossy(&self.partial).into_owned();
+        self.partial.clear();
+        out
+    }
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
 
+    fn from_vars(vars: &[(&str, &str)]) -> CoalesceOptions {
+        CoalesceOptions::from_lookup(|name| {
+            vars.iter()
+                .find(|(k, _)| *k == name)
+                .map(|(_, v)| v.to_string())
+        })
+    }
+
+    #[test]
+    fn coalesce_options_from_vars_parses_overrides() {
+        let opts = from_vars(&[
+            ("MDSTREAM_COALESCE_MAX_DELAY_MS", "25"),
+            ("MDSTREAM_COALESCE_MAX_BYTES", " 2048 "),
+            ("MDSTREAM_COALESCE_FLUSH_ON_NEWLINE", "Off"),
+        ]);
+        assert_eq!(opts.max_delay, Duration::from_millis(25));
+        assert_eq!(opts.max_bytes, 2048);
+        assert!(!opts.flush_on_newline);
+        assert_eq!(opts.hard_max_bytes, None);
+    }
+
+    #[test]
+    fn coalesce_options_from_vars_falls_back_to_defaults() {
+        let defaults = CoalesceOptions::default();
+        for vars in [
+            &[][..],
+            &[
+                ("MDSTREAM_COALESCE_MAX_DELAY_MS", "soon"),
+                ("MDSTREAM_COALESCE_MAX_BYTES", "-1"),
+                ("MDSTREAM_COALESCE_FLUSH_ON_NEWLINE", "maybe"),
+            ][..],
+        ] {
+            let opts = from_vars(vars);
+            assert_eq!(opts.max_delay, defaults.max_delay);
+            assert_eq!(opts.max_bytes, defaults.max_bytes);
+            assert_eq!(opts.flush_on_newline, defaults.flush_on_newline);
+        }
+    }
+
     #[tokio::test]
     async fn coalesces_until_newline_by_default() {
         let (tx, rx) = mpsc::channel::<String>(8);
@@ -436,6 +1147,380 @@ mod tests {
         assert_eq!(stats.last_reason, Some(FlushReason::Newline));
     }
 
+    #[tokio::test]
+    async fn buffered_shows_text_before_the_flush() {
+        let (tx, rx) = mpsc::channel::<String>(8);
+        let opts = CoalesceOptions {
+            max_delay: Duration::from_millis(200),
+            flush_on_newline: false,
+            ..CoalesceOptions::default()
+        };
+        let mut cr = CoalescingReceiver::new(rx, opts);
+        assert_eq!(cr.buffered(), "");
+
+        tx.send("Hel".to_string()).await.unwrap();
+        tx.send("lo".to_string()).await.unwrap();
+        // A render tick cancels the wait before `max_delay` elapses.
+        let early = tokio::time::timeout(Duration::from_millis(30), cr.recv()).await;
+        assert!(early.is_err());
+        assert_eq!(cr.buffered(), "Hello");
+
+        tx.send(" world".to_string()).await.unwrap();
+        let chunk = cr.recv_with_meta().await.unwrap();
+        assert_eq!(chunk.text, "Hello world");
+        assert_eq!(chunk.reason, FlushReason::MaxDelay);
+        assert_eq!(cr.buffered(), "");
+    }
+
+    #[tokio::test]
+    async fn recv_with_lends_chunk_to_closure() {
+        let (tx, rx) = mpsc::channel::<String>(8);
+        let mut cr = CoalescingReceiver::new(rx, CoalesceOptions::default());
+        let mut stream = MdStream::new(mdstream::Options::default());
+
+        tx.send("# Ti".to_string()).await.unwrap();
+        tx.send("tle\n".to_string()).await.unwrap();
+        let update = cr
+            .recv_with(|chunk| {
+                assert_eq!(chunk.text, "# Title\n");
+                assert_eq!(chunk.reason, FlushReason::Newline);
+                assert_eq!(chunk.merged_messages, 2);
+                stream.append(chunk.text)
+            })
+            .await
+            .unwrap();
+        assert_eq!(update.committed.len(), 1);
+        assert_eq!(update.committed[0].raw, "# Title\n");
+        assert_eq!(cr.pending_bytes(), 0);
+
+        tx.send("tail".to_string()).await.unwrap();
+        drop(tx);
+        let len = cr.recv_with(|chunk| chunk.text.len()).await;
+        assert_eq!(len, Some(4));
+        assert_eq!(cr.recv_with(|chunk| chunk.text.len()).await, None);
+
+        let stats = cr.stats();
+        assert_eq!(stats.total_in_messages, 3);
+        assert_eq!(stats.total_out_chunks, 2);
+        assert_eq!(stats.last_reason, Some(FlushReason::ChannelClosed));
+        assert_eq!(stats.high_water, 8);
+    }
+
+    #[tokio::test]
+    async fn update_receiver_merges_quick_updates() {
+        let mut stream = MdStream::new(mdstream::Options::default());
+        let (tx, rx) = mpsc::channel::<Update>(8);
+        let mut ur = UpdateCoalescingReceiver::new(rx, UpdateCoalesceOptions::default());
+
+        tx.send(stream.append("A\n\n")).await.unwrap();
+        tx.send(stream.append("B\n\n")).await.unwrap();
+        tx.send(stream.append("C")).await.unwrap();
+
+        let got = ur.recv_with_meta().await.unwrap();
+        assert_eq!(got.merged_updates, 3);
+        assert_eq!(got.reason, FlushReason::MaxDelay);
+        let raws: Vec<_> = got
+            .update
+            .committed
+            .iter()
+            .map(|b| b.raw.as_str())
+            .collect();
+        assert_eq!(raws, vec!["A\n\n", "B\n\n"]);
+        assert_eq!(got.update.pending.unwrap().raw, "C");
+
+        let opts = UpdateCoalesceOptions {
+            max_updates: 2,
+            ..UpdateCoalesceOptions::default()
+        };
+        ur.set_options(opts);
+        tx.send(stream.append("D")).await.unwrap();
+        tx.send(stream.append("E")).await.unwrap();
+        tx.send(stream.finalize()).await.unwrap();
+        drop(tx);
+        let got = ur.recv_with_meta().await.unwrap();
+        assert_eq!(got.reason, FlushReason::MaxUpdates);
+        assert_eq!(got.update.pending.unwrap().raw, "CDE");
+        let got = ur.recv_with_meta().await.unwrap();
+        assert_eq!(got.reason, FlushReason::ChannelClosed);
+        assert_eq!(got.update.committed.len(), 1);
+        assert!(ur.recv().await.is_none());
+    }
+
+    #[tokio::test]
+    async fn on_flush_runs_once_per_chunk_after_stats() {
+        use std::sync::{Arc, Mutex};
+
+        let (tx, rx) = mpsc::channel::<String>(8);
+        let opts = CoalesceOptions {
+            max_delay: Duration::from_millis(20),
+            flush_on_newline: true,
+            ..CoalesceOptions::default()
+        };
+        let mut cr = CoalescingReceiver::new(rx, opts);
+        let seen: Arc<Mutex<Vec<(FlushReason, String)>>> = Arc::default();
+        let sink = Arc::clone(&seen);
+        cr.on_flush(move |chunk| {
+            sink.lock()
+                .unwrap()
+                .push((chunk.reason, chunk.text.to_string()));
+        });
+
+        tx.send("line\n".to_string()).await.unwrap();
+        assert_eq!(cr.recv().await.as_deref(), Some("line\n"));
+
+        tx.send("slow".to_string()).await.unwrap();
+        let got = cr.recv_with(|c| (c.reason, c.text.to_string())).await;
+        assert_eq!(got, Some((FlushReason::MaxDelay, "slow".to_string())));
+
+        tx.send("tail".to_string()).await.unwrap();
+        drop(tx);
+        assert_eq!(cr.recv().await.as_deref(), Some("tail"));
+        assert_eq!(cr.recv().await, None);
+
+        let seen = seen.lock().unwrap();
+        assert_eq!(
+            *seen,
+            vec![
+                (FlushReason::Newline, "line\n".to_string()),
+                (FlushReason::MaxDelay, "slow".to_string()),
+                (FlushReason::ChannelClosed, "tail".to_string()),
+            ]
+        );
+        assert_eq!(cr.stats().total_out_chunks, seen.len() as u64);
+    }
+
+    #[tokio::test]
+    async fn hard_max_bytes_splits_one_huge_message() {
+        let (tx, rx) = mpsc::channel::<String>(4);
+        let opts = CoalesceOptions {
+            hard_max_bytes: Some(16 * 1024),
+            ..CoalesceOptions::default()
+        };
+        let mut cr = CoalescingReceiver::new(rx, opts);
+
+        // Multibyte characters so a byte-exact cut would land inside one.
+        let mut text = String::new();
+        while text.len() < 100 * 1024 {
+            text.push_str("ab é 字 🦀\n");
+        }
+        tx.send(text.clone()).await.unwrap();
+        drop(tx);
+
+        let mut got = String::new();
+        let mut chunks = Vec::new();
+        while let Some(chunk) = cr.recv_with_meta().await {
+            assert!(chunk.text.len() <= 16 * 1024, "{} bytes", chunk.text.len());
+            got.push_str(&chunk.text);
+            chunks.push(chunk.reason);
+        }
+        assert_eq!(got, text);
+        assert!(chunks.len() >= 7, "{chunks:?}");
+        // The remainder below the cap flushes normally (here on its newlines).
+        let (_, split) = chunks.split_last().unwrap();
+        assert!(
+            split.iter().all(|r| *r == FlushReason::MaxBytes),
+            "{chunks:?}"
+        );
+        assert_eq!(cr.stats().high_water, text.len());
+        assert_eq!(cr.pending_bytes(), 0);
+    }
+
+    #[tokio::test]
+    async fn hard_max_bytes_applies_to_recv_with() {
+        let (tx, rx) = mpsc::channel::<String>(4);
+        let opts = CoalesceOptions {
+            hard_max_bytes: Some(2),
+            ..CoalesceOptions::default()
+        };
+        let mut cr = CoalescingReceiver::new(rx, opts);
+        tx.send("aé字".to_string()).await.unwrap();
+        drop(tx);
+
+        let mut parts = Vec::new();
+        while let Some(part) = cr.recv_with(|c| c.text.to_string()).await {
+            parts.push(part);
+        }
+        // A character wider than the cap is still returned whole.
+        assert_eq!(parts, vec!["a", "é", "字"]);
+    }
+
+    #[tokio::test]
+    async fn max_messages_flushes_a_slow_trickle() {
+        let (tx, rx) = mpsc::channel::<String>(8);
+        let opts = CoalesceOptions {
+            max_delay: Duration::from_secs(60),
+            max_messages: Some(3),
+            ..CoalesceOptions::default()
+        };
+        let mut cr = CoalescingReceiver::new(rx, opts);
+
+        let producer = tokio::spawn(async move {
+            for c in ["a", "b", "c", "d", "e"] {
+                tx.send(c.to_string()).await.unwrap();
+                tokio::time::sleep(Duration::from_millis(5)).await;
+            }
+        });
+
+        let got = tokio::time::timeout(Duration::from_secs(1), cr.recv_with_meta())
+            .await
+            .expect("max_messages should flush well before max_delay")
+            .unwrap();
+        assert_eq!(got.text, "abc");
+        assert_eq!(got.reason, FlushReason::MaxMessages);
+        assert_eq!(got.merged_messages, 3);
+
+        producer.await.unwrap();
+        let got = cr.recv_with_meta().await.unwrap();
+        assert_eq!(got.text, "de");
+        assert_eq!(got.reason, FlushReason::ChannelClosed);
+    }
+
+    #[tokio::test(start_paused = true)]
+    async fn debounce_waits_for_a_quiet_period_unlike_throttle() {
+        async fn first_chunk(debounce: bool) -> CoalescedChunk {
+            let (tx, rx) = mpsc::channel::<String>(16);
+            let opts = CoalesceOptions {
+                max_delay: Duration::from_millis(60),
+                debounce,
+                flush_on_newline: false,
+                ..CoalesceOptions::default()
+            };
+            let mut cr = CoalescingReceiver::new(rx, opts);
+            let producer = tokio::spawn(async move {
+                // Deltas every 20ms for ~140ms, then silence while the channel stays open.
+                for c in ["a", "b", "c", "d", "e", "f", "g", "h"] {
+                    tx.send(c.to_string()).await.unwrap();
+                    tokio::time::sleep(Duration::from_millis(20)).await;
+                }
+                tokio::time::sleep(Duration::from_millis(300)).await;
+            });
+            let chunk = cr.recv_with_meta().await.unwrap();
+            producer.abort();
+            chunk
+        }
+
+        let throttled = first_chunk(false).await;
+        assert_eq!(throttled.reason, FlushReason::MaxDelay);
+        assert!(throttled.text.len() < 8, "{throttled:?}");
+
+        let debounced = first_chunk(true).await;
+        assert_eq!(debounced.reason, FlushReason::MaxDelay);
+        assert_eq!(debounced.text, "abcdefgh");
+    }
+
+    #[tokio::test]
+    async fn debounce_still_flushes_at_max_bytes() {
+        let (tx, rx) = mpsc::channel::<String>(16);
+        let opts = CoalesceOptions {
+            max_delay: Duration::from_secs(60),
+            debounce: true,
+            max_bytes: 4,
+            flush_on_newline: false,
+            ..CoalesceOptions::default()
+        };
+        let mut cr = CoalescingReceiver::new(rx, opts);
+        for c in ["ab", "cd", "ef"] {
+            tx.send(c.to_string()).await.unwrap();
+        }
+        let chunk = tokio::time::timeout(Duration::from_secs(1), cr.recv_with_meta())
+            .await
+            .expect("max_bytes should flush without a quiet period")
+            .unwrap();
+        assert_eq!(chunk.text, "abcd");
+        assert_eq!(chunk.reason, FlushReason::MaxBytes);
+    }
+
+    #[tokio::test]
+    async fn high_water_tracks_largest_chunk() {
+        let (tx, rx) = mpsc::channel::<String>(8);
+        let mut cr = CoalescingReceiver::new(rx, CoalesceOptions::default());
+        assert_eq!(cr.pending_bytes(), 0);
+
+        tx.send("ab\n".to_string()).await.unwrap();
+        tx.send("cdefgh".to_string()).await.unwrap();
+        tx.send("ij\n".to_string()).await.unwrap();
+        tx.send("k\n".to_string()).await.unwrap();
+
+        assert_eq!(cr.recv().await.as_deref(), Some("ab\n"));
+        assert_eq!(cr.stats().high_water, 3);
+        assert_eq!(cr.recv().await.as_deref(), Some("cdefghij\n"));
+        assert_eq!(cr.recv().await.as_deref(), Some("k\n"));
+        assert_eq!(cr.stats().high_water, 9);
+        assert_eq!(cr.pending_bytes(), 0);
+    }
+
+    #[test]
+    fn stats_rates_guard_zero_chunks() {
+        let mut stats = CoalesceStats::default();
+        assert_eq!(stats.compression_ratio(), 0.0);
+        assert_eq!(stats.avg_chunk_bytes(), 0.0);
+
+        stats.total_in_messages = 9;
+        stats.total_out_chunks = 2;
+        stats.total_out_bytes = 15;
+        assert_eq!(stats.compression_ratio(), 4.5);
+        assert_eq!(stats.avg_chunk_bytes(), 7.5);
+    }
+
+    #[tokio::test]
+    async fn reset_stats_starts_a_fresh_window() {
+        let (tx, rx) = mpsc::channel::<String>(8);
+        let mut cr = CoalescingReceiver::new(rx, CoalesceOptions::default());
+
+        tx.send("a".to_string()).await.unwrap();
+        tx.send("b\n".to_string()).await.unwrap();
+        cr.recv().await.unwrap();
+        assert_eq!(cr.stats().compression_ratio(), 2.0);
+
+        cr.reset_stats();
+        assert_eq!(cr.stats(), CoalesceStats::default());
+
+        tx.send("c\n".to_string()).await.unwrap();
+        cr.recv().await.unwrap();
+        assert_eq!(cr.stats().total_in_messages, 1);
+        assert_eq!(cr.stats().avg_chunk_bytes(), 2.0);
+    }
+
+    #[tokio::test]
+    async fn idle_timeout_reports_stalled_sender_once() {
+        let (tx, rx) = mpsc::channel::<String>(8);
+        let opts = CoalesceOptions {
+            idle_timeout: Some(Duration::from_millis(20)),
+            ..CoalesceOptions::default()
+        };
+        let mut cr = CoalescingReceiver::new(rx, opts);
+
+        // Buffered content still flushes via `max_delay`, not `Idle`.
+        tx.send("partial".to_string()).await.unwrap();
+        let got = cr.recv_with_meta().await.unwrap();
+        assert_eq!(got.text, "partial");
+        assert_eq!(got.reason, FlushReason::MaxDelay);
+
+        // Sender stalls (but stays alive): one idle sentinel.
+        let got = cr.recv_with_meta().await.unwrap();
+        assert_eq!(got.text, "");
+        assert_eq!(got.reason, FlushReason::Idle);
+        assert_eq!(got.merged_messages, 0);
+
+        // The idle period was already reported; the next chunk is real content.
+        let producer = tx.clone();
+        tokio::spawn(async move {
+            tokio::time::sleep(Duration::from_millis(60)).await;
+            producer.send("next\n".to_string()).await.unwrap();
+        });
+        let got = tokio::time::timeout(Duration::from_millis(500), cr.recv_with_meta())
+            .await
+            .expect("receiver should wait for content")
+            .unwrap();
+        assert_eq!(got.text, "next\n");
+        assert_eq!(got.reason, FlushReason::Newline);
+
+        // New activity re-arms the idle signal.
+        let got = cr.recv_with_meta().await.unwrap();
+        assert_eq!(got.reason, FlushReason::Idle);
+        assert_eq!(cr.stats().total_out_chunks, 2);
+    }
+
     #[tokio::test]
     async fn delta_sender_drop_new_drops_when_full() {
         let (tx, mut rx) = mpsc::channel::<String>(1);
@@ -453,6 +1538,36 @@ mod tests {
         assert_eq!(got, None);
     }
 
+    #[tokio::test]
+    async fn delta_sender_block_measures_time_spent_blocked() {
+        let (tx, mut rx) = mpsc::channel::<String>(1);
+        let mut s = DeltaSender::new(tx, BackpressurePolicy::Block);
+
+        // Uncontended sends take the fast path and are not timed.
+        assert_eq!(s.send("a").await.unwrap(), SendOutcome::Sent);
+        assert_eq!(s.stats().blocked_nanos, 0);
+
+        // A stalled receiver: the next send has to wait until it drains.
+        let drain = tokio::spawn(async move {
+            tokio::time::sleep(Duration::from_millis(20)).await;
+            let mut got = Vec::new();
+            while let Some(msg) = rx.recv().await {
+                got.push(msg);
+            }
+            got
+        });
+        assert_eq!(s.send("b").await.unwrap(), SendOutcome::Sent);
+        let stats = s.stats();
+        assert!(stats.blocked_nanos >= 10_000_000, "{stats:?}");
+        assert_eq!(stats.sent, 2);
+        assert_eq!(stats.dropped, 0);
+
+        s.reset_stats();
+        assert_eq!(s.stats(), SendStats::default());
+        s.finish().await.unwrap();
+        assert_eq!(drain.await.unwrap(), vec!["a", "b"]);
+    }
+
     #[tokio::test]
     async fn delta_sender_coalesce_local_flushes_eventually() {
         let (tx, mut rx) = mpsc::channel::<String>(1);
@@ -470,4 +1585,296 @@ mod tests {
         assert_eq!(s.flush().await.unwrap(), SendOutcome::Sent);
         assert_eq!(rx.recv().await.as_deref(), Some("abcd"));
     }
+
+    #[tokio::test]
+    async fn delta_sender_coalesce_local_escalates_to_blocking() {
+        let (tx, mut rx) = mpsc::channel::<String>(1);
+        let mut s = DeltaSender::new(tx, BackpressurePolicy::CoalesceLocal);
+        s.set_local_max_bytes(8);
+        s.set_local_escalation_factor(2);
+
+        // Fill the channel and stall the consumer for a while before it resumes draining.
+        s.tx.try_send("x".to_string()).unwrap();
+        let drain = tokio::spawn(async move {
+            tokio::time::sleep(Duration::from_millis(20)).await;
+            let mut got = String::new();
+            while let Some(msg) = rx.recv().await {
+                got.push_str(&msg);
+            }
+            got
+        });
+
+        let mut expected = String::from("x");
+        for i in 0..200 {
+            let delta = format!("{:03}", i % 1000);
+            expected.push_str(&delta);
+            s.send(&delta).await.unwrap();
+            assert!(
+                s.local_buf.len() < 16,
+                "local buffer grew to {}",
+                s.local_buf.len()
+            );
+        }
+        let stats = s.stats();
+        assert!(stats.escalated > 0, "{stats:?}");
+        assert!(stats.blocked_nanos > 0, "{stats:?}");
+
+        s.finish().await.unwrap();
+        assert_eq!(drain.await.unwrap(), expected);
+    }
+
+    #[tokio::test]
+    async fn delta_sender_try_send_reports_would_block() {
+        let (tx, mut rx) = mpsc::channel::<String>(1);
+        let mut s = DeltaSender::new(tx, BackpressurePolicy::Block);
+        assert_eq!(s.try_send("a"), Ok(SendOutcome::Sent));
+        assert_eq!(s.try_send("b"), Err(SendError::WouldBlock));
+        assert_eq!(rx.recv().await.as_deref(), Some("a"));
+        assert_eq!(s.try_send("b"), Ok(SendOutcome::Sent));
+        assert_eq!(s.stats().sent, 2);
+
+        // `CoalesceLocal` buffers until the escalation threshold, then refuses the delta.
+        s.set_policy(BackpressurePolicy::CoalesceLocal);
+        s.set_local_max_bytes(4);
+        s.set_local_escalation_factor(2);
+        assert_eq!(s.try_send("cd"), Ok(SendOutcome::Buffered));
+        assert_eq!(s.try_send("efg"), Ok(SendOutcome::Buffered));
+        assert_eq!(s.try_send("hij"), Err(SendError::WouldBlock));
+        assert_eq!(s.local_buf, "cdefg");
+
+        assert_eq!(rx.recv().await.as_deref(), Some("b"));
+        assert_eq!(s.try_send("hij"), Ok(SendOutcome::Sent));
+        assert_eq!(rx.recv().await.as_deref(), Some("cdefghij"));
+    }
+
+    #[tokio::test]
+    async fn delta_sender_buffer_full_without_escalation() {
+        let (tx, mut rx) = mpsc::channel::<String>(1);
+        let mut s = DeltaSender::new(tx, BackpressurePolicy::CoalesceLocal);
+        s.set_local_max_bytes(4);
+        s.set_local_escalation_factor(2);
+        s.set_local_escalation(false);
+        s.tx.try_send("x".to_string()).unwrap();
+
+        assert_eq!(s.send("abcd").await, Ok(SendOutcome::Buffered));
+        let err = s.send("efgh").await.unwrap_err();
+        assert_eq!(err, SendError::BufferFull);
+        assert_eq!(err.to_string(), "local buffer full");
+        assert_eq!(s.stats().escalated, 0);
+
+        assert_eq!(rx.recv().await.as_deref(), Some("x"));
+        assert_eq!(s.send("efgh").await, Ok(SendOutcome::Sent));
+        assert_eq!(rx.recv().await.as_deref(), Some("abcdefgh"));
+    }
+
+    #[tokio::test]
+    async fn delta_sender_closed_is_a_std_error() {
+        let (tx, rx) = mpsc::channel::<String>(1);
+        drop(rx);
+        let mut s = DeltaSender::new(tx, BackpressurePolicy::Block);
+        let err: Box<dyn std::error::Error> = Box::new(s.send("a").await.unwrap_err());
+        assert_eq!(err.to_string(), "channel closed");
+        assert_eq!(s.try_send("a"), Err(SendError::Closed));
+    }
+
+    /// Yields one preset chunk per read.
+    struct ChunkedReader(std::collections::VecDeque<Vec<u8>>);
+
+    impl AsyncRead for ChunkedReader {
+        fn poll_read(
+            mut self: std::pin::Pin<&mut Self>,
+            _cx: &mut std::task::Context<'_>,
+            buf: &mut tokio::io::ReadBuf<'_>,
+        ) -> std::task::Poll<std::io::Result<()>> {
+            if let Some(chunk) = self.0.pop_front() {
+                buf.put_slice(&chunk);
+            }
+            std::task::Poll::Ready(Ok(()))
+        }
+    }
+
+    #[test]
+    fn utf8_decoder_holds_back_split_sequences() {
+        let mut d = Utf8Decoder::default();
+        let bytes = "aé😀".as_bytes();
+        assert_eq!(d.push(&bytes[..2]), "a");
+        assert_eq!(d.push(&bytes[2..4]), "é");
+        assert_eq!(d.push(&bytes[4..6]), "");
+        assert_eq!(d.push(&bytes[6..]), "😀");
+        assert_eq!(d.push(b"x\xffy"), "x\u{FFFD}y");
+        assert_eq!(d.push(&[0xe2, 0x82]), "");
+        assert_eq!(d.finish(), "\u{FFFD}");
+        assert_eq!(d.finish(), "");
+    }
+
+    #[tokio::test]
+    async fn reader_helper_reassembles_multibyte_chars_across_reads() {
+        let text = "# Café\n\nnaïve 😀 text\n";
+        let bytes = text.as_bytes();
+        let split = text.find('é').unwrap() + 1;
+        let emoji = text.find('😀').unwrap() + 2;
+        let reader = ChunkedReader(
+            [&bytes[..split], &bytes[split..emoji], &bytes[emoji..]]
+                .into_iter()
+                .map(<[u8]>::to_vec)
+                .collect(),
+        );
+
+        let mut rx = spawn_mdstream_from_reader(
+            reader,
+            MdStream::new(mdstream::Options::default()),
+            CoalesceOptions::default(),
+        );
+        let mut state = mdstream::DocumentState::new();
+        while let Some(update) = rx.recv().await {
+            state.apply(update);
+        }
+        let raws: Vec<&str> = state.blocks().map(|b| b.raw.as_str()).collect();
+        assert_eq!(raws, vec!["# Café\n", "naïve 😀 text\n"]);
+        assert!(state.pending().is_none());
+    }
+
+    #[tokio::test]
+    async fn actor_events_end_with_finalized_after_producer_drops() {
+        let (tx, rx) = mpsc::channel::<String>(8);
+        let mut events = spawn_mdstream_actor_events(
+            MdStream::new(mdstream::Options::default()),
+            rx,
+            CoalesceOptions::default(),
+        );
+        tx.send("# Title\n".to_string()).await.unwrap();
+        tx.send("Body".to_string()).await.unwrap();
+        drop(tx);
+
+        let mut received = Vec::new();
+        while let Some(event) = events.recv().await {
+            received.push(event);
+        }
+        let (last, rest) = received.split_last().unwrap();
+        assert!(last.is_finalized());
+        assert!(rest.iter().all(|e| !e.is_finalized()));
+        assert_eq!(last.update().committed[0].raw, "Body");
+
+        let mut state = mdstream::DocumentState::new();
+        for event in received {
+            state.apply(event.into_update());
+        }
+        let raws: Vec<&str> = state.blocks().map(|b| b.raw.as_str()).collect();
+        assert_eq!(raws, vec!["# Title\n", "Body"]);
+    }
+
+    #[tokio::test(start_paused = true)]
+    async fn actor_heartbeats_during_producer_silence() {
+        let (tx, rx) = mpsc::channel::<String>(8);
+        let opts = CoalesceOptions {
+            max_delay: Duration::from_millis(5),
+            heartbeat: Some(Duration::from_millis(20)),
+            ..CoalesceOptions::default()
+        };
+        let mut updates =
+            spawn_mdstream_actor(MdStream::new(mdstream::Options::default()), rx, opts);
+        tx.send("# Title\nBody".to_string()).await.unwrap();
+
+        let first = updates.recv().await.unwrap();
+        assert_eq!(first.committed.len(), 1);
+        assert_eq!(first.pending.as_ref().unwrap().raw, "Body");
+
+        // Nothing is sent for a while: heartbeats repeat the pending block.
+        for _ in 0..3 {
+            let beat = updates.recv().await.unwrap();
+            assert!(beat.committed.is_empty());
+            assert_eq!(beat.pending, first.pending);
+        }
+
+        tx.send(" more".to_string()).await.unwrap();
+        drop(tx);
+        let mut rest = Vec::new();
+        while let Some(u) = updates.recv().await {
+            rest.push(u);
+        }
+        let last = rest.last().unwrap();
+        assert_eq!(last.committed[0].raw, "Body more");
+    }
+
+    #[tokio::test(start_paused = true)]
+    async fn cancelled_recv_keeps_merge_count() {
+        let (tx, rx) = mpsc::channel::<String>(8);
+        let opts = CoalesceOptions {
+            max_delay: Duration::from_secs(1),
+            max_messages: Some(3),
+            ..CoalesceOptions::default()
+        };
+        let mut cr = CoalescingReceiver::new(rx, opts);
+        let producer = tokio::spawn(async move {
+            for c in ["a", "b", "c", "d"] {
+                tx.send(c.to_string()).await.unwrap();
+                tokio::time::sleep(Duration::from_millis(30)).await;
+            }
+        });
+
+        // Cancel `recv` every 10ms, as a render tick in `select!` would.
+        let got = loop {
+            match tokio::time::timeout(Duration::from_millis(10), cr.recv_with_meta()).await {
+                Ok(got) => break got.unwrap(),
+                Err(_) => continue,
+            }
+        };
+        assert_eq!(got.text, "abc");
+        assert_eq!(got.reason, FlushReason::MaxMessages);
+        assert_eq!(got.merged_messages, 3);
+        assert_eq!(cr.stats().last_merged_messages, 3);
+        producer.await.unwrap();
+    }
+
+    #[tokio::test(start_paused = true)]
+    async fn actor_heartbeat_does_not_break_max_messages() {
+        let (tx, rx) = mpsc::channel::<String>(8);
+        let opts = CoalesceOptions {
+            max_delay: Duration::from_secs(1),
+            max_messages: Some(3),
+            heartbeat: Some(Duration::from_millis(10)),
+            ..CoalesceOptions::default()
+        };
+        let mut updates =
+            spawn_mdstream_actor(MdStream::new(mdstream::Options::default()), rx, opts);
+        tokio::spawn(async move {
+            for c in ["a", "b", "c", "d"] {
+                tx.send(c.to_string()).await.unwrap();
+                tokio::time::sleep(Duration::from_millis(30)).await;
+            }
+            tokio::time::sleep(Duration::from_secs(5)).await;
+        });
+
+        // Heartbeats may come first, but the first real update is the `max_messages` flush.
+        let first = loop {
+            let u = updates.recv().await.unwrap();
+            if u.pending.is_some() {
+                break u;
+            }
+        };
+        assert_eq!(first.pending.unwrap().raw, "abc");
+    }
+
+    #[tokio::test]
+    async fn delta_sender_finish_flushes_local_buffer() {
+        let (tx, mut rx) = mpsc::channel::<String>(4);
+        let mut s = DeltaSender::new(tx, BackpressurePolicy::CoalesceLocal);
+
+        // No newline and below `local_max_bytes`: stays buffered locally.
+        assert_eq!(s.send("tail").await.unwrap(), SendOutcome::Buffered);
+        s.finish().await.unwrap();
+
+        assert_eq!(rx.recv().await.as_deref(), Some("tail"));
+        assert_eq!(rx.recv().await, None);
+    }
+
+    #[cfg(debug_assertions)]
+    #[tokio::test]
+    #[should_panic(expected = "unflushed bytes")]
+    async fn delta_sender_drop_with_buffered_data_asserts_in_debug() {
+        let (tx, _rx) = mpsc::channel::<String>(4);
+        let mut s = DeltaSender::new(tx, BackpressurePolicy::CoalesceLocal);
+        s.send("tail").await.unwrap();
+        drop(s);
+    }
 }
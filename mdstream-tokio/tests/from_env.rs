@@ -0,0 +1,22 @@
+//! `CoalesceOptions::from_env` against the real process environment.
+//!
+//! Kept in its own test binary with a single test: `set_var` is only sound while no other thread
+//! reads or writes the environment, which the harness cannot promise with several tests running
+//! in parallel. Parsing and fallbacks are covered by the unit tests in `src/lib.rs`.
+
+use std::time::Duration;
+
+use mdstream_tokio::CoalesceOptions;
+
+#[test]
+fn coalesce_options_from_env_reads_process_environment() {
+    // SAFETY: this is the only test in this binary, so no other thread touches the environment
+    // while it runs.
+    unsafe {
+        std::env::set_var("MDSTREAM_COALESCE_MAX_DELAY_MS", "5");
+        std::env::set_var("MDSTREAM_COALESCE_MAX_BYTES", "oops");
+    }
+    let opts = CoalesceOptions::from_env();
+    assert_eq!(opts.max_delay, Duration::from_millis(5));
+    assert_eq!(opts.max_bytes, CoalesceOptions::default().max_bytes);
+}
@@ -27,6 +27,7 @@ fn main() {
     let mut adapter = PulldownAdapter::new(PulldownAdapterOptions {
         pulldown: PulldownOptions::ENABLE_TABLES | PulldownOptions::ENABLE_STRIKETHROUGH,
         prefer_display_for_pending: true,
+        max_reference_definitions: None,
     });
 
     let chunks = [
@@ -21,6 +21,9 @@ pub struct PulldownAdapterOptions {
     pub pulldown: PulldownOptions,
     /// If true, pending blocks are parsed from `display` (terminated) when available.
     pub prefer_display_for_pending: bool,
+    /// Optional cap on tracked reference definitions; the oldest (by defining block) are
+    /// evicted first.
+    pub max_reference_definitions: Option<usize>,
 }
 
 impl Default for PulldownAdapterOptions {
@@ -28,6 +31,7 @@ impl Default for PulldownAdapterOptions {
         Self {
             pulldown: PulldownOptions::empty(),
             prefer_display_for_pending: true,
+            max_reference_definitions: None,
         }
     }
 }
@@ -37,7 +41,10 @@ pub struct PulldownAdapter {
     opts: PulldownAdapterOptions,
     committed_raw: HashMap<BlockId, String>,
     committed_cache: HashMap<BlockId, Vec<Event<'static>>>,
-    reference_definitions: BTreeMap<String, String>,
+    /// Label -> (defining block, definition line). The latest definition of a label wins.
+    reference_definitions: BTreeMap<String, (BlockId, String)>,
+    /// Definitions each committed block contributed, to restore or drop them in `forget_block`.
+    block_definitions: HashMap<BlockId, Vec<(String, String)>>,
     reference_definitions_text: String,
     reference_definitions_dirty: bool,
     parse_scratch: ParseScratch,
@@ -50,6 +57,7 @@ impl PulldownAdapter {
             committed_raw: HashMap::new(),
             committed_cache: HashMap::new(),
             reference_definitions: BTreeMap::new(),
+            block_definitions: HashMap::new(),
             reference_definitions_text: String::new(),
             reference_definitions_dirty: false,
             parse_scratch: Default::default(),
@@ -60,6 +68,7 @@ impl PulldownAdapter {
         self.committed_raw.clear();
         self.committed_cache.clear();
         self.reference_definitions.clear();
+        self.block_definitions.clear();
         self.reference_definitions_text.clear();
         self.reference_definitions_dirty = false;
         #[cfg(not(feature = "sync"))]
@@ -80,7 +89,7 @@ impl PulldownAdapter {
         }
         for block in &update.committed {
             self.committed_raw.insert(block.id, block.raw.clone());
-            self.collect_reference_definitions(&block.raw);
+            self.collect_reference_definitions(block.id, &block.raw);
             self.refresh_reference_definitions_text();
             let events = self.parse_with_definitions(&block.raw);
             self.committed_cache.insert(block.id, events);
@@ -96,6 +105,51 @@ impl PulldownAdapter {
         }
     }
 
+    /// Drop a committed block and the reference definitions it contributed.
+    ///
+    /// A label the block had redefined falls back to the latest remaining definition. Other
+    /// committed blocks keep their cached events. Returns `false` if the block is unknown.
+    pub fn forget_block(&mut self, id: BlockId) -> bool {
+        let known = self.committed_raw.remove(&id).is_some();
+        self.committed_cache.remove(&id);
+        let Some(defs) = self.block_definitions.remove(&id) else {
+            return known;
+        };
+        for (label, _) in defs {
+            if self
+                .reference_definitions
+                .get(&label)
+                .is_none_or(|(source, _)| *source != id)
+            {
+                continue;
+            }
+            let fallback = self
+                .block_definitions
+                .iter()
+                .filter_map(|(block, defs)| {
+                    let (_, line) = defs.iter().find(|(l, _)| *l == label)?;
+                    Some((*block, line.clone()))
+                })
+                .max_by_key(|(block, _)| *block);
+            match fallback {
+                Some(def) => {
+                    self.reference_definitions.insert(label, def);
+                }
+                None => {
+                    self.reference_definitions.remove(&label);
+                }
+            }
+            self.reference_definitions_dirty = true;
+        }
+        self.refresh_reference_definitions_text();
+        known
+    }
+
+    /// Number of reference definitions currently applied to parses.
+    pub fn reference_definition_count(&self) -> usize {
+        self.reference_definitions.len()
+    }
+
     pub fn committed_events(&self, id: BlockId) -> Option<&[Event<'static>]> {
         self.committed_cache.get(&id).map(|v| v.as_slice())
     }
@@ -139,24 +193,53 @@ impl PulldownAdapter {
         }
     }
 
-    fn collect_reference_definitions(&mut self, raw: &str) {
+    fn collect_reference_definitions(&mut self, id: BlockId, raw: &str) {
         // Best-effort: extract single-line reference definitions and keep the latest per label.
+        let mut contributed = Vec::new();
         for line in raw.split('\n') {
             if let Some((label, def_line)) = reference::extract_reference_definition_line(line) {
+                contributed.push((label.clone(), def_line.clone()));
                 match self.reference_definitions.entry(label) {
                     Entry::Vacant(v) => {
-                        v.insert(def_line);
+                        v.insert((id, def_line));
                         self.reference_definitions_dirty = true;
                     }
                     Entry::Occupied(mut o) => {
-                        if o.get() != &def_line {
-                            o.insert(def_line);
+                        if o.get().1 != def_line {
                             self.reference_definitions_dirty = true;
                         }
+                        o.insert((id, def_line));
                     }
                 }
             }
         }
+        if contributed.is_empty() {
+            return;
+        }
+        self.block_definitions.insert(id, contributed);
+        self.evict_reference_definitions();
+    }
+
+    fn evict_reference_definitions(&mut self) {
+        let Some(max) = self.opts.max_reference_definitions else {
+            return;
+        };
+        while self.reference_definitions.len() > max {
+            let Some(oldest) = self
+                .reference_definitions
+                .iter()
+                .min_by_key(|(_, (source, _))| *source)
+                .map(|(label, _)| label.clone())
+            else {
+                break;
+            };
+            self.reference_definitions.remove(&oldest);
+            // Evicted labels must not come back through `forget_block` fallbacks.
+            for defs in self.block_definitions.values_mut() {
+                defs.retain(|(label, _)| *label != oldest);
+            }
+            self.reference_definitions_dirty = true;
+        }
     }
 
     fn refresh_reference_definitions_text(&mut self) {
@@ -166,7 +249,7 @@ impl PulldownAdapter {
         self.reference_definitions_text = self
             .reference_definitions
             .values()
-            .cloned()
+            .map(|(_, line)| line.clone())
             .collect::<Vec<_>>()
             .join("\n");
         self.reference_definitions_dirty = false;
@@ -1,6 +1,18 @@
-use std::collections::HashMap;
-
+#[cfg(not(feature = "std"))]
+use alloc::collections::BTreeMap as Map;
+use alloc::format;
+use alloc::string::{String, ToString};
+use alloc::vec::Vec;
+use core::ops::Range;
+#[cfg(feature = "std")]
+use std::collections::HashMap as Map;
+
+use crate::boundary::FenceBoundaryPlugin;
+use crate::inline::{closing_backticks, find_matching_close_bracket};
+use crate::stream::footnotes::{is_footnote_continuation, is_footnote_definition_start};
+use crate::stream::html::{HtmlTag, parse_tag_at};
 use crate::syntax::{is_code_fence_closing_line, parse_code_fence_header_from_block};
+use crate::table::{is_table_delimiter_row, table_cell_ranges};
 use crate::types::BlockStatus;
 use crate::types::{Block, BlockId, BlockKind, Update};
 use crate::{MdStream, Options};
@@ -73,7 +85,7 @@ where
 {
     inner: MdStream,
     analyzer: A,
-    committed_meta: HashMap<BlockId, A::Meta>,
+    committed_meta: Map<BlockId, A::Meta>,
 }
 
 impl<A> AnalyzedStream<A>
@@ -84,7 +96,25 @@ where
         Self {
             inner: MdStream::new(opts),
             analyzer,
-            committed_meta: HashMap::new(),
+            committed_meta: Map::new(),
+        }
+    }
+
+    /// Like `new`, but pre-reserves metadata for `expected_blocks` committed blocks.
+    ///
+    /// Without the `std` feature the metadata map is a `BTreeMap` and the hint is ignored.
+    pub fn with_capacity(opts: Options, analyzer: A, expected_blocks: usize) -> Self {
+        #[cfg(feature = "std")]
+        let committed_meta = Map::with_capacity(expected_blocks);
+        #[cfg(not(feature = "std"))]
+        let committed_meta = {
+            let _ = expected_blocks;
+            Map::new()
+        };
+        Self {
+            inner: MdStream::new(opts),
+            analyzer,
+            committed_meta,
         }
     }
 
@@ -162,7 +192,10 @@ pub enum CodeFenceClass {
 #[derive(Debug, Clone, PartialEq, Eq)]
 pub struct CodeFenceMeta {
     pub info: String,
+    /// Language as written in the fence header (`Rust` stays `Rust`).
     pub language: Option<String>,
+    /// `language` with ASCII letters lowercased, for case-insensitive lookups.
+    pub language_lower: Option<String>,
     pub class: CodeFenceClass,
 }
 
@@ -194,6 +227,7 @@ impl BlockAnalyzer for CodeFenceAnalyzer {
         Some(CodeFenceMeta {
             info: header.info.to_string(),
             language: header.language.map(|s| s.to_string()),
+            language_lower: header.language.map(|s| s.to_ascii_lowercase()),
             class: Self::classify_language(header.language),
         })
     }
@@ -207,7 +241,7 @@ pub struct MathMeta {
 #[derive(Debug, Default, Clone)]
 pub struct MathAnalyzer;
 
-fn count_double_dollars_unescaped(text: &str) -> usize {
+pub(crate) fn count_double_dollars_unescaped(text: &str) -> usize {
     let bytes = text.as_bytes();
     let mut count = 0usize;
     let mut i = 0usize;
@@ -266,7 +300,7 @@ fn last_nonempty_line(text: &str) -> Option<&str> {
     text.split('\n').rev().find(|line| !line.trim().is_empty())
 }
 
-fn code_fence_is_closed(text: &str) -> bool {
+pub(crate) fn code_fence_is_closed(text: &str) -> bool {
     let Some(header) = parse_code_fence_header_from_block(text) else {
         return false;
     };
@@ -293,10 +327,8 @@ impl BlockAnalyzer for BlockHintAnalyzer {
         }
 
         match block.kind {
-            BlockKind::CodeFence => {
-                if !code_fence_is_closed(&block.raw) {
-                    flags |= BlockHintMeta::UNCLOSED_CODE_FENCE;
-                }
+            BlockKind::CodeFence if !code_fence_is_closed(&block.raw) => {
+                flags |= BlockHintMeta::UNCLOSED_CODE_FENCE;
             }
             BlockKind::MathBlock => {
                 let count = count_double_dollars_unescaped(&block.raw);
@@ -311,6 +343,399 @@ impl BlockAnalyzer for BlockHintAnalyzer {
     }
 }
 
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct HtmlBlockMeta {
+    /// The block starts with an HTML comment (`<!-- ... -->`).
+    pub is_comment: bool,
+    /// Lowercased name of the outermost opening tag (`None` for comments).
+    pub root_tag: Option<String>,
+}
+
+/// Distinguishes HTML comments from element blocks without changing `BlockKind::HtmlBlock`.
+#[derive(Debug, Default, Clone)]
+pub struct HtmlBlockAnalyzer;
+
+impl BlockAnalyzer for HtmlBlockAnalyzer {
+    type Meta = HtmlBlockMeta;
+
+    fn analyze_block(&mut self, block: &Block) -> Option<Self::Meta> {
+        if block.kind != BlockKind::HtmlBlock {
+            return None;
+        }
+        let s = block.raw.trim_start();
+        let (tag, _) = parse_tag_at(s, 0)?;
+        Some(match tag {
+            HtmlTag::CommentOpen => HtmlBlockMeta {
+                is_comment: true,
+                root_tag: None,
+            },
+            HtmlTag::Opening { name, .. } => HtmlBlockMeta {
+                is_comment: false,
+                root_tag: Some(name),
+            },
+            HtmlTag::Closing { .. } => HtmlBlockMeta {
+                is_comment: false,
+                root_tag: None,
+            },
+        })
+    }
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct HardBreakMeta {
+    /// Byte offsets (into `block.raw`) of the `\n` that ends each hard-break line.
+    pub break_offsets: Vec<usize>,
+}
+
+/// Finds GFM hard line breaks in paragraph and heading blocks.
+///
+/// A line ending in two or more spaces, or in an unescaped `\`, is a hard break when another line
+/// of the block follows it. A single trailing space is not a hard break (the pending terminator
+/// also trims it while streaming).
+#[derive(Debug, Default, Clone)]
+pub struct HardBreakAnalyzer;
+
+fn ends_with_hard_break(line: &str) -> bool {
+    let spaces = line.len() - line.trim_end_matches(' ').len();
+    if spaces >= 2 {
+        return true;
+    }
+    // An odd run of trailing backslashes leaves the last one unescaped.
+    let backslashes = line.len() - line.trim_end_matches('\\').len();
+    backslashes % 2 == 1
+}
+
+impl BlockAnalyzer for HardBreakAnalyzer {
+    type Meta = HardBreakMeta;
+
+    fn analyze_block(&mut self, block: &Block) -> Option<Self::Meta> {
+        if !matches!(block.kind, BlockKind::Paragraph | BlockKind::Heading) {
+            return None;
+        }
+        let mut break_offsets = Vec::new();
+        let mut offset = 0usize;
+        let mut lines = block.raw.split_inclusive('\n').peekable();
+        while let Some(line) = lines.next() {
+            offset += line.len();
+            let Some(text) = line.strip_suffix('\n') else {
+                continue;
+            };
+            let next_has_content = lines.peek().is_some_and(|next| !next.trim().is_empty());
+            if next_has_content && ends_with_hard_break(text) {
+                break_offsets.push(offset - 1);
+            }
+        }
+        Some(HardBreakMeta { break_offsets })
+    }
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct LinkSpan {
+    /// Byte range of the link text (between the brackets) in `block.raw`.
+    pub text_range: Range<usize>,
+    /// Destination of an inline link (`[text](url)`); `None` for reference links.
+    pub url: Option<String>,
+    /// Label of a reference link (`[text][label]`, `[label][]`, `[label]`), as written.
+    pub reference: Option<String>,
+}
+
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct InlineMeta {
+    pub links: Vec<LinkSpan>,
+    /// Byte ranges of code spans in `block.raw`, including the backtick delimiters.
+    pub code_spans: Vec<Range<usize>>,
+}
+
+/// Finds inline links and code spans in paragraph-like blocks.
+///
+/// Best-effort and analysis-only: reference labels are not resolved against definitions, and
+/// shortcut references (`[label]`) are reported even if no definition exists. Images and
+/// footnote references (`[^1]`) are skipped.
+#[derive(Debug, Default, Clone)]
+pub struct InlineAnalyzer;
+
+fn inline_destination(inner: &str) -> String {
+    let inner = inner.trim();
+    if let Some(rest) = inner.strip_prefix('<') {
+        if let Some(end) = rest.find('>') {
+            return rest[..end].to_string();
+        }
+    }
+    inner
+        .split(char::is_whitespace)
+        .next()
+        .unwrap_or_default()
+        .to_string()
+}
+
+fn matching_close_paren(bytes: &[u8], open: usize) -> Option<usize> {
+    let mut depth = 0usize;
+    let mut i = open;
+    while i < bytes.len() {
+        match bytes[i] {
+            b'\\' => i += 1,
+            b'(' => depth += 1,
+            b')' => {
+                depth -= 1;
+                if depth == 0 {
+                    return Some(i);
+                }
+            }
+            _ => {}
+        }
+        i += 1;
+    }
+    None
+}
+
+fn scan_inline(raw: &str) -> InlineMeta {
+    let bytes = raw.as_bytes();
+    let mut meta = InlineMeta::default();
+    let mut i = 0usize;
+    while i < bytes.len() {
+        match bytes[i] {
+            b'\\' => i += 2,
+            b'`' => {
+                let start = i;
+                while i < bytes.len() && bytes[i] == b'`' {
+                    i += 1;
+                }
+                // An unclosed backtick run is literal text.
+                if let Some(close) = closing_backticks(bytes, i, i - start) {
+                    i = close + (i - start);
+                    meta.code_spans.push(start..i);
+                }
+            }
+            b'[' => {
+                let Some(close) = find_matching_close_bracket(raw, i) else {
+                    i += 1;
+                    continue;
+                };
+                let text_range = i + 1..close;
+                let is_image = i > 0 && bytes[i - 1] == b'!';
+                let is_footnote = bytes.get(i + 1) == Some(&b'^');
+                let at_line_start = raw[..i]
+                    .rsplit('\n')
+                    .next()
+                    .is_some_and(|p| p.trim().is_empty());
+                let is_definition = at_line_start && bytes.get(close + 1) == Some(&b':');
+                if is_image
+                    || is_footnote
+                    || is_definition
+                    || raw[text_range.clone()].trim().is_empty()
+                {
+                    i += 1;
+                    continue;
+                }
+                match bytes.get(close + 1) {
+                    Some(b'(') => {
+                        let Some(paren) = matching_close_paren(bytes, close + 1) else {
+                            i += 1;
+                            continue;
+                        };
+                        meta.links.push(LinkSpan {
+                            text_range,
+                            url: Some(inline_destination(&raw[close + 2..paren])),
+                            reference: None,
+                        });
+                        i = paren + 1;
+                    }
+                    Some(b'[') => {
+                        let Some(label_close) = find_matching_close_bracket(raw, close + 1) else {
+                            i += 1;
+                            continue;
+                        };
+                        let label = &raw[close + 2..label_close];
+                        let label = if label.trim().is_empty() {
+                            &raw[text_range.clone()]
+                        } else {
+                            label
+                        };
+                        meta.links.push(LinkSpan {
+                            reference: Some(label.to_string()),
+                            text_range,
+                            url: None,
+                        });
+                        i = label_close + 1;
+                    }
+                    _ => {
+                        meta.links.push(LinkSpan {
+                            reference: Some(raw[text_range.clone()].to_string()),
+                            text_range,
+                            url: None,
+                        });
+                        i = close + 1;
+                    }
+                }
+            }
+            _ => i += 1,
+        }
+    }
+    meta
+}
+
+impl BlockAnalyzer for InlineAnalyzer {
+    type Meta = InlineMeta;
+
+    fn analyze_block(&mut self, block: &Block) -> Option<Self::Meta> {
+        if !matches!(
+            block.kind,
+            BlockKind::Paragraph
+                | BlockKind::Heading
+                | BlockKind::List
+                | BlockKind::BlockQuote
+                | BlockKind::Table
+        ) {
+            return None;
+        }
+        Some(scan_inline(&block.raw))
+    }
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct WikiLink {
+    /// Page name before the `|` (trimmed).
+    pub target: String,
+    /// Text after the `|` (trimmed), if any.
+    pub alias: Option<String>,
+    /// Byte range of the whole `[[...]]` in `block.raw`.
+    pub range: Range<usize>,
+}
+
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct WikiLinkMeta {
+    pub links: Vec<WikiLink>,
+}
+
+/// Finds Obsidian-style `[[Page]]` / `[[Page|Alias]]` links in the same blocks as `InlineAnalyzer`.
+///
+/// Analysis-only: wikilinks do not affect block splitting. A link must close on the same line and
+/// may not contain brackets; code spans, escaped brackets and embeds (`![[...]]`) are skipped.
+#[derive(Debug, Default, Clone)]
+pub struct WikiLinkAnalyzer;
+
+fn scan_wikilinks(raw: &str) -> WikiLinkMeta {
+    let bytes = raw.as_bytes();
+    let mut meta = WikiLinkMeta::default();
+    let mut i = 0usize;
+    while i < bytes.len() {
+        match bytes[i] {
+            b'\\' => i += 2,
+            b'`' => {
+                let start = i;
+                while i < bytes.len() && bytes[i] == b'`' {
+                    i += 1;
+                }
+                if let Some(close) = closing_backticks(bytes, i, i - start) {
+                    i = close + (i - start);
+                }
+            }
+            b'[' if bytes.get(i + 1) == Some(&b'[') => {
+                let start = i;
+                let inner_start = i + 2;
+                let inner_len = raw[inner_start..]
+                    .find(['[', ']', '\n'])
+                    .unwrap_or(raw.len() - inner_start);
+                let inner_end = inner_start + inner_len;
+                let is_embed = start > 0 && bytes[start - 1] == b'!';
+                if !raw[inner_end..].starts_with("]]") || is_embed {
+                    i += 1;
+                    continue;
+                }
+                let inner = &raw[inner_start..inner_end];
+                let (target, alias) = match inner.split_once('|') {
+                    Some((target, alias)) => (target.trim(), Some(alias.trim())),
+                    None => (inner.trim(), None),
+                };
+                i = inner_end + 2;
+                if target.is_empty() {
+                    continue;
+                }
+                meta.links.push(WikiLink {
+                    target: target.to_string(),
+                    alias: alias.filter(|a| !a.is_empty()).map(ToString::to_string),
+                    range: start..i,
+                });
+            }
+            _ => i += 1,
+        }
+    }
+    meta
+}
+
+impl BlockAnalyzer for WikiLinkAnalyzer {
+    type Meta = WikiLinkMeta;
+
+    fn analyze_block(&mut self, block: &Block) -> Option<Self::Meta> {
+        if !matches!(
+            block.kind,
+            BlockKind::Paragraph
+                | BlockKind::Heading
+                | BlockKind::List
+                | BlockKind::BlockQuote
+                | BlockKind::Table
+        ) {
+            return None;
+        }
+        Some(scan_wikilinks(&block.raw))
+    }
+}
+
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct TableMeta {
+    /// Column count given by the delimiter row (the header cell count while it has not arrived).
+    pub columns: usize,
+    /// Byte ranges of the header cells' contents in `block.raw`.
+    pub header: Vec<Range<usize>>,
+    /// Byte ranges of each body row's cell contents in `block.raw`, in order.
+    ///
+    /// Ragged rows keep the cells they have: a row may hold fewer or more than `columns`.
+    pub rows: Vec<Vec<Range<usize>>>,
+}
+
+/// Maps the cells of `Table` blocks to byte ranges, e.g. to make single cells clickable.
+///
+/// Ranges cover the trimmed cell text without the surrounding pipes; escaped pipes (`\|`) and
+/// pipes in code spans stay inside their cell. The delimiter row and blank lines are skipped.
+#[derive(Debug, Default, Clone)]
+pub struct TableAnalyzer;
+
+fn scan_table(raw: &str) -> TableMeta {
+    let mut meta = TableMeta::default();
+    let mut offset = 0usize;
+    for (i, line) in raw.split_inclusive('\n').enumerate() {
+        let start = offset;
+        offset += line.len();
+        let line = line.trim_end_matches('\n');
+        if line.trim().is_empty() {
+            continue;
+        }
+        let cells = table_cell_ranges(line)
+            .into_iter()
+            .map(|r| start + r.start..start + r.end);
+        match i {
+            0 => {
+                meta.header = cells.collect();
+                meta.columns = meta.header.len();
+            }
+            1 if is_table_delimiter_row(line) => meta.columns = cells.count(),
+            _ => meta.rows.push(cells.collect()),
+        }
+    }
+    meta
+}
+
+impl BlockAnalyzer for TableAnalyzer {
+    type Meta = TableMeta;
+
+    fn analyze_block(&mut self, block: &Block) -> Option<Self::Meta> {
+        if block.kind != BlockKind::Table {
+            return None;
+        }
+        Some(scan_table(&block.raw))
+    }
+}
+
 #[derive(Debug, Clone, PartialEq, Eq)]
 pub struct TaggedBlockMeta {
     pub tag: String,
@@ -462,3 +887,290 @@ impl BlockAnalyzer for TaggedBlockAnalyzer {
         })
     }
 }
+
+/// Text after the opening markers of a `FenceBoundaryPlugin`-style block (`:::tip{#id}`).
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct FenceInfo {
+    /// Leading identifier after the markers (`tip`), if any.
+    pub name: Option<String>,
+    /// Remainder of the opening line after the name, trimmed (`{#id}`).
+    pub rest: String,
+}
+
+/// Reads `FenceInfo` from the first line of blocks opened by a fence-like marker.
+///
+/// Pair it with a `FenceBoundaryPlugin` using the same `fence_char` / `min_len` (see
+/// `FenceInfoAnalyzer::for_plugin`). Code fences are skipped so `~~~` markers do not overlap.
+#[derive(Debug, Clone)]
+pub struct FenceInfoAnalyzer {
+    pub fence_char: char,
+    pub min_len: usize,
+}
+
+impl FenceInfoAnalyzer {
+    pub fn new(fence_char: char, min_len: usize) -> Self {
+        Self {
+            fence_char,
+            min_len,
+        }
+    }
+
+    pub fn triple_colon() -> Self {
+        Self::new(':', 3)
+    }
+
+    pub fn for_plugin(plugin: &FenceBoundaryPlugin) -> Self {
+        Self::new(plugin.fence_char, plugin.min_len)
+    }
+
+    fn parse_opening_line(&self, line: &str) -> Option<FenceInfo> {
+        let line = line.trim_end_matches(['\n', '\r']);
+        let indent = line.len() - line.trim_start_matches(' ').len();
+        if indent > 3 {
+            return None;
+        }
+        let s = &line[indent..];
+        let markers = s.len() - s.trim_start_matches(self.fence_char).len();
+        if markers < self.fence_char.len_utf8() * self.min_len {
+            return None;
+        }
+        let info = s[markers..].trim();
+        let name_len = info
+            .bytes()
+            .take_while(|b| b.is_ascii_alphanumeric() || *b == b'_' || *b == b'-')
+            .count();
+        let name = (name_len > 0).then(|| info[..name_len].to_string());
+        Some(FenceInfo {
+            name,
+            rest: info[name_len..].trim().to_string(),
+        })
+    }
+}
+
+impl Default for FenceInfoAnalyzer {
+    fn default() -> Self {
+        Self::triple_colon()
+    }
+}
+
+impl BlockAnalyzer for FenceInfoAnalyzer {
+    type Meta = FenceInfo;
+
+    fn analyze_block(&mut self, block: &Block) -> Option<Self::Meta> {
+        if block.kind == BlockKind::CodeFence {
+            return None;
+        }
+        let first_line = block.raw.split('\n').next().unwrap_or(&block.raw);
+        self.parse_opening_line(first_line)
+    }
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct HeadingMeta {
+    pub level: u8,
+    /// Heading text with inline markup stripped.
+    pub text: String,
+    /// GitHub-style anchor slug, unique within the document (`intro`, `intro-1`, ...).
+    pub slug: String,
+}
+
+/// Extracts heading level/text and generates document-unique anchor slugs.
+///
+/// Slugs are only reserved for committed headings; a pending heading gets the slug it would get if
+/// committed now, so repeated pending updates do not consume de-duplication suffixes.
+#[derive(Debug, Default, Clone)]
+pub struct HeadingAnalyzer {
+    slug_counts: Map<String, usize>,
+}
+
+fn parse_heading(raw: &str) -> Option<(u8, &str)> {
+    let raw = raw.trim_end_matches(['\n', '\r']);
+    let mut lines = raw.split('\n');
+    let first = lines.next()?.trim_end_matches('\r');
+
+    let s = first.trim_start();
+    if s.starts_with('#') {
+        let level = s.bytes().take_while(|&b| b == b'#').count();
+        if level > 6 {
+            return None;
+        }
+        let rest = &s[level..];
+        if !rest.is_empty() && !rest.starts_with([' ', '\t']) {
+            return None;
+        }
+        // Optional closing sequence: trailing `#`s preceded by whitespace.
+        let mut text = rest.trim();
+        let without_hashes = text.trim_end_matches('#');
+        if without_hashes.is_empty() || without_hashes.ends_with([' ', '\t']) {
+            text = without_hashes.trim_end();
+        }
+        return Some((level as u8, text));
+    }
+
+    let underline = lines.next()?.trim();
+    let level = match underline.chars().next()? {
+        '=' => 1,
+        '-' => 2,
+        _ => return None,
+    };
+    Some((level, first.trim()))
+}
+
+fn strip_inline_markup(text: &str) -> String {
+    let mut out = String::with_capacity(text.len());
+    let chars: Vec<char> = text.chars().collect();
+    let mut i = 0usize;
+    while i < chars.len() {
+        let c = chars[i];
+        match c {
+            '*' | '`' | '~' => {}
+            '!' if chars.get(i + 1) == Some(&'[') => {}
+            '[' | ']' => {
+                // Drop the `(url)` part of `[text](url)`.
+                if c == ']' && chars.get(i + 1) == Some(&'(') {
+                    if let Some(close) = chars[i + 1..].iter().position(|&c| c == ')') {
+                        i += close + 2;
+                        continue;
+                    }
+                }
+            }
+            '<' => {
+                if let Some(close) = chars[i..].iter().position(|&c| c == '>') {
+                    i += close + 1;
+                    continue;
+                }
+                out.push(c);
+            }
+            '_' => {
+                // Keep intra-word underscores (`snake_case`), drop emphasis markers.
+                let prev_word = i > 0 && chars[i - 1].is_alphanumeric();
+                let next_word = chars.get(i + 1).is_some_and(|c| c.is_alphanumeric());
+                if prev_word && next_word {
+                    out.push(c);
+                }
+            }
+            _ => out.push(c),
+        }
+        i += 1;
+    }
+    out.split_whitespace().collect::<Vec<_>>().join(" ")
+}
+
+fn slug_base(text: &str) -> String {
+    text.chars()
+        .filter_map(|c| {
+            if c.is_alphanumeric() || c == '-' || c == '_' {
+                Some(c.to_lowercase().collect::<String>())
+            } else if c == ' ' {
+                Some("-".to_string())
+            } else {
+                None
+            }
+        })
+        .collect()
+}
+
+impl HeadingAnalyzer {
+    /// Returns the slug for `base` and the updated occurrence count (github-slugger semantics).
+    fn unique_slug(&self, base: &str) -> (String, usize) {
+        let Some(&count) = self.slug_counts.get(base) else {
+            return (base.to_string(), 0);
+        };
+        let mut count = count;
+        loop {
+            count += 1;
+            let slug = format!("{base}-{count}");
+            if !self.slug_counts.contains_key(&slug) {
+                return (slug, count);
+            }
+        }
+    }
+}
+
+impl BlockAnalyzer for HeadingAnalyzer {
+    type Meta = HeadingMeta;
+
+    fn analyze_block(&mut self, block: &Block) -> Option<Self::Meta> {
+        if block.kind != BlockKind::Heading {
+            return None;
+        }
+        let (level, raw_text) = parse_heading(&block.raw)?;
+        let text = strip_inline_markup(raw_text);
+        let base = slug_base(&text);
+        let (slug, count) = self.unique_slug(&base);
+        if block.status == BlockStatus::Committed {
+            self.slug_counts.insert(base, count);
+            // Reserve the generated slug too, so a later literal `Intro 1` becomes `intro-1-1`.
+            self.slug_counts.entry(slug.clone()).or_insert(0);
+        }
+        Some(HeadingMeta { level, text, slug })
+    }
+
+    fn reset(&mut self) {
+        self.slug_counts.clear();
+    }
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct FootnoteMeta {
+    /// Footnote label without the `^` (`note` for `[^note]: ...`).
+    pub label: String,
+    /// Definition text with continuation lines dedented; trailing blank lines are dropped.
+    pub content: String,
+}
+
+/// Extracts the label and body of `FootnoteDefinition` blocks.
+///
+/// Footnote definitions only get their own blocks with `FootnotesMode::Invalidate`; in
+/// `SingleBlock` mode the whole document is one `Unknown` block and nothing is reported.
+#[derive(Debug, Default, Clone, Copy)]
+pub struct FootnoteAnalyzer;
+
+fn dedent_footnote_continuation(line: &str) -> &str {
+    line.strip_prefix('\t')
+        .or_else(|| line.strip_prefix("    "))
+        .unwrap_or(line)
+}
+
+fn parse_footnote_definition(raw: &str) -> Option<FootnoteMeta> {
+    let mut lines = raw.split('\n').map(|l| l.trim_end_matches('\r'));
+    let first = lines.next()?;
+    if !is_footnote_definition_start(first) {
+        return None;
+    }
+    let s = first.trim_start();
+    let close = s.find("]:")?;
+    let label = &s[2..close];
+    if label.is_empty() {
+        return None;
+    }
+
+    let mut content = String::from(s[close + 2..].trim_start());
+    for line in lines {
+        content.push('\n');
+        if is_footnote_continuation(line) {
+            content.push_str(dedent_footnote_continuation(line));
+        } else {
+            // Blank separators between paragraphs (and lazy lines) are kept as-is.
+            content.push_str(line.trim_start());
+        }
+    }
+    let trimmed = content.trim_end().len();
+    content.truncate(trimmed);
+
+    Some(FootnoteMeta {
+        label: label.to_string(),
+        content,
+    })
+}
+
+impl BlockAnalyzer for FootnoteAnalyzer {
+    type Meta = FootnoteMeta;
+
+    fn analyze_block(&mut self, block: &Block) -> Option<Self::Meta> {
+        if block.kind != BlockKind::FootnoteDefinition {
+            return None;
+        }
+        parse_footnote_definition(&block.raw)
+    }
+}
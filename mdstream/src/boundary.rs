@@ -1,3 +1,7 @@
+use alloc::boxed::Box;
+use alloc::string::{String, ToString};
+use alloc::vec::Vec;
+
 #[derive(Debug, Clone, Copy, PartialEq, Eq)]
 pub enum BoundaryUpdate {
     Continue,
@@ -27,6 +31,12 @@ pub trait BoundaryPlugin: Send + Sync {
     fn update(&mut self, line: &str) -> BoundaryUpdate;
 
     fn reset(&mut self) {}
+
+    /// Short name for the blocks this plugin claims (e.g. `thinking`), reported by
+    /// `MdStream::active_boundary_label`. Empty by default.
+    fn label(&self) -> &str {
+        ""
+    }
 }
 
 #[cfg(not(feature = "sync"))]
@@ -45,6 +55,12 @@ pub trait BoundaryPlugin: Send {
     fn update(&mut self, line: &str) -> BoundaryUpdate;
 
     fn reset(&mut self) {}
+
+    /// Short name for the blocks this plugin claims (e.g. `thinking`), reported by
+    /// `MdStream::active_boundary_label`. Empty by default.
+    fn label(&self) -> &str {
+        ""
+    }
 }
 
 type MatchStartFn = dyn Fn(&str) -> bool + Send + Sync;
@@ -75,6 +91,7 @@ pub struct FnBoundaryPlugin {
     start: Option<Box<StartFn>>,
     update: Box<UpdateFn>,
     reset: Option<Box<ResetFn>>,
+    label: String,
 }
 
 impl FnBoundaryPlugin {
@@ -89,6 +106,7 @@ impl FnBoundaryPlugin {
             start: None,
             update: Box::new(update),
             reset: None,
+            label: String::new(),
         }
     }
 
@@ -103,6 +121,7 @@ impl FnBoundaryPlugin {
             start: None,
             update: Box::new(update),
             reset: None,
+            label: String::new(),
         }
     }
 
@@ -124,6 +143,12 @@ impl FnBoundaryPlugin {
         self
     }
 
+    /// Set the label reported by `BoundaryPlugin::label`.
+    pub fn with_label(mut self, label: impl Into<String>) -> Self {
+        self.label = label.into();
+        self
+    }
+
     #[cfg(not(feature = "sync"))]
     pub fn with_reset<R>(mut self, reset: R) -> Self
     where
@@ -163,6 +188,10 @@ impl BoundaryPlugin for FnBoundaryPlugin {
             (f)();
         }
     }
+
+    fn label(&self) -> &str {
+        &self.label
+    }
 }
 
 fn strip_up_to_three_leading_spaces(line: &str) -> &str {
@@ -303,6 +332,8 @@ impl BoundaryPlugin for FenceBoundaryPlugin {
 /// - The start tag must be complete on the line (must contain `>`).
 /// - End must be a standalone closing tag line (after up to 3 spaces), unless
 ///   `require_standalone_end` is set to `false`.
+/// - A self-closing start tag (`<thinking/>`) or a start line that already ends with the closing
+///   tag (`<thinking>done</thinking>`) produces a one-line block.
 #[derive(Debug, Clone)]
 pub struct TagBoundaryPlugin {
     pub tag: String,
@@ -310,6 +341,7 @@ pub struct TagBoundaryPlugin {
     pub allow_attributes: bool,
     pub require_standalone_end: bool,
     active: bool,
+    closes_on_start_line: bool,
 }
 
 impl TagBoundaryPlugin {
@@ -320,6 +352,7 @@ impl TagBoundaryPlugin {
             allow_attributes: true,
             require_standalone_end: true,
             active: false,
+            closes_on_start_line: false,
         }
     }
 
@@ -331,31 +364,32 @@ impl TagBoundaryPlugin {
         b.is_ascii_alphanumeric() || b == b'-' || b == b'_' || b == b':'
     }
 
-    fn norm_tag<'a>(&self, tag: &'a str) -> std::borrow::Cow<'a, str> {
+    fn norm_tag<'a>(&self, tag: &'a str) -> alloc::borrow::Cow<'a, str> {
         if self.case_insensitive {
-            std::borrow::Cow::Owned(tag.to_ascii_lowercase())
+            alloc::borrow::Cow::Owned(tag.to_ascii_lowercase())
         } else {
-            std::borrow::Cow::Borrowed(tag)
+            alloc::borrow::Cow::Borrowed(tag)
         }
     }
 
-    fn matches_opening(&self, line: &str) -> bool {
+    /// Parse an opening tag at the start of `line`.
+    ///
+    /// Returns `(self_closing, rest)` where `rest` is the text after the opening tag's `>`.
+    fn parse_opening<'a>(&self, line: &'a str) -> Option<(bool, &'a str)> {
         let s = strip_up_to_three_leading_spaces(line).trim_end();
         if !s.starts_with('<') {
-            return false;
+            return None;
         }
         // Require the tag to be complete on this line.
-        let Some(gt) = s.find('>') else {
-            return false;
-        };
+        let gt = s.find('>')?;
         let inside = &s[1..gt];
         if inside.starts_with('/') || inside.starts_with('!') || inside.starts_with('?') {
-            return false;
+            return None;
         }
 
         let bytes = inside.as_bytes();
         if bytes.is_empty() || !bytes[0].is_ascii_alphabetic() {
-            return false;
+            return None;
         }
         let mut name_end = 1usize;
         while name_end < bytes.len() && Self::is_tag_name_char(bytes[name_end]) {
@@ -365,46 +399,77 @@ impl TagBoundaryPlugin {
         let name = self.norm_tag(name);
         let want = self.norm_tag(self.tag.as_str());
         if name != want {
-            return false;
+            return None;
         }
 
-        let rest = inside[name_end..].trim();
-        if rest.is_empty() {
-            return true;
-        }
-        if !self.allow_attributes {
-            return false;
+        let attrs = inside[name_end..].trim();
+        let (self_closing, attrs) = match attrs.strip_suffix('/') {
+            Some(a) => (true, a.trim_end()),
+            None => (false, attrs),
+        };
+        if !attrs.is_empty() && !self.allow_attributes {
+            return None;
         }
-        true
+        Some((self_closing, &s[gt + 1..]))
     }
 
-    fn matches_closing(&self, line: &str) -> bool {
-        let s = strip_up_to_three_leading_spaces(line).trim_end();
-        if !s.starts_with("</") {
-            return false;
-        }
-        let want = self.norm_tag(self.tag.as_str());
+    fn matches_opening(&self, line: &str) -> bool {
+        self.parse_opening(line).is_some()
+    }
 
-        let after = &s[2..];
+    /// If `s` starts with a closing tag for this plugin, return `(between, after)`: the text
+    /// between the tag name and `>`, and the text after `>`.
+    fn split_closing_tag<'a>(&self, s: &'a str) -> Option<(&'a str, &'a str)> {
+        let after = s.strip_prefix("</")?;
         let bytes = after.as_bytes();
         if bytes.is_empty() || !bytes[0].is_ascii_alphabetic() {
-            return false;
+            return None;
         }
         let mut name_end = 1usize;
         while name_end < bytes.len() && Self::is_tag_name_char(bytes[name_end]) {
             name_end += 1;
         }
         let name = self.norm_tag(&after[..name_end]);
-        if name != want {
-            return false;
+        if name != self.norm_tag(self.tag.as_str()) {
+            return None;
         }
+        let rest = &after[name_end..];
+        let gt = rest.find('>')?;
+        Some((&rest[..gt], &rest[gt + 1..]))
+    }
 
-        let rest = after[name_end..].trim();
+    fn matches_closing(&self, line: &str) -> bool {
+        let s = strip_up_to_three_leading_spaces(line).trim_end();
+        let Some((between, after)) = self.split_closing_tag(s) else {
+            return false;
+        };
         if self.require_standalone_end {
-            rest == ">"
+            between.trim().is_empty() && after.trim().is_empty()
         } else {
-            rest.contains('>')
+            true
+        }
+    }
+
+    /// Whether the opening line also closes the block (`<tag/>` or `<tag>...</tag>`).
+    fn closes_on_opening_line(&self, line: &str) -> bool {
+        let Some((self_closing, rest)) = self.parse_opening(line) else {
+            return false;
+        };
+        if self_closing {
+            return true;
+        }
+        let mut search = rest;
+        while let Some(pos) = search.find("</") {
+            if let Some((between, after)) = self.split_closing_tag(&search[pos..]) {
+                if !self.require_standalone_end
+                    || (between.trim().is_empty() && after.trim().is_empty())
+                {
+                    return true;
+                }
+            }
+            search = &search[pos + 2..];
         }
+        false
     }
 }
 
@@ -413,16 +478,18 @@ impl BoundaryPlugin for TagBoundaryPlugin {
         self.matches_opening(line)
     }
 
-    fn start(&mut self, _line: &str) {
+    fn start(&mut self, line: &str) {
         self.active = true;
+        self.closes_on_start_line = self.closes_on_opening_line(line);
     }
 
     fn update(&mut self, line: &str) -> BoundaryUpdate {
         if !self.active {
             return BoundaryUpdate::Continue;
         }
-        if self.matches_closing(line) {
+        if self.closes_on_start_line || self.matches_closing(line) {
             self.active = false;
+            self.closes_on_start_line = false;
             return BoundaryUpdate::Close;
         }
         BoundaryUpdate::Continue
@@ -430,6 +497,12 @@ impl BoundaryPlugin for TagBoundaryPlugin {
 
     fn reset(&mut self) {
         self.active = false;
+        self.closes_on_start_line = false;
+    }
+
+    /// The tag name, e.g. `thinking`.
+    fn label(&self) -> &str {
+        &self.tag
     }
 }
 
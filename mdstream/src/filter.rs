@@ -0,0 +1,81 @@
+use alloc::vec::Vec;
+
+use crate::types::{Block, Update};
+use crate::{MdStream, Options};
+
+/// Wraps an `MdStream` and only forwards blocks matching a predicate.
+///
+/// Every chunk is still fed to the inner stream, so block splitting is the same as without the
+/// filter; only the returned updates are narrowed. `committed`, `reemitted` and
+/// `finished_messages` keep the matching blocks, and `pending` is `None` unless it matches.
+/// `reset`, `invalidated` and `diagnostics` are passed through unchanged, so `invalidated` may name
+/// blocks that were never forwarded.
+///
+/// A pending block is tested on its partial content: a code fence is recognized from its opening
+/// line, but a block whose kind settles late may show up only once it is committed.
+pub struct BlockFilter<F>
+where
+    F: Fn(&Block) -> bool,
+{
+    inner: MdStream,
+    predicate: F,
+}
+
+impl<F> BlockFilter<F>
+where
+    F: Fn(&Block) -> bool,
+{
+    pub fn new(opts: Options, predicate: F) -> Self {
+        Self::with_stream(MdStream::new(opts), predicate)
+    }
+
+    pub fn with_stream(inner: MdStream, predicate: F) -> Self {
+        Self { inner, predicate }
+    }
+
+    pub fn inner(&self) -> &MdStream {
+        &self.inner
+    }
+
+    pub fn inner_mut(&mut self) -> &mut MdStream {
+        &mut self.inner
+    }
+
+    pub fn into_inner(self) -> MdStream {
+        self.inner
+    }
+
+    pub fn matches(&self, block: &Block) -> bool {
+        (self.predicate)(block)
+    }
+
+    pub fn append(&mut self, chunk: &str) -> Update {
+        let update = self.inner.append(chunk);
+        self.filter_update(update)
+    }
+
+    pub fn finalize(&mut self) -> Update {
+        let update = self.inner.finalize();
+        self.filter_update(update)
+    }
+
+    pub fn reset(&mut self) {
+        self.inner.reset();
+    }
+
+    /// Narrows an update produced by the inner stream (e.g. from `inner_mut().append_all(..)`).
+    pub fn filter_update(&self, mut update: Update) -> Update {
+        update.committed = self.filter_committed(update.committed);
+        update.pending = update.pending.filter(|b| self.matches(b));
+        update.reemitted = self.filter_committed(update.reemitted);
+        for message in &mut update.finished_messages {
+            message.retain(|b| self.matches(b));
+        }
+        update
+    }
+
+    pub fn filter_committed(&self, mut blocks: Vec<Block>) -> Vec<Block> {
+        blocks.retain(|b| self.matches(b));
+        blocks
+    }
+}
@@ -0,0 +1,60 @@
+//! Inline scanning helpers shared by the terminator, table splitting, and analyzers.
+
+/// Start of the next backtick run of exactly `run` backticks at or after `from`.
+pub(crate) fn closing_backticks(bytes: &[u8], from: usize, run: usize) -> Option<usize> {
+    let mut j = from;
+    while j < bytes.len() {
+        if bytes[j] != b'`' {
+            j += 1;
+            continue;
+        }
+        let start = j;
+        while j < bytes.len() && bytes[j] == b'`' {
+            j += 1;
+        }
+        if j - start == run {
+            return Some(start);
+        }
+    }
+    None
+}
+
+pub(crate) fn find_matching_open_bracket(text: &str, close_index: usize) -> Option<usize> {
+    let bytes = text.as_bytes();
+    let mut depth = 1usize;
+    let mut i = close_index;
+    while i > 0 {
+        i -= 1;
+        match bytes[i] {
+            b']' => depth += 1,
+            b'[' => {
+                depth -= 1;
+                if depth == 0 {
+                    return Some(i);
+                }
+            }
+            _ => {}
+        }
+    }
+    None
+}
+
+pub(crate) fn find_matching_close_bracket(text: &str, open_index: usize) -> Option<usize> {
+    let bytes = text.as_bytes();
+    let mut depth = 1usize;
+    let mut i = open_index + 1;
+    while i < bytes.len() {
+        match bytes[i] {
+            b'[' => depth += 1,
+            b']' => {
+                depth -= 1;
+                if depth == 0 {
+                    return Some(i);
+                }
+            }
+            _ => {}
+        }
+        i += 1;
+    }
+    None
+}
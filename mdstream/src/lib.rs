@@ -1,22 +1,39 @@
+#![cfg_attr(not(feature = "std"), no_std)]
+
+extern crate alloc;
+
 pub mod analyze;
 pub mod boundary;
+pub mod filter;
+mod inline;
 pub mod options;
+#[cfg(feature = "rayon")]
+pub mod parallel;
 pub mod pending;
+#[cfg(feature = "std")]
 mod reference;
+#[cfg(feature = "render")]
+pub mod render;
 pub mod state;
 pub mod stream;
 pub mod syntax;
+mod table;
 pub mod transform;
 pub mod types;
+#[cfg(feature = "std")]
+pub mod writer;
 
 #[cfg(feature = "pulldown")]
 pub mod adapters;
 
 pub use analyze::*;
 pub use boundary::*;
+pub use filter::*;
 pub use options::*;
 pub use state::*;
 pub use stream::*;
 pub use syntax::*;
 pub use transform::*;
 pub use types::*;
+#[cfg(feature = "std")]
+pub use writer::*;
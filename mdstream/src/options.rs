@@ -1,4 +1,18 @@
+use alloc::string::String;
+use alloc::vec::Vec;
+
+#[cfg(any(feature = "std", feature = "trace"))]
+use alloc::sync::Arc;
+
 use crate::pending::TerminatorOptions;
+#[cfg(feature = "std")]
+use crate::types::AppendTiming;
+#[cfg(feature = "trace")]
+use crate::types::ModeChange;
+
+/// Callback type of `Options::trace`.
+#[cfg(feature = "trace")]
+pub type TraceHook = Arc<dyn Fn(&ModeChange) + Send + Sync>;
 
 #[derive(Debug, Clone, Copy, PartialEq, Eq)]
 pub enum FootnotesMode {
@@ -16,11 +30,14 @@ pub enum ReferenceDefinitionsMode {
     StabilityFirst,
     /// Emit invalidation events so adapters can selectively re-parse affected blocks.
     ///
+    /// Needs the `std` feature: without it the usage index is not built and this behaves like
+    /// `StabilityFirst` (no invalidations are reported).
+    ///
     /// Note: Invalidation support is planned post-MVP.
     Invalidate,
 }
 
-#[derive(Debug, Clone)]
+#[derive(Clone)]
 pub struct Options {
     pub footnotes: FootnotesMode,
     pub reference_definitions: ReferenceDefinitionsMode,
@@ -28,6 +45,194 @@ pub struct Options {
     pub terminator_window_bytes: usize,
     /// Optional hard cap for the internal buffer.
     pub max_buffer_bytes: Option<usize>,
+    /// Optional cap on the pending snapshot of a single line that has no newline yet.
+    ///
+    /// When the last line of the pending block has no newline yet and is longer than this,
+    /// `Update::pending` (and `append_ref`/`snapshot_blocks`) expose only its last bytes instead of
+    /// copying the whole block on every append; earlier lines of a multi-line block (e.g. a code
+    /// fence header) are left out of the snapshot as well. Committed blocks still carry the full
+    /// text.
+    pub max_pending_line_bytes: Option<usize>,
+    /// Detect Pandoc-style definition lists (`Term` followed by `: definition` lines) as
+    /// `BlockKind::DefinitionList`.
+    pub definition_lists: bool,
+    /// Most `#` an ATX heading may start with (default 6, as in CommonMark).
+    ///
+    /// Longer runs such as `####### text` are paragraph text.
+    pub heading_max_level: u8,
+    /// Fewest backticks or tildes that open a code fence (default 3, as in CommonMark; smaller
+    /// values count as 3).
+    ///
+    /// Some flavors require 4+, so shorter fences inside stay content. A fence only closes on a run
+    /// at least as long as its opening one either way.
+    pub code_fence_min_len: usize,
+    /// Keep lines indented by 4+ columns after an ATX heading in the heading block (default false).
+    ///
+    /// For formats that put metadata under a heading. The heading then commits at the first blank
+    /// or less indented line instead of at the end of its own line.
+    pub heading_absorbs_indented_continuation: bool,
+    /// Commit blank lines that no block owns as `BlockKind::Blank` blocks instead of dropping them
+    /// (default false).
+    ///
+    /// Blank lines after a block that ends at a blank line (paragraph, list, blockquote, table, ...)
+    /// are part of that block's `raw`, however many there are: `A\n\n\n\nB` gives `A\n\n\n\n` and
+    /// `B`. Blank lines after a block that closes itself (heading, thematic break, closed code
+    /// fence, math or HTML block, boundary-plugin container) and at the start of the stream belong to
+    /// no block and are dropped. With this set they become their own blocks, so the committed raws
+    /// concatenate back to the (newline-normalized) input.
+    pub emit_blank_blocks: bool,
+    /// Characters accepted as bullet list markers besides `-`, `+` and `*` (default none, as in
+    /// CommonMark).
+    ///
+    /// For producers that write Unicode bullets, e.g. `vec!['•', '‣']` makes `• item` a list item.
+    /// Like ASCII bullets, the marker must be followed by a space or tab. Renderers that parse the
+    /// block with a CommonMark parser still see a paragraph unless they accept the same markers.
+    pub extra_list_markers: Vec<char>,
+    /// Consecutive blank lines a list or blockquote may contain (default 1).
+    ///
+    /// After more blank lines, only indented continuation content stays in the block; a new list
+    /// item or quote marker starts a new block.
+    pub list_blank_line_limit: usize,
+    /// Minimum indentation (in columns, tabs count to the next multiple of 4) for a line after a
+    /// blank line to continue a list item (default 2). Less indented lines start a new block.
+    pub list_continuation_min_indent: usize,
+    /// Optional cap on distinct reference labels tracked for invalidation.
+    ///
+    /// When exceeded, the oldest labels are evicted. Definitions for evicted labels no longer
+    /// invalidate earlier blocks (fewer invalidations), but block splitting is unaffected.
+    ///
+    /// Each label also records the ids of the blocks using it. Those are only bounded by
+    /// `invalidation_max_lookback` (ids outside the window are dropped); without it they grow with
+    /// the number of using blocks.
+    pub max_reference_labels: Option<usize>,
+    /// Only invalidate blocks among the most recent N committed blocks.
+    ///
+    /// With `ReferenceDefinitionsMode::Invalidate`, a late definition normally invalidates every
+    /// earlier user of its label. Older users (e.g. long scrolled off-screen) are then skipped and
+    /// keep their stale rendering, trading a little correctness for fewer re-parses.
+    pub invalidation_max_lookback: Option<usize>,
+    /// Also put a copy of every invalidated committed block into `Update::reemitted`.
+    ///
+    /// Only meaningful with `ReferenceDefinitionsMode::Invalidate`.
+    pub reemit_invalidated: bool,
+    /// Compute the terminated `display` of the pending block (default true).
+    ///
+    /// Headless consumers that ignore partial blocks can turn this off: pending blocks then carry
+    /// `display: None`, and neither termination nor pending transformers run.
+    pub compute_pending_display: bool,
+    /// Keep a `display` on committed blocks when it differs from `raw`.
+    ///
+    /// The display is computed like the pending display (terminator, code fence closing, pending
+    /// transformers), so the final block renders the same as it did while pending.
+    pub commit_with_display: bool,
+    /// Let `finalize` append a `\n` when the stream ended mid-line.
+    ///
+    /// The newline goes through the normal line processing before the final commit, so a last line
+    /// such as a table delimiter row is recognized as it would be with a trailing newline. The
+    /// committed `raw` of the last block then ends with that `\n`.
+    pub finalize_appends_newline: bool,
+    /// Let `finalize` keep a still-open boundary-plugin container (e.g. `<thinking>` without its
+    /// closing tag) as the pending block instead of committing it.
+    ///
+    /// An unterminated container at end of input usually means the generation was cut off; with
+    /// this set, `finalize` returns it in `Update::pending` and later appends can still close it.
+    /// See `MdStream::has_open_container`.
+    pub commit_on_finalize_returns_pending_if_open_container: bool,
+    /// Expand tabs to spaces (at `tab_width` columns) in the `display` of code fence blocks.
+    ///
+    /// `raw` always keeps the tabs. Committed code fences only carry a display with
+    /// `commit_with_display`.
+    pub expand_tabs_in_code_display: bool,
+    /// Tab stop width used by `expand_tabs_in_code_display` (default 4).
+    pub tab_width: usize,
+    /// Shorten the `display` of a pending code fence whose body is longer than this many lines.
+    ///
+    /// The display then shows the opening line, a `… N more lines …` marker and the last lines of
+    /// the body, so a growing fence keeps a bounded height. `raw` stays complete and committed
+    /// blocks are never shortened.
+    pub pending_code_fence_display_max_lines: Option<usize>,
+    /// Leave the display of HTML blocks untouched by the terminator (default true).
+    ///
+    /// Text inside an HTML block is raw HTML, so `*not emphasis*` must not be balanced into
+    /// markdown. Pending transformers still run.
+    pub keep_html_raw_text_verbatim: bool,
+    /// End-of-message sentinel (e.g. `<|eot|>`).
+    ///
+    /// When it appears in the stream, everything before it is finalized into
+    /// `Update::finished_messages`, the sentinel is consumed, and the stream resets (`reset: true`)
+    /// with the following text as the new message. Text that could be the start of a split
+    /// sentinel is held back until the next chunk (or `finalize`).
+    pub message_terminator: Option<String>,
+    /// Collect non-fatal structural diagnostics into `Update::diagnostics`.
+    ///
+    /// Useful for lint-style consumers; block splitting is unaffected.
+    pub diagnostics: bool,
+    /// Called at the end of every `append`/`append_ref` with timings for that call.
+    ///
+    /// Nothing is measured while this is `None`. Requires the `std` feature.
+    #[cfg(feature = "std")]
+    pub instrument: Option<Arc<dyn Fn(AppendTiming) + Send + Sync>>,
+    /// Called on every block-mode change of the splitter, to debug why a block split where it did.
+    ///
+    /// Requires the `trace` feature; without it no tracing code is compiled in.
+    #[cfg(feature = "trace")]
+    pub trace: Option<TraceHook>,
+}
+
+impl core::fmt::Debug for Options {
+    fn fmt(&self, f: &mut core::fmt::Formatter<'_>) -> core::fmt::Result {
+        let mut s = f.debug_struct("Options");
+        s.field("footnotes", &self.footnotes)
+            .field("reference_definitions", &self.reference_definitions)
+            .field("terminator", &self.terminator)
+            .field("terminator_window_bytes", &self.terminator_window_bytes)
+            .field("max_buffer_bytes", &self.max_buffer_bytes)
+            .field("max_pending_line_bytes", &self.max_pending_line_bytes)
+            .field("definition_lists", &self.definition_lists)
+            .field("heading_max_level", &self.heading_max_level)
+            .field("code_fence_min_len", &self.code_fence_min_len)
+            .field(
+                "heading_absorbs_indented_continuation",
+                &self.heading_absorbs_indented_continuation,
+            )
+            .field("emit_blank_blocks", &self.emit_blank_blocks)
+            .field("extra_list_markers", &self.extra_list_markers)
+            .field("list_blank_line_limit", &self.list_blank_line_limit)
+            .field(
+                "list_continuation_min_indent",
+                &self.list_continuation_min_indent,
+            )
+            .field("max_reference_labels", &self.max_reference_labels)
+            .field("invalidation_max_lookback", &self.invalidation_max_lookback)
+            .field("reemit_invalidated", &self.reemit_invalidated)
+            .field("compute_pending_display", &self.compute_pending_display)
+            .field("commit_with_display", &self.commit_with_display)
+            .field("finalize_appends_newline", &self.finalize_appends_newline)
+            .field(
+                "commit_on_finalize_returns_pending_if_open_container",
+                &self.commit_on_finalize_returns_pending_if_open_container,
+            )
+            .field(
+                "expand_tabs_in_code_display",
+                &self.expand_tabs_in_code_display,
+            )
+            .field("tab_width", &self.tab_width)
+            .field(
+                "pending_code_fence_display_max_lines",
+                &self.pending_code_fence_display_max_lines,
+            )
+            .field(
+                "keep_html_raw_text_verbatim",
+                &self.keep_html_raw_text_verbatim,
+            )
+            .field("message_terminator", &self.message_terminator)
+            .field("diagnostics", &self.diagnostics);
+        #[cfg(feature = "std")]
+        s.field("instrument", &self.instrument.is_some());
+        #[cfg(feature = "trace")]
+        s.field("trace", &self.trace.is_some());
+        s.finish()
+    }
 }
 
 impl Default for Options {
@@ -38,6 +243,32 @@ impl Default for Options {
             terminator: TerminatorOptions::default(),
             terminator_window_bytes: 16 * 1024,
             max_buffer_bytes: None,
+            max_pending_line_bytes: None,
+            definition_lists: false,
+            heading_max_level: 6,
+            code_fence_min_len: 3,
+            heading_absorbs_indented_continuation: false,
+            emit_blank_blocks: false,
+            extra_list_markers: Vec::new(),
+            list_blank_line_limit: 1,
+            list_continuation_min_indent: 2,
+            max_reference_labels: None,
+            invalidation_max_lookback: None,
+            reemit_invalidated: false,
+            compute_pending_display: true,
+            commit_with_display: false,
+            finalize_appends_newline: false,
+            commit_on_finalize_returns_pending_if_open_container: false,
+            expand_tabs_in_code_display: false,
+            tab_width: 4,
+            pending_code_fence_display_max_lines: None,
+            keep_html_raw_text_verbatim: true,
+            message_terminator: None,
+            diagnostics: false,
+            #[cfg(feature = "std")]
+            instrument: None,
+            #[cfg(feature = "trace")]
+            trace: None,
         }
     }
 }
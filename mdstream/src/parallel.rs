@@ -0,0 +1,33 @@
+//! Split many independent documents on a rayon thread pool (requires the `rayon` feature).
+//!
+//! Each document gets its own fresh `MdStream`, so results are exactly what
+//! `MdStream::parse_complete` returns for that document alone, in input order.
+
+use alloc::vec::Vec;
+
+use rayon::prelude::*;
+
+use crate::options::Options;
+use crate::stream::MdStream;
+use crate::types::Block;
+
+/// Parse every document in `docs` in parallel with a clone of `opts`.
+///
+/// Boundary plugins and pending transformers live on `MdStream`, not `Options`, so this path is
+/// plugin-free; use [`parse_blocks_parallel_with`] to configure them per stream.
+pub fn parse_blocks_parallel(docs: &[&str], opts: Options) -> Vec<Vec<Block>> {
+    parse_blocks_parallel_with(docs, || MdStream::new(opts.clone()))
+}
+
+/// Parse every document in `docs` in parallel, building each stream with `factory`.
+///
+/// `factory` is called once per document on a worker thread, so streams (and their plugins)
+/// never cross threads.
+pub fn parse_blocks_parallel_with<F>(docs: &[&str], factory: F) -> Vec<Vec<Block>>
+where
+    F: Fn() -> MdStream + Sync,
+{
+    docs.par_iter()
+        .map(|doc| factory().parse_complete(doc).collect())
+        .collect()
+}
@@ -1,3 +1,9 @@
+use alloc::format;
+use alloc::string::{String, ToString};
+use alloc::vec::Vec;
+
+use crate::inline::{find_matching_close_bracket, find_matching_open_bracket};
+
 #[derive(Debug, Clone)]
 pub struct TerminatorOptions {
     pub setext_headings: bool,
@@ -7,6 +13,22 @@ pub struct TerminatorOptions {
     pub inline_code: bool,
     pub strikethrough: bool,
     pub katex_block: bool,
+    /// Hide a trailing incomplete emoji shortcode (`:smi` while `:smile:` streams in), so
+    /// shortcode-replacing renderers do not flicker. Off by default.
+    pub emoji_shortcodes: bool,
+    /// Only close `*`/`_` runs that can open emphasis under CommonMark's left/right-flanking rules,
+    /// so e.g. the `*` in `2 * 3` or `foo*"bar` is left alone. Off by default (streamdown parity).
+    pub strict_flanking: bool,
+    /// Close several open emphasis runs, or a mix of `*` and `_` runs, innermost first from a
+    /// delimiter stack (`**bold *ital` → `**bold *ital***`). Off by default: the per-marker passes
+    /// match streamdown/remend, which leave the outer run open there.
+    pub nested_emphasis: bool,
+    /// Treat ANSI CSI escape sequences (`\x1b[` ... final byte, e.g. `\x1b[1;31m` or `\x1b[3~`)
+    /// as opaque, so markers inside them are never balanced. Off by default.
+    ///
+    /// For prose or inline code carrying terminal output; a sequence cut off at the end of the
+    /// text counts up to the end.
+    pub ignore_ansi: bool,
     pub incomplete_link_url: String,
     /// Tail-only scan window for termination logic.
     pub window_bytes: usize,
@@ -22,12 +44,66 @@ impl Default for TerminatorOptions {
             inline_code: true,
             strikethrough: true,
             katex_block: true,
+            emoji_shortcodes: false,
+            strict_flanking: false,
+            nested_emphasis: false,
+            ignore_ansi: false,
             incomplete_link_url: "streamdown:incomplete-link".to_string(),
             window_bytes: 16 * 1024,
         }
     }
 }
 
+impl TerminatorOptions {
+    /// Only close inline code spans and links; emphasis and other markers are left alone.
+    pub fn conservative() -> Self {
+        Self {
+            setext_headings: false,
+            images: false,
+            emphasis: false,
+            strikethrough: false,
+            katex_block: false,
+            ..Self::default()
+        }
+    }
+
+    /// Every termination pass enabled, including opt-in ones like `emoji_shortcodes` and
+    /// `nested_emphasis`.
+    pub fn aggressive() -> Self {
+        Self {
+            emoji_shortcodes: true,
+            nested_emphasis: true,
+            ..Self::default()
+        }
+    }
+
+    /// All passes disabled: `terminate_markdown` returns its input unchanged.
+    pub fn none() -> Self {
+        Self {
+            setext_headings: false,
+            links: false,
+            images: false,
+            emphasis: false,
+            inline_code: false,
+            strikethrough: false,
+            katex_block: false,
+            emoji_shortcodes: false,
+            ..Self::default()
+        }
+    }
+
+    fn any_pass_enabled(&self) -> bool {
+        self.setext_headings
+            || self.links
+            || self.images
+            || self.emphasis
+            || self.inline_code
+            || self.strikethrough
+            || self.katex_block
+            || self.emoji_shortcodes
+    }
+}
+
 fn is_space_or_tab(b: u8) -> bool {
     b == b' ' || b == b'\t'
 }
@@ -38,6 +114,40 @@ fn is_inside_incomplete_multiline_code_block(text: &str) -> bool {
     text.contains('\n') && text.match_indices("```").count() % 2 == 1
 }
 
+fn is_fence_opening_in_progress(text: &str) -> bool {
+    // The last line is a fence header being typed (```` ```rus ````, `~~~py`): not inline markup.
+    let last_line = text.rsplit('\n').next().unwrap_or(text);
+    let indent = last_line.len() - last_line.trim_start_matches(' ').len();
+    if indent > 3 {
+        return false;
+    }
+    let line = &last_line[indent..];
+    let Some(ch) = line.chars().next().filter(|c| matches!(c, '`' | '~')) else {
+        return false;
+    };
+    let run = line.len() - line.trim_start_matches(ch).len();
+    // Backtick fences cannot have backticks in the info string (that would be inline code).
+    run >= 3 && !(ch == '`' && line[run..].contains('`'))
+}
+
+/// Whether the byte at `index` is backslash-escaped (preceded by an odd number of backslashes).
+fn is_escaped(text: &str, index: usize) -> bool {
+    text.as_bytes()[..index]
+        .iter()
+        .rev()
+        .take_while(|&&b| b == b'\\')
+        .count()
+        % 2
+        == 1
+}
+
+/// Byte offsets of the unescaped, non-overlapping occurrences of `marker`, left to right.
+fn unescaped_matches<'a>(text: &'a str, marker: &'a str) -> impl Iterator<Item = usize> + 'a {
+    text.match_indices(marker)
+        .map(|(i, _)| i)
+        .filter(move |&i| !is_escaped(text, i))
+}
+
 fn is_word_char(c: char) -> bool {
     c.is_ascii_alphanumeric() || c == '_' || c.is_alphanumeric()
 }
@@ -82,6 +192,22 @@ fn is_inside_code_block(text: &str, position: usize) -> bool {
     in_inline || in_multiline
 }
 
+/// Whether `text` ends like a previous pass over an unclosed inline code span: an emphasis closer
+/// right before the span's closing backtick (optionally followed by `~~` / `$$` closers).
+///
+/// Emphasis closers are appended before the code span is closed, so they end up inside it; a
+/// second pass would then see the emphasis as still open and close it again.
+fn ends_with_emphasis_closed_inside_code_span(text: &str) -> bool {
+    let t = text.trim_end_matches(['~', '$']);
+    let Some(before_tick) = t.strip_suffix('`') else {
+        return false;
+    };
+    if before_tick.ends_with('`') || !before_tick.ends_with(['*', '_']) {
+        return false;
+    }
+    is_inside_code_block(t, before_tick.len())
+}
+
 fn tail_window(text: &str, window_bytes: usize) -> (&str, usize) {
     if text.len() <= window_bytes {
         return (text, 0);
@@ -95,6 +221,98 @@ fn tail_window(text: &str, window_bytes: usize) -> (&str, usize) {
     (&text[s..], s)
 }
 
+/// Where the terminator window should start so it does not begin inside a code fence.
+///
+/// Fence parity is counted over the whole text: when `window_bytes` cuts into a long code block,
+/// the window alone could look like prose. Returns `offset` if it is outside any fence, the
+/// start of the line after the closing fence if the fence closes inside the window, and `None`
+/// if the fence is still open at the end of the text.
+fn window_start_outside_fence(text: &str, offset: usize) -> Option<usize> {
+    if offset == 0 {
+        return Some(0);
+    }
+    let mut inside = false;
+    for (i, _) in text.match_indices("```") {
+        if i >= offset && !inside {
+            return Some(offset);
+        }
+        inside = !inside;
+        if !inside && i + 3 > offset {
+            return Some(text[i..].find('\n').map_or(text.len(), |n| i + n + 1));
+        }
+    }
+    (!inside).then_some(offset)
+}
+
+/// `text` with every byte of each ANSI CSI sequence replaced by ESC (same length, no markers).
+fn mask_ansi_csi(text: &str) -> String {
+    let mut bytes = text.as_bytes().to_vec();
+    let mut i = 0usize;
+    while i < bytes.len() {
+        if bytes[i] != 0x1b || bytes.get(i + 1) != Some(&b'[') {
+            i += 1;
+            continue;
+        }
+        let start = i;
+        i += 2;
+        // Parameter bytes, then intermediate bytes, then one final byte.
+        while i < bytes.len() && (0x30..=0x3f).contains(&bytes[i]) {
+            i += 1;
+        }
+        while i < bytes.len() && (0x20..=0x2f).contains(&bytes[i]) {
+            i += 1;
+        }
+        if i < bytes.len() && (0x40..=0x7e).contains(&bytes[i]) {
+            i += 1;
+        }
+        bytes[start..i].fill(0x1b);
+    }
+    // Only ASCII bytes were replaced by ASCII.
+    String::from_utf8(bytes).expect("masking keeps UTF-8 valid")
+}
+
+/// `terminate_markdown` with ANSI CSI sequences masked (`TerminatorOptions::ignore_ansi`).
+///
+/// The passes only edit one region of the text (usually its end), so that region is taken from
+/// the masked result and everything around it from `text`.
+fn terminate_ignoring_ansi(text: &str, opts: &TerminatorOptions) -> String {
+    let masked = mask_ansi_csi(text);
+    let inner = TerminatorOptions {
+        ignore_ansi: false,
+        ..opts.clone()
+    };
+    let out = terminate_markdown(&masked, &inner);
+    if out == masked {
+        return text.to_string();
+    }
+    let (a, b) = (masked.as_bytes(), out.as_bytes());
+    let mut prefix = a.iter().zip(b).take_while(|(x, y)| x == y).count();
+    while !(masked.is_char_boundary(prefix) && out.is_char_boundary(prefix)) {
+        prefix -= 1;
+    }
+    let max_suffix = a.len().min(b.len()) - prefix;
+    let mut suffix = a
+        .iter()
+        .rev()
+        .zip(b.iter().rev())
+        .take(max_suffix)
+        .take_while(|(x, y)| x == y)
+        .count();
+    while !masked.is_char_boundary(a.len() - suffix) {
+        suffix -= 1;
+    }
+    let edited = &out[prefix..out.len() - suffix];
+    if edited.contains('\x1b') {
+        // The edit spans a masked sequence: keep the text as it is rather than lose the escape.
+        return text.to_string();
+    }
+    let mut result = String::with_capacity(out.len());
+    result.push_str(&text[..prefix]);
+    result.push_str(edited);
+    result.push_str(&text[text.len() - suffix..]);
+    result
+}
+
 fn is_within_math_block(text: &str, position: usize) -> bool {
     // Toggle on $ and $$, skipping escaped \$.
     let bytes = text.as_bytes();
@@ -202,46 +420,6 @@ fn apply_setext_heading_protection(text: &str) -> String {
     trimmed.to_string()
 }
 
-fn find_matching_open_bracket(text: &str, close_index: usize) -> Option<usize> {
-    let bytes = text.as_bytes();
-    let mut depth = 1usize;
-    let mut i = close_index;
-    while i > 0 {
-        i -= 1;
-        match bytes[i] {
-            b']' => depth += 1,
-            b'[' => {
-                depth -= 1;
-                if depth == 0 {
-                    return Some(i);
-                }
-            }
-            _ => {}
-        }
-    }
-    None
-}
-
-fn find_matching_close_bracket(text: &str, open_index: usize) -> Option<usize> {
-    let bytes = text.as_bytes();
-    let mut depth = 1usize;
-    let mut i = open_index + 1;
-    while i < bytes.len() {
-        match bytes[i] {
-            b'[' => depth += 1,
-            b']' => {
-                depth -= 1;
-                if depth == 0 {
-                    return Some(i);
-                }
-            }
-            _ => {}
-        }
-        i += 1;
-    }
-    None
-}
-
 pub(crate) fn fix_incomplete_link_or_image(
     text: &str,
     incomplete_url: &str,
@@ -408,7 +586,7 @@ fn should_skip_asterisk(text: &str, index: usize) -> bool {
         0
     };
 
-    if prev == b'\\' {
+    if is_escaped(text, index) {
         return true;
     }
 
@@ -479,7 +657,7 @@ fn should_skip_underscore(text: &str, index: usize) -> bool {
         0
     };
 
-    if prev == b'\\' {
+    if is_escaped(text, index) {
         return true;
     }
     if is_inside_code_block(text, index) {
@@ -516,7 +694,7 @@ fn count_single_underscores(text: &str) -> usize {
 
 fn handle_incomplete_bold(text: &str) -> String {
     // boldPattern: /(\*\*)([^*]*?)$/
-    let Some(marker_idx) = text.rfind("**") else {
+    let Some(marker_idx) = unescaped_matches(text, "**").last() else {
         return text.to_string();
     };
     if text[marker_idx + 2..].contains('*') {
@@ -539,7 +717,7 @@ fn handle_incomplete_bold(text: &str) -> String {
         return text.to_string();
     }
 
-    let pairs = text.match_indices("**").count();
+    let pairs = unescaped_matches(text, "**").count();
     if pairs % 2 == 1 {
         let mut out = String::with_capacity(text.len() + 2);
         out.push_str(text);
@@ -551,7 +729,7 @@ fn handle_incomplete_bold(text: &str) -> String {
 
 fn handle_incomplete_double_underscore_italic(text: &str) -> String {
     // italicPattern: /(__)([^_]*?)$/
-    let Some(marker_idx) = text.rfind("__") else {
+    let Some(marker_idx) = unescaped_matches(text, "__").last() else {
         return text.to_string();
     };
     if text[marker_idx + 2..].contains('_') {
@@ -574,7 +752,7 @@ fn handle_incomplete_double_underscore_italic(text: &str) -> String {
         return text.to_string();
     }
 
-    let pairs = text.match_indices("__").count();
+    let pairs = unescaped_matches(text, "__").count();
     if pairs % 2 == 1 {
         let mut out = String::with_capacity(text.len() + 2);
         out.push_str(text);
@@ -597,7 +775,7 @@ fn handle_incomplete_single_asterisk_italic(text: &str) -> String {
         }
         let prev = if i > 0 { bytes[i - 1] } else { 0 };
         let next = if i + 1 < bytes.len() { bytes[i + 1] } else { 0 };
-        if prev == b'*' || next == b'*' || prev == b'\\' {
+        if prev == b'*' || next == b'*' || is_escaped(text, i) {
             continue;
         }
         if text.contains('$') && is_within_math_block(text, i) {
@@ -700,7 +878,7 @@ fn find_first_single_underscore_index(text: &str) -> Option<usize> {
         }
         let prev = if i > 0 { bytes[i - 1] } else { 0 };
         let next = if i + 1 < bytes.len() { bytes[i + 1] } else { 0 };
-        if prev == b'_' || next == b'_' || prev == b'\\' {
+        if prev == b'_' || next == b'_' || is_escaped(text, i) {
             continue;
         }
         if text.contains('$') && is_within_math_block(text, i) {
@@ -765,7 +943,7 @@ fn handle_incomplete_bold_italic(text: &str) -> String {
         return text.to_string();
     }
 
-    let Some(marker_idx) = text.rfind("***") else {
+    let Some(marker_idx) = unescaped_matches(text, "***").last() else {
         return text.to_string();
     };
     if text[marker_idx + 3..].contains('*') {
@@ -795,6 +973,191 @@ fn handle_incomplete_bold_italic(text: &str) -> String {
     text.to_string()
 }
 
+/// Emphasis runs of `text` matched with CommonMark-style flanking rules.
+struct EmphasisScan {
+    /// Unclosed openers, outermost first, as `(marker, run length)`.
+    open: Vec<(u8, usize)>,
+    /// Both `*` and `_` runs take part in emphasis.
+    mixed: bool,
+}
+
+/// Scan the `*`/`_` runs of `text` and pair them up.
+///
+/// Returns `None` when the text ends inside a code span (closers would land inside it) so the
+/// remend-compatible passes handle it. Openers with nothing but whitespace after them are still
+/// being typed and are left out.
+fn scan_emphasis(text: &str) -> Option<EmphasisScan> {
+    let bytes = text.as_bytes();
+    let has_math = text.contains('$');
+    // (marker, run length, byte index after the run)
+    let mut stack: Vec<(u8, usize, usize)> = Vec::new();
+    let mut seen = [false; 2];
+    let mut in_inline = false;
+    let mut in_multiline = false;
+    let mut i = 0usize;
+    while i < bytes.len() {
+        let b = bytes[i];
+        if b == b'\\' {
+            i += 2;
+            continue;
+        }
+        if bytes[i..].starts_with(b"```") {
+            in_multiline = !in_multiline;
+            i += 3;
+            continue;
+        }
+        if b == b'`' {
+            if !in_multiline {
+                in_inline = !in_inline;
+            }
+            i += 1;
+            continue;
+        }
+        if (b != b'*' && b != b'_') || in_inline || in_multiline {
+            i += 1;
+            continue;
+        }
+
+        let start = i;
+        while i < bytes.len() && bytes[i] == b {
+            i += 1;
+        }
+        let run = i - start;
+        if run > 3
+            || (has_math && is_within_math_block(text, start))
+            || is_within_link_or_image_url(text, start)
+            || (b == b'*' && is_list_marker_at(text, start))
+            || is_horizontal_rule_line(text, start, b)
+        {
+            continue;
+        }
+        let prev = text[..start].chars().next_back();
+        let next = text[i..].chars().next();
+        if prev
+            .zip(next)
+            .is_some_and(|(p, n)| is_word_char(p) && is_word_char(n))
+        {
+            continue;
+        }
+        let space = |c: Option<char>| c.is_none_or(char::is_whitespace);
+        let punct = |c: Option<char>| c.is_some_and(|c| c.is_ascii_punctuation());
+        let left_flanking = !space(next) && (!punct(next) || space(prev) || punct(prev));
+        let right_flanking = !space(prev) && (!punct(prev) || space(next) || punct(next));
+        let (can_open, can_close) = if b == b'*' {
+            (left_flanking, right_flanking)
+        } else {
+            (
+                left_flanking && (!right_flanking || punct(prev)),
+                right_flanking && (!left_flanking || punct(next)),
+            )
+        };
+        if can_open || can_close {
+            seen[usize::from(b == b'_')] = true;
+        }
+
+        if can_close && stack.iter().any(|&(m, _, _)| m == b) {
+            // Close the nearest openers of this marker (`***` may close `**` then `*`). Openers
+            // of the other marker in between stay literal.
+            let mut left = run;
+            while left > 0 {
+                let Some(pos) = stack.iter().rposition(|&(m, _, _)| m == b) else {
+                    break;
+                };
+                stack.truncate(pos + 1);
+                if stack[pos].1 <= left {
+                    left -= stack[pos].1;
+                    stack.pop();
+                } else {
+                    stack[pos].1 -= left;
+                    left = 0;
+                }
+            }
+            continue;
+        }
+        if can_open {
+            stack.push((b, run, i));
+        }
+    }
+    if in_inline || in_multiline {
+        return None;
+    }
+    while let Some(&(_, _, after)) = stack.last() {
+        if !text[after..].trim().is_empty() {
+            break;
+        }
+        stack.pop();
+    }
+    Some(EmphasisScan {
+        open: stack.into_iter().map(|(m, n, _)| (m, n)).collect(),
+        mixed: seen == [true, true],
+    })
+}
+
+/// Close nested emphasis openers (`*a **b _c`) innermost first.
+///
+/// The single-marker passes each append their own closer in a fixed order, which scrambles the
+/// nesting (or drops a closer) once several openers, or both `*` and `_`, are in play. Tails
+/// with at most one opener and a single marker kind keep the remend-compatible passes.
+fn close_nested_emphasis(text: &str) -> Option<String> {
+    let scan = scan_emphasis(text)?;
+    if scan.open.len() < 2 && !scan.mixed {
+        return None;
+    }
+    Some(close_emphasis_openers(text, &scan.open))
+}
+
+/// Append closers for `open` (outermost first) to `text`, before any trailing newlines.
+fn close_emphasis_openers(text: &str, open: &[(u8, usize)]) -> String {
+    let body = text.trim_end_matches('\n');
+    let mut out = String::with_capacity(text.len() + open.len() * 3);
+    out.push_str(body);
+    for &(marker, run) in open.iter().rev() {
+        for _ in 0..run {
+            out.push(char::from(marker));
+        }
+    }
+    out.push_str(&text[body.len()..]);
+    out
+}
+
+fn balance_emphasis(text: &str, opts: &TerminatorOptions) -> String {
+    if opts.strict_flanking {
+        // Only runs the flanking scan accepts as openers are closed.
+        if let Some(scan) = scan_emphasis(text) {
+            return close_emphasis_openers(text, &scan.open);
+        }
+    } else if opts.nested_emphasis {
+        if let Some(closed) = close_nested_emphasis(text) {
+            return closed;
+        }
+    }
+    let text = handle_incomplete_bold_italic(text);
+    let text = handle_incomplete_bold(&text);
+    let text = handle_incomplete_double_underscore_italic(&text);
+    let text = handle_incomplete_single_asterisk_italic(&text);
+    handle_incomplete_single_underscore_italic(&text)
+}
+
+/// Byte offset just past the last blank line of `text` outside fenced code and `$$` math blocks,
+/// i.e. where the last paragraph starts (0 if there is no such line).
+fn last_paragraph_start(text: &str) -> usize {
+    let mut start = 0usize;
+    let mut offset = 0usize;
+    let mut in_fence = false;
+    let mut in_math = false;
+    for line in text.split_inclusive('\n') {
+        if line.trim_start_matches([' ', '\t']).starts_with("```") {
+            in_fence = !in_fence;
+        } else if !in_fence && line.matches("$$").count() % 2 == 1 {
+            in_math = !in_math;
+        } else if !in_fence && !in_math && line.ends_with('\n') && line.trim().is_empty() {
+            start = offset + line.len();
+        }
+        offset += line.len();
+    }
+    start
+}
+
 fn balance_inline_code(text: &str) -> String {
     // Inline triple backticks (no newlines): ```code``` or ```code``
     if !text.contains('\n') && text.starts_with("```") {
@@ -827,14 +1190,20 @@ fn balance_inline_code(text: &str) -> String {
         return text.to_string();
     }
 
-    // Match /(`)([^`]*?)$/ for non-triple backticks
+    // Match /(`)([^`]*?)$/ for non-triple backticks. Outside a code span, `\`` is a literal
+    // backtick; inside one, backslashes are literal and every backtick counts.
     let bytes = text.as_bytes();
+    let mut count = 0usize;
     let mut marker_idx = None;
-    for i in (0..bytes.len()).rev() {
-        if bytes[i] == b'`' && !is_part_of_triple_backtick(text, i) {
-            marker_idx = Some(i);
-            break;
+    for (i, b) in bytes.iter().enumerate() {
+        if *b != b'`' || is_part_of_triple_backtick(text, i) {
+            continue;
         }
+        if count % 2 == 0 && is_escaped(text, i) {
+            continue;
+        }
+        count += 1;
+        marker_idx = Some(i);
     }
     let Some(marker_idx) = marker_idx else {
         return text.to_string();
@@ -850,13 +1219,6 @@ fn balance_inline_code(text: &str) -> String {
         return text.to_string();
     }
 
-    // Count single backticks (excluding triple backticks)
-    let mut count = 0usize;
-    for (i, b) in bytes.iter().enumerate() {
-        if *b == b'`' && !is_part_of_triple_backtick(text, i) {
-            count += 1;
-        }
-    }
     if count % 2 == 1 {
         let mut out = String::with_capacity(text.len() + 1);
         out.push_str(text);
@@ -869,7 +1231,7 @@ fn balance_inline_code(text: &str) -> String {
 
 fn balance_strikethrough(text: &str) -> String {
     // /(~~)([^~]*?)$/
-    let Some(marker_idx) = text.rfind("~~") else {
+    let Some(marker_idx) = unescaped_matches(text, "~~").last() else {
         return text.to_string();
     };
     if text[marker_idx + 2..].contains('~') {
@@ -879,7 +1241,7 @@ fn balance_strikethrough(text: &str) -> String {
     if content_after.is_empty() || whitespace_or_markers_only(content_after) {
         return text.to_string();
     }
-    let pairs = text.match_indices("~~").count();
+    let pairs = unescaped_matches(text, "~~").count();
     if pairs % 2 == 1 {
         let mut out = String::with_capacity(text.len() + 2);
         out.push_str(text);
@@ -889,6 +1251,31 @@ fn balance_strikethrough(text: &str) -> String {
     text.to_string()
 }
 
+fn is_shortcode_char(b: u8) -> bool {
+    b.is_ascii_alphanumeric() || matches!(b, b'_' | b'+' | b'-')
+}
+
+fn hide_incomplete_emoji_shortcode(text: &str) -> &str {
+    // Trailing `:word` with no closing colon. The colon must start a word, which rules out
+    // times (`12:3`), ports and schemes (`http://x:8080`), and `Note:`-style labels.
+    let bytes = text.as_bytes();
+    let name_start = bytes
+        .iter()
+        .rposition(|&b| !is_shortcode_char(b))
+        .map_or(0, |i| i + 1);
+    if name_start == bytes.len() || name_start == 0 || bytes[name_start - 1] != b':' {
+        return text;
+    }
+    let colon = name_start - 1;
+    if colon > 0 && !bytes[colon - 1].is_ascii_whitespace() {
+        return text;
+    }
+    if is_inside_code_block(text, colon) {
+        return text;
+    }
+    text[..colon].trim_end_matches([' ', '\t'])
+}
+
 fn balance_katex_block(text: &str) -> String {
     // Streamdown counts $$ pairs outside inline code (`...`), ignoring triple backticks.
     let bytes = text.as_bytes();
@@ -896,6 +1283,11 @@ fn balance_katex_block(text: &str) -> String {
     let mut in_inline_code = false;
     let mut i = 0usize;
     while i + 1 < bytes.len() {
+        if !in_inline_code && bytes[i] == b'\\' {
+            // An escaped `$` or backtick is literal.
+            i += 2;
+            continue;
+        }
         if bytes[i] == b'`' && !is_part_of_triple_backtick(text, i) {
             in_inline_code = !in_inline_code;
             i += 1;
@@ -931,14 +1323,32 @@ fn balance_katex_block(text: &str) -> String {
 
 /// Terminate a streaming Markdown tail to avoid partial rendering artifacts.
 ///
-/// This function is intentionally conservative and only modifies the pending tail.
+/// This function is intentionally conservative and only modifies the pending tail. It is
+/// idempotent: `terminate_markdown(&terminate_markdown(x, o), o) == terminate_markdown(x, o)`.
 pub fn terminate_markdown(text: &str, opts: &TerminatorOptions) -> String {
     if text.is_empty() {
         return String::new();
     }
+    if !opts.any_pass_enabled() {
+        return text.to_string();
+    }
+    if opts.ignore_ansi && text.contains('\x1b') {
+        return terminate_ignoring_ansi(text, opts);
+    }
+
+    if is_fence_opening_in_progress(text) {
+        // A fence header being typed (including a partial `{...}` attribute block) is left as-is:
+        // its info string is not inline Markdown, and trimming it would make it jump.
+        return text.to_string();
+    }
 
     let text = trim_trailing_single_space(text);
-    let (window, offset) = tail_window(text, opts.window_bytes);
+    let (_, offset) = tail_window(text, opts.window_bytes);
+    let Some(offset) = window_start_outside_fence(text, offset) else {
+        // The window lies inside a code fence that is still open: nothing to terminate.
+        return text.to_string();
+    };
+    let window = &text[offset..];
 
     // Work on the tail window but keep a stable prefix.
     let prefix = &text[..offset];
@@ -956,11 +1366,26 @@ pub fn terminate_markdown(text: &str, opts: &TerminatorOptions) -> String {
         return out;
     }
 
+    if opts.emoji_shortcodes {
+        let visible = hide_incomplete_emoji_shortcode(&tail);
+        if visible.len() != tail.len() {
+            tail = visible.to_string();
+        }
+    }
+
     if opts.links || opts.images {
+        let placeholder = format!("]({})", opts.incomplete_link_url);
+        if tail.ends_with(&placeholder) {
+            // Output of a previous pass: the placeholder is only ever appended last.
+            let mut out = String::with_capacity(prefix.len() + tail.len());
+            out.push_str(prefix);
+            out.push_str(&tail);
+            return out;
+        }
         if let Some(processed) =
             fix_incomplete_link_or_image(&tail, &opts.incomplete_link_url, opts.links, opts.images)
         {
-            if processed.ends_with(&format!("]({})", opts.incomplete_link_url)) {
+            if processed.ends_with(&placeholder) {
                 let mut out = String::with_capacity(prefix.len() + processed.len());
                 out.push_str(prefix);
                 out.push_str(&processed);
@@ -971,11 +1396,16 @@ pub fn terminate_markdown(text: &str, opts: &TerminatorOptions) -> String {
     }
 
     if opts.emphasis {
-        tail = handle_incomplete_bold_italic(&tail);
-        tail = handle_incomplete_bold(&tail);
-        tail = handle_incomplete_double_underscore_italic(&tail);
-        tail = handle_incomplete_single_asterisk_italic(&tail);
-        tail = handle_incomplete_single_underscore_italic(&tail);
+        // Emphasis cannot span a blank line: only balance the last paragraph.
+        let para_start = last_paragraph_start(&tail);
+        let para = &tail[para_start..];
+        if !ends_with_emphasis_closed_inside_code_span(para) {
+            let closed = balance_emphasis(para, opts);
+            if closed != para {
+                tail.truncate(para_start);
+                tail.push_str(&closed);
+            }
+        }
     }
     if opts.inline_code {
         tail = balance_inline_code(&tail);
@@ -8,6 +8,11 @@ fn strip_up_to_three_leading_spaces(line: &str) -> &str {
     s
 }
 
+/// CommonMark label normalization: trim, collapse internal whitespace, case-fold.
+///
+/// The single implementation behind both the stream's invalidation index (usages and
+/// definitions) and the pulldown adapter's definition store, so the two always agree on which
+/// labels match.
 pub(crate) fn normalize_reference_label(label: &str) -> Option<String> {
     let trimmed = label.trim();
     if trimmed.is_empty() {
@@ -27,13 +32,47 @@ pub(crate) fn normalize_reference_label(label: &str) -> Option<String> {
             out.push(' ');
         }
         last_was_ws = false;
-        for lc in ch.to_lowercase() {
-            out.push(lc);
-        }
+        push_case_folded(&mut out, ch);
     }
     if out.is_empty() { None } else { Some(out) }
 }
 
+/// Unicode full case folding (as used by CommonMark label matching), without a table dependency.
+///
+/// `char::to_lowercase` already matches case folding for almost every character; the exceptions
+/// are expansions (`ß` -> `ss`, ligatures) and characters whose fold differs from their
+/// lowercase form (final sigma, Greek symbol variants, long s).
+fn push_case_folded(out: &mut String, ch: char) {
+    for lc in ch.to_lowercase() {
+        let folded = match lc {
+            'ß' => "ss",
+            'ſ' => "s",
+            'ς' => "σ",
+            'ϐ' => "β",
+            'ϑ' => "θ",
+            'ϕ' => "φ",
+            'ϖ' => "π",
+            'ϰ' => "κ",
+            'ϱ' => "ρ",
+            'ϵ' => "ε",
+            'ẛ' => "ṡ",
+            '\u{345}' => "ι",
+            'ŉ' => "ʼn",
+            'ﬀ' => "ff",
+            'ﬁ' => "fi",
+            'ﬂ' => "fl",
+            'ﬃ' => "ffi",
+            'ﬄ' => "ffl",
+            'ﬅ' | 'ﬆ' => "st",
+            _ => {
+                out.push(lc);
+                continue;
+            }
+        };
+        out.push_str(folded);
+    }
+}
+
 pub(crate) fn extract_reference_definition_label(line: &str) -> Option<String> {
     // CommonMark-ish reference definition, single line only:
     // up to 3 leading spaces, then "[label]:"
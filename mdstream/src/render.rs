@@ -0,0 +1,76 @@
+//! Terminal-style wrapping helpers for block displays (requires the `render` feature).
+//!
+//! Widths are measured in terminal columns with `unicode-width`: CJK and other wide characters
+//! count as two columns, and zero-width characters (combining marks, variation selectors, the
+//! parts of a ZWJ sequence) stay attached to the character before them, so a line is never broken
+//! inside a grapheme.
+
+use alloc::string::{String, ToString};
+use alloc::vec::Vec;
+
+use unicode_width::{UnicodeWidthChar, UnicodeWidthStr};
+
+use crate::types::BlockKind;
+
+/// Whether lines of this block kind should be wrapped (prose) or kept as-is (code, tables, HTML,
+/// math), where a hard break would change the meaning or the alignment.
+pub fn wraps_block_kind(kind: BlockKind) -> bool {
+    !matches!(
+        kind,
+        BlockKind::CodeFence | BlockKind::Table | BlockKind::HtmlBlock | BlockKind::MathBlock
+    )
+}
+
+/// Display width of `text` in terminal columns.
+pub fn display_width(text: &str) -> usize {
+    UnicodeWidthStr::width(text)
+}
+
+/// Splits `text` into output lines of at most `width` columns, wrapping only prose kinds.
+///
+/// Each source line (without its newline) yields at least one output line, so blank lines are
+/// kept. Lines of non-wrapping kinds (see `wraps_block_kind`) are returned unchanged and may be
+/// wider than `width`. A `width` of 0 disables wrapping.
+pub fn wrap_block_display(kind: BlockKind, text: &str, width: usize) -> Vec<String> {
+    let mut out = Vec::new();
+    for line in text.lines() {
+        if width == 0 || !wraps_block_kind(kind) {
+            out.push(line.to_string());
+        } else {
+            wrap_line_into(line, width, &mut out);
+        }
+    }
+    out
+}
+
+/// Hard-wraps one line at `width` columns (no word boundaries; a single character wider than
+/// `width` gets a line of its own). A `width` of 0 returns the line unchanged.
+pub fn wrap_line(line: &str, width: usize) -> Vec<String> {
+    let mut out = Vec::new();
+    if width == 0 {
+        out.push(line.to_string());
+    } else {
+        wrap_line_into(line, width, &mut out);
+    }
+    out
+}
+
+fn wrap_line_into(line: &str, width: usize, out: &mut Vec<String>) {
+    let mut cur = String::new();
+    let mut cur_w = 0usize;
+    let mut after_zwj = false;
+    for ch in line.chars() {
+        let w = UnicodeWidthChar::width(ch).unwrap_or(0);
+        let joins_previous = w == 0 || after_zwj;
+        if !joins_previous && cur_w + w > width && !cur.is_empty() {
+            out.push(core::mem::take(&mut cur));
+            cur_w = 0;
+        }
+        cur.push(ch);
+        if !after_zwj {
+            cur_w += w;
+        }
+        after_zwj = ch == '\u{200d}';
+    }
+    out.push(cur);
+}
@@ -1,3 +1,5 @@
+use alloc::vec::Vec;
+
 use crate::types::{AppliedUpdate, Block, BlockId, Update};
 
 /// A UI-friendly document state container for streaming Markdown.
@@ -30,10 +32,20 @@ impl DocumentState {
         self.pending.as_mut()
     }
 
+    /// Committed blocks in order, then the pending block (if any), as a renderer draws them.
     pub fn blocks(&self) -> impl Iterator<Item = &Block> {
         self.committed.iter().chain(self.pending.iter())
     }
 
+    /// Number of blocks `blocks` yields: committed plus the pending one.
+    pub fn len(&self) -> usize {
+        self.committed.len() + usize::from(self.pending.is_some())
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.committed.is_empty() && self.pending.is_none()
+    }
+
     pub fn clear(&mut self) {
         self.committed.clear();
         self.pending = None;
@@ -51,3 +63,45 @@ impl DocumentState {
         self.committed.iter_mut().find(|b| b.id == id)
     }
 }
+
+/// An ordered recording of the `Update`s a stream produced, for offline replay.
+///
+/// With the `serde` feature the whole log (de)serializes with any serde format. Borrowed
+/// `UpdateRef`s are not serializable; record `UpdateRef::to_owned()` instead.
+#[derive(Debug, Default, Clone, PartialEq, Eq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+#[cfg_attr(feature = "serde", serde(transparent))]
+pub struct UpdateLog {
+    updates: Vec<Update>,
+}
+
+impl UpdateLog {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    pub fn push(&mut self, update: Update) {
+        self.updates.push(update);
+    }
+
+    pub fn updates(&self) -> &[Update] {
+        &self.updates
+    }
+
+    pub fn len(&self) -> usize {
+        self.updates.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.updates.is_empty()
+    }
+
+    /// Apply every recorded update, in order, to a fresh `DocumentState`.
+    pub fn replay(&self) -> DocumentState {
+        let mut state = DocumentState::new();
+        for update in &self.updates {
+            state.apply(update.clone());
+        }
+        state
+    }
+}
@@ -1,22 +1,41 @@
-use std::collections::{HashMap, HashSet};
-
+use alloc::borrow::Cow;
+use alloc::boxed::Box;
+use alloc::string::{String, ToString};
+use alloc::vec;
+use alloc::vec::Vec;
+
+mod callbacks;
+mod checkpoint;
 mod compaction;
-mod footnotes;
-mod html;
+mod diagnostics;
+mod edit;
+pub(crate) mod footnotes;
+pub(crate) mod html;
+mod instrument;
 mod lines;
+mod messages;
+#[cfg(feature = "std")]
 mod refs;
+mod trace;
 
+use self::callbacks::{CommitCallback, PendingCallback};
+pub use self::checkpoint::MdStreamCheckpoint;
+pub use self::edit::ReplaceError;
 use self::footnotes::{detect_footnotes, is_footnote_continuation, is_footnote_definition_start};
-use self::html::{html_block_start_state, update_html_block_state};
+use self::html::{has_text_after_last_tag, html_block_start_state, update_html_block_state};
 use self::lines::{Line, take_prefix_at_char_boundary, update_tail};
-use self::refs::extract_reference_usages;
+#[cfg(feature = "std")]
+use self::refs::ReferenceUsageIndex;
+use self::trace::TraceSite;
 
 use crate::boundary::{BoundaryPlugin, BoundaryUpdate};
-use crate::options::{FootnotesMode, Options, ReferenceDefinitionsMode};
+use crate::options::{FootnotesMode, Options};
 use crate::pending::terminate_markdown;
-use crate::reference::extract_reference_definition_label;
+use crate::table::{is_table_delimiter_row, is_table_row};
 use crate::transform::{PendingTransformInput, PendingTransformer};
-use crate::types::{Block, BlockId, BlockKind, BlockStatus, PendingBlockRef, Update, UpdateRef};
+use crate::types::{
+    Block, BlockId, BlockKind, BlockStatus, Diagnostic, PendingBlockRef, Update, UpdateRef,
+};
 
 #[derive(Debug, Clone)]
 enum BlockMode {
@@ -32,26 +51,42 @@ enum BlockMode {
         plugin_index: usize,
         started: bool,
     },
-    List,
-    BlockQuote,
+    List {
+        /// Fence opened inside a list item (`- ````` or an indented ````` line), not closed yet.
+        fence: Option<(char, usize)>,
+    },
+    BlockQuote {
+        /// Fence opened by a quoted line (`> `````) that has not been closed yet.
+        fence: Option<(char, usize)>,
+    },
     HtmlBlock {
         stack: Vec<String>,
         in_comment: bool,
+        /// Text followed the tags on a line that closed them (`<div>x</div> more`): the block now
+        /// runs to the next blank line, as CommonMark HTML blocks do.
+        to_blank_line: bool,
     },
     Table,
     MathBlock {
         open_count: usize,
     },
     FootnoteDefinition,
+    DefinitionList,
 }
 
 fn is_empty_line(line: &str) -> bool {
     line.trim().is_empty()
 }
 
-fn is_heading(line: &str) -> bool {
+/// Level of an ATX heading opener: 1..=`max_level` `#` followed by a space/tab or the line end.
+fn atx_heading_level(line: &str, max_level: u8) -> Option<usize> {
     let trimmed = line.trim_start();
-    trimmed.starts_with('#') && trimmed[1..].starts_with([' ', '\t', '#'])
+    let level = trimmed.bytes().take_while(|&b| b == b'#').count();
+    if level == 0 || level > usize::from(max_level) {
+        return None;
+    }
+    let rest = &trimmed[level..];
+    (rest.is_empty() || rest.starts_with([' ', '\t'])).then_some(level)
 }
 
 fn thematic_break_char(line: &str) -> Option<char> {
@@ -91,46 +126,41 @@ fn is_thematic_break(line: &str) -> bool {
 }
 
 fn setext_underline_char(line: &str) -> Option<char> {
-    // Best-effort setext underline:
-    // - up to 3 leading spaces
-    // - '=' or '-' repeated >= 2
-    // - spaces/tabs may appear between markers
-    // - no other characters
+    // Setext underline (CommonMark):
+    // - up to 3 leading spaces, trailing spaces/tabs allowed
+    // - a run of '=' or '-' with no internal spaces (`- - -` is a thematic break)
+    // - a single '-' is not accepted: while streaming it may still become a list marker
     let mut s = line;
     let mut spaces = 0usize;
     while spaces < 3 && s.starts_with(' ') {
         s = &s[1..];
         spaces += 1;
     }
-    let s = s.trim_end_matches([' ', '\t']);
-    let mut it = s.chars();
-    let first = it.next()?;
+    let s = s.trim_end_matches([' ', '\t', '\n', '\r']);
+    let first = s.chars().next()?;
     if first != '=' && first != '-' {
         return None;
     }
-    let mut count = 1usize;
-    for c in it {
-        if c == first {
-            count += 1;
-            continue;
-        }
-        if c == ' ' || c == '\t' {
-            continue;
-        }
+    if !s.chars().all(|c| c == first) {
         return None;
     }
-    if count >= 2 { Some(first) } else { None }
+    if first == '-' && s.len() < 2 {
+        return None;
+    }
+    Some(first)
 }
 
-fn fence_start(line: &str) -> Option<(char, usize)> {
+/// Opening code fence of at least `min_len` (and never fewer than 3) backticks or tildes.
+fn fence_start(line: &str, min_len: usize) -> Option<(char, usize)> {
     let mut s = line;
     let mut spaces = 0usize;
     while spaces < 3 && s.starts_with(' ') {
         s = &s[1..];
         spaces += 1;
     }
+    let min_len = min_len.max(3);
     let bytes = s.as_bytes();
-    if bytes.len() < 3 {
+    if bytes.len() < min_len {
         return None;
     }
     let ch = bytes[0] as char;
@@ -141,7 +171,7 @@ fn fence_start(line: &str) -> Option<(char, usize)> {
     while len < bytes.len() && bytes[len] == bytes[0] {
         len += 1;
     }
-    if len < 3 {
+    if len < min_len {
         return None;
     }
     Some((ch, len))
@@ -158,6 +188,15 @@ fn fence_end(line: &str, fence_char: char, fence_len: usize) -> bool {
     trimmed.chars().all(|c| c == fence_char) && trimmed.chars().count() >= fence_len
 }
 
+/// Whether a code fence block's last line (after the opening line) closes it.
+fn code_fence_block_is_closed(raw: &str, fence_char: char, fence_len: usize) -> bool {
+    let mut lines = raw.trim_end_matches('\n').split('\n');
+    let _ = lines.next();
+    lines
+        .next_back()
+        .is_some_and(|last| fence_end(last, fence_char, fence_len))
+}
+
 fn code_fence_suffix(raw_ended_with_newline: bool, fence_char: char, fence_len: usize) -> String {
     let mut out = String::new();
     if !raw_ended_with_newline {
@@ -170,13 +209,81 @@ fn code_fence_suffix(raw_ended_with_newline: bool, fence_char: char, fence_len:
     out
 }
 
+/// A pending code fence cut down to its opening line, a `… N more lines …` marker and its last
+/// `keep` lines, or `None` when its body has at most `keep` lines.
+fn truncated_code_fence_raw(raw: &str, keep: usize) -> Option<String> {
+    let body_start = raw.find('\n')? + 1;
+    let body = &raw[body_start..];
+    let total = body.split_inclusive('\n').count();
+    if total <= keep {
+        return None;
+    }
+    let tail_len: usize = body
+        .split_inclusive('\n')
+        .rev()
+        .take(keep)
+        .map(str::len)
+        .sum();
+    let hidden = total - keep;
+    let mut out = String::with_capacity(body_start + tail_len + 32);
+    out.push_str(&raw[..body_start]);
+    out.push_str(&alloc::format!("\u{2026} {hidden} more lines \u{2026}\n"));
+    out.push_str(&body[body.len() - tail_len..]);
+    Some(out)
+}
+
+/// Append `text` to `out`, expanding tabs to the next multiple of `tab_width` columns.
+///
+/// `out` may already hold text; columns continue from its last line.
+fn push_tab_expanded(out: &mut String, text: &str, tab_width: usize) {
+    let tab_width = tab_width.max(1);
+    let mut col = out.rsplit('\n').next().map_or(0, |l| l.chars().count());
+    for c in text.chars() {
+        match c {
+            '\t' => {
+                let n = tab_width - col % tab_width;
+                out.extend(core::iter::repeat_n(' ', n));
+                col += n;
+            }
+            '\n' => {
+                out.push(c);
+                col = 0;
+            }
+            _ => {
+                out.push(c);
+                col += 1;
+            }
+        }
+    }
+}
+
 fn is_blockquote_start(line: &str) -> bool {
     let trimmed = line.trim_start();
     trimmed.starts_with('>')
 }
 
-fn is_list_item_start(line: &str) -> bool {
+/// Strip (possibly nested) blockquote markers so nested block syntax can be recognized.
+fn strip_blockquote_markers(line: &str) -> &str {
+    let mut s = line;
+    loop {
+        let trimmed = s.trim_start_matches(' ');
+        if s.len() - trimmed.len() > 3 {
+            return s;
+        }
+        let Some(rest) = trimmed.strip_prefix('>') else {
+            return s;
+        };
+        s = rest.strip_prefix([' ', '\t']).unwrap_or(rest);
+    }
+}
+
+/// List item marker: `-`/`+`/`*`, `1.`/`1)`, or one of `extra_markers`
+/// (`Options::extra_list_markers`), followed by whitespace.
+fn is_list_item_start(line: &str, extra_markers: &[char]) -> bool {
     let s = line.trim_start();
+    if let Some(c) = s.chars().next().filter(|c| extra_markers.contains(c)) {
+        return s[c.len_utf8()..].starts_with([' ', '\t']);
+    }
     if s.len() < 2 {
         return false;
     }
@@ -198,37 +305,79 @@ fn is_list_item_start(line: &str) -> bool {
     }
 }
 
-fn is_list_continuation(line: &str) -> bool {
-    // Best-effort continuation line for lists:
-    // - indented content (>=2 spaces or a tab)
-    // - or a nested list item starter
-    if is_list_item_start(line) {
-        return true;
+/// Line content after a list item marker (`- `, `1. `), or the line itself if it has none.
+fn strip_list_item_marker<'a>(line: &'a str, extra_markers: &[char]) -> &'a str {
+    if !is_list_item_start(line, extra_markers) {
+        return line;
     }
-    let bytes = line.as_bytes();
-    if bytes.first() == Some(&b'\t') {
-        return true;
+    let s = line.trim_start();
+    let marker_len = s
+        .find([' ', '\t'])
+        .expect("list item start has whitespace after the marker");
+    &s[marker_len..]
+}
+
+/// CommonMark: only non-empty bullet items and ordered items starting at 1 interrupt a paragraph.
+fn list_item_can_interrupt_paragraph(line: &str, extra_markers: &[char]) -> bool {
+    let s = strip_list_item_marker(line, extra_markers);
+    if s.trim().is_empty() {
+        return false;
     }
-    let mut spaces = 0usize;
-    for &b in bytes {
-        if b == b' ' {
-            spaces += 1;
-            if spaces >= 2 {
-                return true;
-            }
-            continue;
+    let marker = line.trim_start();
+    !marker.starts_with(|c: char| c.is_ascii_digit())
+        || marker.starts_with("1.")
+        || marker.starts_with("1)")
+}
+
+fn indent_width(line: &str) -> usize {
+    // Leading indentation in columns (tabs advance to the next multiple of 4).
+    let mut width = 0usize;
+    for b in line.bytes() {
+        match b {
+            b' ' => width += 1,
+            b'\t' => width += 4 - width % 4,
+            _ => break,
         }
-        break;
     }
-    false
+    width
+}
+
+fn is_list_continuation(line: &str, min_indent: usize, extra_markers: &[char]) -> bool {
+    // Best-effort continuation line for lists:
+    // - indented content (>= `min_indent` columns)
+    // - or a nested list item starter
+    is_list_item_start(line, extra_markers) || indent_width(line) >= min_indent.max(1)
+}
+
+fn is_definition_line(line: &str) -> bool {
+    // Pandoc-style definition marker: up to 3 leading spaces, ':' and whitespace.
+    let mut s = line;
+    let mut spaces = 0usize;
+    while spaces < 3 && s.starts_with(' ') {
+        s = &s[1..];
+        spaces += 1;
+    }
+    let Some(rest) = s.strip_prefix(':') else {
+        return false;
+    };
+    // Streaming-only: a bare ':' may still become a marker with more input.
+    rest.is_empty() || rest.starts_with([' ', '\t'])
 }
 
-fn is_list_item_start_prefix(line: &str) -> bool {
+fn is_indented_continuation(line: &str) -> bool {
+    // Indented content (>=2 spaces or a tab) continues the current definition.
+    line.starts_with('\t') || line.starts_with("  ")
+}
+
+fn is_list_item_start_prefix(line: &str, extra_markers: &[char]) -> bool {
     // Streaming-only heuristic: treat certain "prefix" lines as potential list item starters.
     // This prevents premature block commits when the marker is split across chunks (e.g. "-" then " item").
     let s = line.trim_start();
-    if s.is_empty() {
+    let Some(first) = s.chars().next() else {
         return false;
+    };
+    if extra_markers.contains(&first) {
+        return s.len() == first.len_utf8();
     }
     let bytes = s.as_bytes();
     match bytes[0] {
@@ -298,13 +447,28 @@ pub struct MdStream {
     footnote_scan_tail: String,
     pending_cr: bool,
     last_finalized_buffer_len: usize,
-
-    reference_usage_index: HashMap<String, HashSet<BlockId>>,
+    /// Absolute stream offset of `buffer[0]` (bytes dropped by compaction so far).
+    buffer_base_offset: usize,
+    /// Input held back because it may be the start of a split `Options::message_terminator`.
+    message_terminator_tail: String,
+    commit_callback: Option<CommitCallback>,
+    pending_callback: Option<PendingCallback>,
+
+    #[cfg(feature = "std")]
+    reference_usage_index: ReferenceUsageIndex,
 }
 
+/// Bytes of recent input kept to detect footnote markers split across chunks.
+const FOOTNOTE_SCAN_TAIL: usize = 256;
+
 struct AppendCtx<'a> {
     committed_out: Option<&'a mut Vec<Block>>,
     invalidated: Vec<BlockId>,
+    reemitted: Vec<Block>,
+    diagnostics: Vec<Diagnostic>,
+    finished_messages: Vec<Vec<Block>>,
+    lines_processed: usize,
+    whitespace_fast_path: bool,
     reset: bool,
 }
 
@@ -313,6 +477,11 @@ impl<'a> AppendCtx<'a> {
         Self {
             committed_out,
             invalidated: Vec::new(),
+            reemitted: Vec::new(),
+            diagnostics: Vec::new(),
+            finished_messages: Vec::new(),
+            lines_processed: 0,
+            whitespace_fast_path: false,
             reset: false,
         }
     }
@@ -331,8 +500,8 @@ struct PendingInfo {
     raw_start: usize,
 }
 
-impl std::fmt::Debug for MdStream {
-    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+impl core::fmt::Debug for MdStream {
+    fn fmt(&self, f: &mut core::fmt::Formatter<'_>) -> core::fmt::Result {
         f.debug_struct("MdStream")
             .field("buffer_len", &self.buffer.len())
             .field("lines_len", &self.lines.len())
@@ -354,6 +523,8 @@ impl std::fmt::Debug for MdStream {
             .field("active_boundary_plugin", &self.active_boundary_plugin)
             .field("footnotes_detected", &self.footnotes_detected)
             .field("last_finalized_buffer_len", &self.last_finalized_buffer_len)
+            .field("commit_callback", &self.commit_callback.is_some())
+            .field("pending_callback", &self.pending_callback.is_some())
             .finish()
     }
 }
@@ -386,10 +557,27 @@ impl MdStream {
             footnote_scan_tail: String::new(),
             pending_cr: false,
             last_finalized_buffer_len: 0,
-            reference_usage_index: HashMap::new(),
+            buffer_base_offset: 0,
+            message_terminator_tail: String::new(),
+            commit_callback: None,
+            pending_callback: None,
+            #[cfg(feature = "std")]
+            reference_usage_index: ReferenceUsageIndex::default(),
         }
     }
 
+    /// Like `new`, but pre-allocates for a document of roughly `expected_bytes` / `expected_lines`.
+    ///
+    /// Capacity is only a hint (useful when the whole document is already in memory); the
+    /// committed list reserves one block per four expected lines.
+    pub fn with_capacity(opts: Options, expected_bytes: usize, expected_lines: usize) -> Self {
+        let mut s = Self::new(opts);
+        s.buffer.reserve(expected_bytes);
+        s.lines.reserve(expected_lines);
+        s.committed.reserve(expected_lines / 4);
+        s
+    }
+
     /// Construct a stream with Streamdown-compatible defaults for incomplete links/images.
     ///
     /// This keeps the built-in terminator for emphasis/inline code/etc, but delegates incomplete
@@ -454,6 +642,74 @@ impl MdStream {
         &self.buffer
     }
 
+    /// Stream offset up to which content has been committed (or skipped as blank lines).
+    ///
+    /// Counted in bytes of normalized input since creation or the last reset, so it keeps growing
+    /// across buffer compaction. After `finalize` it equals the total input length; compare it with
+    /// a known document length to drive a progress bar.
+    pub fn committed_byte_offset(&self) -> usize {
+        let local = if self.finalized_without_new_input() {
+            self.buffer.len()
+        } else {
+            self.lines
+                .get(self.current_block_start_line)
+                .map_or(self.buffer.len(), |line| line.start)
+        };
+        self.buffer_base_offset + local
+    }
+
+    /// Bytes currently held in the buffer (shrinks when `Options::max_buffer_bytes` compacts it).
+    pub fn buffered_byte_len(&self) -> usize {
+        self.buffer.len()
+    }
+
+    /// Whether footnotes were detected (only scanned for in `FootnotesMode::SingleBlock`).
+    pub fn footnotes_detected(&self) -> bool {
+        self.footnotes_detected
+    }
+
+    pub fn first_committed_block(&self) -> Option<&Block> {
+        self.committed.first()
+    }
+
+    pub fn last_committed_block(&self) -> Option<&Block> {
+        self.committed.last()
+    }
+
+    /// Look up a committed block by id in O(log n) (committed ids are strictly increasing).
+    pub fn committed_block_by_id(&self, id: BlockId) -> Option<&Block> {
+        let idx = self.committed.binary_search_by_key(&id, |b| b.id).ok()?;
+        Some(&self.committed[idx])
+    }
+
+    /// The `raw` of the pending block, as `Update::pending` carries it, without copying it.
+    pub fn pending_raw(&self) -> Option<&str> {
+        if self.finalized_without_new_input() {
+            return None;
+        }
+        let info = self.current_pending_info()?;
+        Some(&self.buffer[info.raw_start..])
+    }
+
+    /// The terminated `display` of the pending block, as `Update::pending` carries it.
+    ///
+    /// Mutable because the display is computed and cached on the first call after an append; later
+    /// calls only clone it. `None` without a pending block or with `compute_pending_display` off.
+    pub fn pending_display(&mut self) -> Option<String> {
+        if self.finalized_without_new_input() || self.current_pending_info().is_none() {
+            return None;
+        }
+        match &self.pending_display_cache {
+            Some(cached) => Some(cached.clone()),
+            None => self.current_pending_block()?.display,
+        }
+    }
+
+    /// Nothing was appended since creation or since the last `finalize` committed everything.
+    fn finalized_without_new_input(&self) -> bool {
+        !self.pending_cr && self.buffer.len() == self.last_finalized_buffer_len
+    }
+
     pub fn snapshot_blocks(&mut self) -> Vec<Block> {
         let mut blocks = self.committed.clone();
         // Pending is computed without mutating structural state, but pending transformers may
@@ -464,6 +720,23 @@ impl MdStream {
         blocks
     }
 
+    /// Append a complete document, finalize, and iterate over every committed block in order.
+    ///
+    /// This is the batch counterpart of `append` + `finalize` for callers that already have the
+    /// full text. Configured plugins and transformers apply as usual. Blocks invalidated by a
+    /// `reset` (e.g. footnote single-block mode) are dropped, so the result matches what a
+    /// `DocumentState` would hold after applying both updates.
+    pub fn parse_complete(&mut self, text: &str) -> alloc::vec::IntoIter<Block> {
+        let mut blocks = Vec::new();
+        for update in [self.append(text), self.finalize()] {
+            if update.reset {
+                blocks.clear();
+            }
+            blocks.extend(update.committed);
+        }
+        blocks.into_iter()
+    }
+
     fn start_mode_for_line(&self, line: &str) -> BlockMode {
         if let Some(idx) = self
             .boundary_plugins
@@ -475,13 +748,13 @@ impl MdStream {
                 started: false,
             };
         }
-        if is_heading(line) {
+        if atx_heading_level(line, self.opts.heading_max_level).is_some() {
             return BlockMode::Heading;
         }
         if is_thematic_break(line) {
             return BlockMode::ThematicBreak;
         }
-        if let Some((ch, len)) = fence_start(line) {
+        if let Some((ch, len)) = fence_start(line, self.opts.code_fence_min_len) {
             return BlockMode::CodeFence {
                 fence_char: ch,
                 fence_len: len,
@@ -491,16 +764,20 @@ impl MdStream {
             return BlockMode::FootnoteDefinition;
         }
         if is_blockquote_start(line) {
-            return BlockMode::BlockQuote;
+            return BlockMode::BlockQuote { fence: None };
         }
-        if is_list_item_start(line) {
-            return BlockMode::List;
+        if is_list_item_start(line, &self.opts.extra_list_markers) {
+            return BlockMode::List { fence: None };
         }
         if let Some((stack, in_comment)) = html_block_start_state(line) {
-            return BlockMode::HtmlBlock { stack, in_comment };
+            return BlockMode::HtmlBlock {
+                stack,
+                in_comment,
+                to_blank_line: false,
+            };
         }
-        let dollars = count_double_dollars(line);
-        if dollars % 2 == 1 && line.trim_start().starts_with("$$") {
+        // Check the cheap prefix first: counting scans the whole (possibly huge) line.
+        if line.trim_start().starts_with("$$") && count_double_dollars(line) % 2 == 1 {
             // `open_count` is tracked via `update_mode_with_line`, including the opening line.
             return BlockMode::MathBlock { open_count: 0 };
         }
@@ -514,12 +791,13 @@ impl MdStream {
             BlockMode::ThematicBreak => BlockKind::ThematicBreak,
             BlockMode::CodeFence { .. } => BlockKind::CodeFence,
             BlockMode::CustomBoundary { .. } => BlockKind::Unknown,
-            BlockMode::List => BlockKind::List,
-            BlockMode::BlockQuote => BlockKind::BlockQuote,
+            BlockMode::List { .. } => BlockKind::List,
+            BlockMode::BlockQuote { .. } => BlockKind::BlockQuote,
             BlockMode::HtmlBlock { .. } => BlockKind::HtmlBlock,
             BlockMode::Table => BlockKind::Table,
             BlockMode::MathBlock { .. } => BlockKind::MathBlock,
             BlockMode::FootnoteDefinition => BlockKind::FootnoteDefinition,
+            BlockMode::DefinitionList => BlockKind::DefinitionList,
             BlockMode::Unknown => BlockKind::Unknown,
         }
     }
@@ -538,73 +816,79 @@ impl MdStream {
         }
 
         let raw = self.buffer[start_off..end_off].to_string();
-        if raw.trim().is_empty() {
+        if raw.trim().is_empty() && !self.opts.emit_blank_blocks {
             // Never emit whitespace-only blocks. Keep stable behavior by advancing the block cursor.
             self.current_block_start_line = end_line_inclusive + 1;
             self.current_block_id = BlockId(self.next_block_id);
             self.next_block_id += 1;
-            self.current_mode = BlockMode::Unknown;
+            self.set_mode(end_line_inclusive, TraceSite::Commit, BlockMode::Unknown);
             self.active_boundary_plugin = None;
             self.pending_display_cache = None;
             self.pending_display_cache_suffix = None;
             return;
         }
-        let block = Block {
+        let kind = if raw.trim().is_empty() {
+            BlockKind::Blank
+        } else {
+            Self::kind_for_mode(&self.current_mode)
+        };
+        let mut block = Block {
             id: self.current_block_id,
             status: BlockStatus::Committed,
-            kind: Self::kind_for_mode(&self.current_mode),
+            kind,
             raw,
             display: None,
         };
+        if self.opts.commit_with_display {
+            block.display = self.committed_display(block.kind, &block.raw);
+        }
+        if self.opts.diagnostics {
+            self.diagnose_committed_block(&block, start_off, ctx);
+        }
         self.push_committed_block(block, ctx);
 
         self.current_block_start_line = end_line_inclusive + 1;
         self.current_block_id = BlockId(self.next_block_id);
         self.next_block_id += 1;
-        self.current_mode = BlockMode::Unknown;
+        self.set_mode(end_line_inclusive, TraceSite::Commit, BlockMode::Unknown);
         self.active_boundary_plugin = None;
         self.pending_display_cache = None;
         self.pending_display_cache_suffix = None;
     }
 
-    fn push_committed_block(&mut self, block: Block, ctx: &mut AppendCtx<'_>) {
-        // Index usages for invalidation-based adapters.
-        if block.kind != BlockKind::CodeFence && block.raw.contains('[') {
-            let used = extract_reference_usages(&block.raw);
-            if !used.is_empty() {
-                for label in used {
-                    self.reference_usage_index
-                        .entry(label)
-                        .or_default()
-                        .insert(block.id);
+    /// Display for a block being committed (`Options::commit_with_display`), computed the same way
+    /// as the pending display so the block does not visually change when it commits.
+    fn committed_display(&mut self, kind: BlockKind, raw: &str) -> Option<String> {
+        let display = match self.current_mode {
+            BlockMode::CodeFence {
+                fence_char,
+                fence_len,
+            } if kind == BlockKind::CodeFence => {
+                let closed = code_fence_block_is_closed(raw, fence_char, fence_len);
+                if closed && !self.expands_code_tabs(kind) {
+                    return None;
                 }
-            }
-        }
-
-        // Emit invalidations when new reference definitions arrive.
-        if self.opts.reference_definitions == ReferenceDefinitionsMode::Invalidate
-            && block.kind != BlockKind::CodeFence
-            && block.raw.contains("]:")
-        {
-            let mut invalidated = HashSet::new();
-            for line in block.raw.split('\n') {
-                let Some(label) = extract_reference_definition_label(line) else {
-                    continue;
-                };
-                if let Some(ids) = self.reference_usage_index.get(&label) {
-                    for id in ids {
-                        if *id != block.id {
-                            invalidated.insert(*id);
-                        }
-                    }
+                let mut display = self.code_display_base(kind, raw);
+                if !closed {
+                    display.push_str(&code_fence_suffix(
+                        raw.ends_with('\n'),
+                        fence_char,
+                        fence_len,
+                    ));
                 }
+                display
             }
-            if !invalidated.is_empty() {
-                let mut ids: Vec<BlockId> = invalidated.into_iter().collect();
-                ids.sort_by_key(|id| id.0);
-                ctx.invalidated.extend(ids);
+            _ => {
+                let display = self.terminated_display(kind, raw);
+                self.transform_pending_display(kind, raw, display)
             }
-        }
+        };
+        if display == raw { None } else { Some(display) }
+    }
+
+    fn push_committed_block(&mut self, block: Block, ctx: &mut AppendCtx<'_>) {
+        #[cfg(feature = "std")]
+        self.track_reference_definitions(&block, ctx);
 
         self.committed.push(block);
         let block = self
@@ -614,8 +898,19 @@ impl MdStream {
         ctx.push_committed_clone(block);
     }
 
+    /// Switch `current_mode`, reporting the change to `Options::trace` (`trace` feature).
+    #[cfg_attr(not(feature = "trace"), allow(unused_variables))]
+    fn set_mode(&mut self, line_index: usize, site: TraceSite, mode: BlockMode) {
+        #[cfg(feature = "trace")]
+        self.trace_mode_change(line_index, site, &mode);
+        self.current_mode = mode;
+    }
+
     fn maybe_commit_single_line(&mut self, line_index: usize, ctx: &mut AppendCtx<'_>) {
         match self.current_mode {
+            BlockMode::Heading if self.opts.heading_absorbs_indented_continuation => {
+                // Stays open for indented continuation lines; see `is_new_block_boundary`.
+            }
             BlockMode::Heading | BlockMode::ThematicBreak => {
                 self.commit_block(line_index, ctx);
             }
@@ -641,10 +936,14 @@ impl MdStream {
         if line_index == self.current_block_start_line {
             // Defensive: the first line of a block is the single source of truth for the block mode.
             // This avoids stale-mode edge cases where `current_mode` is not `Unknown` at a new start.
-            self.current_mode = self.start_mode_for_line(self.line_str(line_index));
+            let mode = self.start_mode_for_line(self.line_str(line_index));
+            self.set_mode(line_index, TraceSite::StartMode, mode);
             self.maybe_commit_single_line(line_index, ctx);
             // Even on the first line, some modes need to update internal state (e.g. HTML tag stack).
-            self.update_mode_with_line(line_index, ctx);
+            // A single-line block (heading, thematic break) is already committed.
+            if self.current_block_start_line == line_index {
+                self.update_mode_with_line(line_index, ctx);
+            }
             return;
         }
 
@@ -664,12 +963,14 @@ impl MdStream {
         if boundary {
             self.commit_block(line_index - 1, ctx);
             if let Some(m) = next_mode {
-                self.current_mode = m;
+                self.set_mode(line_index, TraceSite::StartMode, m);
             }
             self.maybe_commit_single_line(line_index, ctx);
             // If we started a new mode on this line, we must also update its per-line state.
             // This is required for modes like HTML/math where the opening line affects context.
-            self.update_mode_with_line(line_index, ctx);
+            if self.current_block_start_line == line_index {
+                self.update_mode_with_line(line_index, ctx);
+            }
             return;
         }
 
@@ -701,11 +1002,13 @@ impl MdStream {
 
         if boundary {
             self.commit_block(last - 1, ctx);
-            self.current_mode = self.start_mode_for_line(self.line_str(last));
+            let mode = self.start_mode_for_line(self.line_str(last));
+            self.set_mode(last, TraceSite::StartMode, mode);
         }
     }
 
     fn is_new_block_boundary(&self, prev: &str, curr: &str, curr_line_index: usize) -> bool {
+        let extra_markers = self.opts.extra_list_markers.as_slice();
         // Never split inside fenced code blocks.
         if let BlockMode::CodeFence { .. } = self.current_mode {
             return false;
@@ -718,10 +1021,43 @@ impl MdStream {
                 return false;
             }
         }
-        if let BlockMode::HtmlBlock { stack, in_comment } = &self.current_mode {
+        if let BlockMode::HtmlBlock {
+            stack,
+            in_comment,
+            to_blank_line,
+        } = &self.current_mode
+        {
             if *in_comment || !stack.is_empty() {
                 return false;
             }
+            if *to_blank_line {
+                return is_empty_line(prev) && !is_empty_line(curr);
+            }
+        }
+
+        // Heading with `heading_absorbs_indented_continuation`: only indented lines continue it. A
+        // whitespace-only tail line may still become indented content, so wait for its newline.
+        if let BlockMode::Heading = self.current_mode {
+            if is_empty_line(curr) {
+                return self.lines[curr_line_index].has_newline;
+            }
+            return indent_width(curr) < 4;
+        }
+
+        // Inside a quoted code fence, only quoted lines continue the block (no lazy continuation).
+        // An unquoted blank line ends the quote too; like in plain quotes it stays in the block, so
+        // the split happens at the line after it.
+        if let BlockMode::BlockQuote { fence: Some(_) } = self.current_mode {
+            if is_empty_line(curr) {
+                return false;
+            }
+            return is_empty_line(prev) || !is_blockquote_start(curr);
+        }
+
+        // Inside a fence nested in a list item, only indented lines continue the list.
+        if let BlockMode::List { fence: Some(_) } = self.current_mode {
+            return !is_empty_line(curr)
+                && indent_width(curr) < self.opts.list_continuation_min_indent.max(1);
         }
 
         // Footnote definition: continuation lines should remain in the same block.
@@ -733,22 +1069,42 @@ impl MdStream {
             return true;
         }
 
+        // Definition list: markers and indented continuations (also after blank lines) stay in the
+        // block. Lazy lines and compact `Term` lines without a blank line are kept as well.
+        if let BlockMode::DefinitionList = self.current_mode {
+            if is_empty_line(curr) || is_definition_line(curr) || is_indented_continuation(curr) {
+                return false;
+            }
+        }
+
         // A new block can start after an empty line.
         if is_empty_line(prev) && !is_empty_line(curr) {
             // Be robust against mode drift in streaming scenarios: the current block's "start line"
             // is the source of truth for whether we're inside a list/quote container.
             let block_start_mode =
                 self.start_mode_for_line(self.line_str(self.current_block_start_line));
-            let in_list = matches!(self.current_mode, BlockMode::List)
-                || matches!(block_start_mode, BlockMode::List);
-            let in_blockquote = matches!(self.current_mode, BlockMode::BlockQuote)
-                || matches!(block_start_mode, BlockMode::BlockQuote);
+            let in_list = matches!(self.current_mode, BlockMode::List { .. })
+                || matches!(block_start_mode, BlockMode::List { .. });
+            let in_blockquote = matches!(self.current_mode, BlockMode::BlockQuote { .. })
+                || matches!(block_start_mode, BlockMode::BlockQuote { .. });
+            // More than `list_blank_line_limit` blank lines end the container unless the next line is
+            // indented content.
+            let within_limit =
+                self.blank_lines_before(curr_line_index) <= self.opts.list_blank_line_limit;
+            let min_indent = self.opts.list_continuation_min_indent;
+            if in_list && indent_width(curr) >= min_indent.max(1) {
+                return false;
+            }
             // Lists can legally contain blank lines between items and within an item's continuation.
-            if in_list && (is_list_continuation(curr) || is_list_item_start_prefix(curr)) {
+            if within_limit
+                && in_list
+                && (is_list_continuation(curr, min_indent, extra_markers)
+                    || is_list_item_start_prefix(curr, extra_markers))
+            {
                 return false;
             }
             // Blockquotes can continue after blank lines only if the marker is present.
-            if in_blockquote && is_blockquote_start(curr) {
+            if within_limit && in_blockquote && is_blockquote_start(curr) {
                 return false;
             }
             return true;
@@ -758,17 +1114,20 @@ impl MdStream {
         if matches!(self.current_mode, BlockMode::Paragraph | BlockMode::Unknown)
             && setext_underline_char(curr).is_some()
             && !is_empty_line(prev)
-            && self.current_block_start_line + 1 == curr_line_index
+            && self.current_block_start_line < curr_line_index
         {
             return false;
         }
 
         // Certain block starters can interrupt paragraphs/lists/quotes.
-        if is_heading(curr) || is_thematic_break(curr) {
+        if self.is_heading_line(curr, curr_line_index) || is_thematic_break(curr) {
             return true;
         }
-        if fence_start(curr).is_some() {
-            return true;
+        if fence_start(curr, self.opts.code_fence_min_len).is_some() {
+            // An indented fence opens a code block inside the current list item.
+            let nested_in_list = matches!(self.current_mode, BlockMode::List { .. })
+                && indent_width(curr) >= self.opts.list_continuation_min_indent.max(1);
+            return !nested_in_list;
         }
         if self.boundary_plugins.iter().any(|p| p.matches_start(curr)) {
             return true;
@@ -778,13 +1137,15 @@ impl MdStream {
         }
         if is_blockquote_start(curr)
             && !is_blockquote_start(prev)
-            && !matches!(self.current_mode, BlockMode::BlockQuote)
+            && !matches!(self.current_mode, BlockMode::BlockQuote { .. })
         {
             return true;
         }
-        if is_list_item_start(curr)
-            && !is_list_item_start(prev)
-            && !matches!(self.current_mode, BlockMode::List)
+        if is_list_item_start(curr, extra_markers)
+            && !is_list_item_start(prev, extra_markers)
+            && !matches!(self.current_mode, BlockMode::List { .. })
+            && (!matches!(self.current_mode, BlockMode::Paragraph)
+                || list_item_can_interrupt_paragraph(curr, extra_markers))
         {
             return true;
         }
@@ -792,8 +1153,8 @@ impl MdStream {
         // Table detection: if current line is a delimiter and previous line contains pipes,
         // consider starting a table block at the previous line.
         if matches!(self.current_mode, BlockMode::Paragraph | BlockMode::Unknown)
-            && self.is_table_delimiter(curr)
-            && prev.contains('|')
+            && is_table_delimiter_row(curr)
+            && is_table_row(prev)
             // table starts at prev line, so boundary at prev-1 if block started earlier.
             && curr_line_index >= 1
             && self.current_block_start_line < curr_line_index - 1
@@ -804,21 +1165,34 @@ impl MdStream {
         false
     }
 
-    fn is_table_delimiter(&self, line: &str) -> bool {
-        let s = line.trim();
-        if s.is_empty() {
+    /// A bare `#` run on the incomplete tail line may still become `#tag`, so it only counts as a
+    /// heading once the line is complete or has text after the marker.
+    fn is_heading_line(&self, line: &str, line_index: usize) -> bool {
+        let Some(level) = atx_heading_level(line, self.opts.heading_max_level) else {
             return false;
-        }
-        // Simple delimiter pattern: contains '-' and optional pipes/colons.
-        let mut has_dash = false;
-        for c in s.chars() {
-            match c {
-                '|' | ':' | ' ' | '\t' => {}
-                '-' => has_dash = true,
-                _ => return false,
-            }
-        }
-        has_dash
+        };
+        self.lines[line_index].has_newline || line.trim_start().len() > level
+    }
+
+    /// Consecutive blank lines right before `line_index` within the current block, counted up to
+    /// `list_blank_line_limit + 1`.
+    fn blank_lines_before(&self, line_index: usize) -> usize {
+        let cap = self.opts.list_blank_line_limit.saturating_add(1);
+        (self.current_block_start_line..line_index)
+            .rev()
+            .take(cap)
+            .take_while(|&i| is_empty_line(self.line_str(i)))
+            .count()
+    }
+
+    /// Whether `line_index` is the first definition line right after a single-line term.
+    fn definition_list_starts_at(&self, line_index: usize) -> bool {
+        self.opts.definition_lists
+            && line_index > 0
+            && self.current_block_start_line + 1 == line_index
+            && !is_empty_line(self.line_str(line_index - 1))
+            && is_definition_line(self.line_str(line_index))
+            && self.line_str(line_index).trim() != ":"
     }
 
     fn update_mode_with_line(&mut self, line_index: usize, ctx: &mut AppendCtx<'_>) {
@@ -829,7 +1203,8 @@ impl MdStream {
         let line = &self.buffer[start..end];
         match &mut self.current_mode {
             BlockMode::Unknown => {
-                self.current_mode = self.start_mode_for_line(line);
+                let mode = self.start_mode_for_line(line);
+                self.set_mode(line_index, TraceSite::StartMode, mode);
                 self.maybe_commit_single_line(line_index, ctx);
             }
             BlockMode::CodeFence {
@@ -868,23 +1243,26 @@ impl MdStream {
                 }
             }
             BlockMode::Paragraph => {
-                // Upgrade to setext heading if underline appears right after a single paragraph line.
+                // Upgrade to setext heading when an underline follows the paragraph's lines.
                 if setext_underline_char(line).is_some()
-                    && self.current_block_start_line + 1 == line_index
-                    && line_index > 0
+                    && self.current_block_start_line < line_index
                 {
                     let prev = self.lines[line_index - 1].as_str(&self.buffer);
                     if !is_empty_line(prev) {
-                        self.current_mode = BlockMode::Heading;
+                        self.set_mode(line_index, TraceSite::UpdateMode, BlockMode::Heading);
                         self.commit_block(line_index, ctx);
                         return;
                     }
                 }
+                if self.definition_list_starts_at(line_index) {
+                    self.set_mode(line_index, TraceSite::UpdateMode, BlockMode::DefinitionList);
+                    return;
+                }
                 // Upgrade to table mode if delimiter row appears.
-                if self.is_table_delimiter(line) && line_index > 0 {
+                if is_table_delimiter_row(line) && line_index > 0 {
                     let prev = self.lines[line_index - 1].as_str(&self.buffer);
-                    if prev.contains('|') {
-                        self.current_mode = BlockMode::Table;
+                    if is_table_row(prev) {
+                        self.set_mode(line_index, TraceSite::UpdateMode, BlockMode::Table);
                     }
                 }
             }
@@ -892,22 +1270,103 @@ impl MdStream {
                 // End table when an empty line is followed by a non-table line.
                 // This is handled by boundary detection on next line arrival.
             }
-            BlockMode::HtmlBlock { stack, in_comment } => {
-                update_html_block_state(line, stack, in_comment);
-                if !*in_comment && stack.is_empty() {
+            BlockMode::HtmlBlock {
+                stack,
+                in_comment,
+                to_blank_line,
+            } => {
+                let mismatched = update_html_block_state(line, stack, in_comment);
+                let closed = !*in_comment && stack.is_empty();
+                if closed && has_text_after_last_tag(line) {
+                    *to_blank_line = true;
+                }
+                let commit = closed && !*to_blank_line;
+                if self.opts.diagnostics && !mismatched.is_empty() {
+                    self.diagnose_html_close_tags(start, mismatched, ctx);
+                }
+                if commit {
                     self.commit_block(line_index, ctx);
                 }
             }
-            BlockMode::FootnoteDefinition => {
+            BlockMode::FootnoteDefinition | BlockMode::DefinitionList => {
                 // Continuation handled by boundary logic.
             }
-            BlockMode::List | BlockMode::BlockQuote => {
-                // Conservative: rely on boundary logic on next line arrival.
+            BlockMode::BlockQuote { fence } => {
+                // Track fences inside the quote so their content never looks like block syntax.
+                let inner = strip_blockquote_markers(line);
+                match *fence {
+                    Some((ch, len)) => {
+                        if fence_end(inner, ch, len) {
+                            *fence = None;
+                        }
+                    }
+                    None => *fence = fence_start(inner, self.opts.code_fence_min_len),
+                }
+            }
+            BlockMode::List { fence } => {
+                // Track fences in list items so their content never looks like block syntax.
+                // Otherwise rely on boundary logic on next line arrival.
+                let inner =
+                    strip_list_item_marker(line, &self.opts.extra_list_markers).trim_start();
+                match *fence {
+                    Some((ch, len)) => {
+                        if fence_end(inner, ch, len) {
+                            *fence = None;
+                        }
+                    }
+                    None => *fence = fence_start(inner, self.opts.code_fence_min_len),
+                }
             }
             BlockMode::Heading | BlockMode::ThematicBreak => {}
         }
     }
 
+    /// Kind of the pending block (outside single-block footnote mode).
+    ///
+    /// A paragraph whose incomplete last line is already an unambiguous setext underline (`---`,
+    /// `===`) reports `Heading`, matching the terminator, which only shields `-`/`--`/`=`/`==`.
+    /// Likewise a header row followed by an incomplete delimiter row with a pipe (`|---|---`)
+    /// reports `Table`, so the header can be drawn before any data row arrives.
+    fn pending_kind(&self) -> BlockKind {
+        // Only a run of blank lines starts with one (see `Options::emit_blank_blocks`).
+        if self.opts.emit_blank_blocks
+            && is_empty_line(self.line_str(self.current_block_start_line))
+        {
+            return BlockKind::Blank;
+        }
+        match self.current_mode {
+            BlockMode::Unknown => {
+                let mode = self.start_mode_for_line(self.line_str(self.current_block_start_line));
+                Self::kind_for_mode(&mode)
+            }
+            BlockMode::Paragraph if self.tail_is_setext_underline() => BlockKind::Heading,
+            BlockMode::Paragraph if self.tail_is_table_delimiter() => BlockKind::Table,
+            ref mode => Self::kind_for_mode(mode),
+        }
+    }
+
+    fn tail_is_setext_underline(&self) -> bool {
+        let last = self.lines.len() - 1;
+        if last <= self.current_block_start_line || self.lines[last].has_newline {
+            return false;
+        }
+        let line = self.line_str(last);
+        setext_underline_char(line).is_some()
+            && line.trim().len() >= 3
+            && !is_empty_line(self.line_str(last - 1))
+    }
+
+    /// The incomplete tail line would promote the block to a table once complete: the block is
+    /// just the header row so far (a longer paragraph splits off before the table).
+    fn tail_is_table_delimiter(&self) -> bool {
+        let last = self.lines.len() - 1;
+        if last != self.current_block_start_line + 1 || self.lines[last].has_newline {
+            return false;
+        }
+        let line = self.line_str(last);
+        line.contains('|') && is_table_delimiter_row(line) && is_table_row(self.line_str(last - 1))
+    }
+
     fn current_pending_info(&self) -> Option<PendingInfo> {
         if self.opts.footnotes == FootnotesMode::SingleBlock && self.footnotes_detected {
             if self.buffer.is_empty() {
@@ -916,7 +1375,7 @@ impl MdStream {
             return Some(PendingInfo {
                 id: BlockId(1),
                 kind: BlockKind::Unknown,
-                raw_start: 0,
+                raw_start: self.bounded_pending_start(0),
             });
         }
 
@@ -931,17 +1390,12 @@ impl MdStream {
             return None;
         }
 
-        let kind = if matches!(self.current_mode, BlockMode::Unknown) {
-            let mode = self.start_mode_for_line(self.line_str(self.current_block_start_line));
-            Self::kind_for_mode(&mode)
-        } else {
-            Self::kind_for_mode(&self.current_mode)
-        };
+        let kind = self.pending_kind();
 
         Some(PendingInfo {
             id: self.current_block_id,
             kind,
-            raw_start: start_off,
+            raw_start: self.bounded_pending_start(start_off),
         })
     }
 
@@ -988,6 +1442,9 @@ impl MdStream {
     }
 
     fn ensure_pending_display_for(&mut self, kind: BlockKind, raw_start: usize) {
+        if !self.opts.compute_pending_display {
+            return;
+        }
         if matches!(kind, BlockKind::CodeFence) {
             if let BlockMode::CodeFence {
                 fence_char,
@@ -999,13 +1456,21 @@ impl MdStream {
                 {
                     return;
                 }
-                let raw = &self.buffer[raw_start..];
+                let raw = self.pending_display_source(kind, raw_start);
                 let suffix = code_fence_suffix(raw.ends_with('\n'), fence_char, fence_len);
-                let mut display = String::with_capacity(raw.len() + suffix.len());
-                display.push_str(raw);
+                let truncated = self.truncated_pending_code_fence(kind, &raw);
+                let mut display =
+                    self.code_display_base(kind, truncated.as_deref().unwrap_or(&raw));
                 display.push_str(&suffix);
+                // A truncated or bounded display cannot be extended in place.
+                let extendable = self.opts.pending_code_fence_display_max_lines.is_none()
+                    && matches!(raw, Cow::Borrowed(_))
+                    && self
+                        .lines
+                        .get(self.current_block_start_line)
+                        .is_some_and(|l| l.start == raw_start);
                 self.pending_display_cache = Some(display);
-                self.pending_display_cache_suffix = Some(suffix);
+                self.pending_display_cache_suffix = extendable.then_some(suffix);
                 return;
             }
         }
@@ -1014,9 +1479,10 @@ impl MdStream {
             return;
         }
         let display = {
-            let raw = &self.buffer[raw_start..];
-            terminate_markdown(raw, &self.opts.terminator)
+            let raw = self.pending_display_source(kind, raw_start);
+            self.terminated_display(kind, &raw)
         };
+        let display = self.expand_code_display_tabs(kind, display);
         let display = self.transform_pending_display_at(kind, raw_start, display);
         self.pending_display_cache = Some(display);
         self.pending_display_cache_suffix = None;
@@ -1026,6 +1492,9 @@ impl MdStream {
         let Some(suffix) = self.pending_display_cache_suffix.as_ref() else {
             return false;
         };
+        if self.appended_line_exceeds_cap(appended) {
+            return false;
+        }
         let Some(display) = self.pending_display_cache.as_mut() else {
             self.pending_display_cache_suffix = None;
             return false;
@@ -1049,7 +1518,11 @@ impl MdStream {
 
         let base_len = display.len().saturating_sub(suffix.len());
         display.truncate(base_len);
-        display.push_str(appended);
+        if self.opts.expand_tabs_in_code_display {
+            push_tab_expanded(display, appended, self.opts.tab_width);
+        } else {
+            display.push_str(appended);
+        }
 
         let new_suffix = code_fence_suffix(new_raw_ended_with_nl, fence_char, fence_len);
         display.push_str(&new_suffix);
@@ -1057,24 +1530,39 @@ impl MdStream {
         true
     }
 
+    /// Whether appending `appended` leaves a last line longer than `Options::max_pending_line_bytes`,
+    /// i.e. the pending snapshot (and its display) gets cut.
+    fn appended_line_exceeds_cap(&self, appended: &str) -> bool {
+        let Some(limit) = self.opts.max_pending_line_bytes else {
+            return false;
+        };
+        let last_line_len = match appended.rfind('\n') {
+            Some(nl) => appended.len() - nl - 1,
+            None => {
+                let last_start = self.lines.last().map_or(0, |l| l.start);
+                self.buffer.len() - last_start + appended.len()
+            }
+        };
+        last_line_len > limit
+    }
+
     fn pending_block_snapshot(&mut self) -> Option<Block> {
         if self.opts.footnotes == FootnotesMode::SingleBlock && self.footnotes_detected {
-            let raw = self.buffer.clone();
+            let raw = self.buffer[self.bounded_pending_start(0)..].to_string();
             if raw.is_empty() {
                 return None;
             }
             let kind = BlockKind::Unknown;
-            let display = self.transform_pending_display(
-                kind,
-                &raw,
-                terminate_markdown(&raw, &self.opts.terminator),
-            );
+            let display = self.opts.compute_pending_display.then(|| {
+                let display = terminate_markdown(&raw, &self.opts.terminator);
+                self.transform_pending_display(kind, &raw, display)
+            });
             return Some(Block {
                 id: BlockId(1),
                 status: BlockStatus::Pending,
                 kind,
                 raw,
-                display: Some(display),
+                display,
             });
         }
 
@@ -1085,24 +1573,37 @@ impl MdStream {
         if start_off >= self.buffer.len() {
             return None;
         }
-        let raw = self.buffer[start_off..].to_string();
+        let raw_start = self.bounded_pending_start(start_off);
+        let raw = self.buffer[raw_start..].to_string();
         if raw.is_empty() {
             return None;
         }
-        let kind = if matches!(self.current_mode, BlockMode::Unknown) {
-            let mode = self.start_mode_for_line(self.line_str(self.current_block_start_line));
-            Self::kind_for_mode(&mode)
-        } else {
-            Self::kind_for_mode(&self.current_mode)
-        };
-        let mut display = terminate_markdown(&raw, &self.opts.terminator);
-        display = self.transform_pending_display(kind, &raw, display);
+        let kind = self.pending_kind();
+        if raw_start != start_off && self.opts.compute_pending_display {
+            // A bounded snapshot: share the display logic of `append_ref`, which restores the
+            // opening line of a fenced block.
+            self.pending_display_cache = None;
+            self.ensure_pending_display_for(kind, raw_start);
+            return Some(Block {
+                id: self.current_block_id,
+                status: BlockStatus::Pending,
+                kind,
+                raw,
+                display: self.pending_display_cache.clone(),
+            });
+        }
+        let display = self.opts.compute_pending_display.then(|| {
+            let truncated = self.truncated_pending_code_fence(kind, &raw);
+            let display = self.terminated_display(kind, truncated.as_deref().unwrap_or(&raw));
+            let display = self.expand_code_display_tabs(kind, display);
+            self.transform_pending_display(kind, &raw, display)
+        });
         Some(Block {
             id: self.current_block_id,
             status: BlockStatus::Pending,
             kind,
             raw,
-            display: Some(display),
+            display,
         })
     }
 
@@ -1110,7 +1611,7 @@ impl MdStream {
         if let Some(cached) = &self.pending_display_cache {
             // Fast path: pending raw still needs to be refreshed.
             if self.opts.footnotes == FootnotesMode::SingleBlock && self.footnotes_detected {
-                let raw = self.buffer.clone();
+                let raw = self.buffer[self.bounded_pending_start(0)..].to_string();
                 if raw.is_empty() {
                     return None;
                 }
@@ -1130,14 +1631,14 @@ impl MdStream {
             if start_off >= self.buffer.len() {
                 return None;
             }
-            let raw = self.buffer[start_off..].to_string();
+            let raw = self.buffer[self.bounded_pending_start(start_off)..].to_string();
             if raw.is_empty() {
                 return None;
             }
             return Some(Block {
                 id: self.current_block_id,
                 status: BlockStatus::Pending,
-                kind: Self::kind_for_mode(&self.current_mode),
+                kind: self.pending_kind(),
                 raw,
                 display: Some(cached.clone()),
             });
@@ -1153,6 +1654,69 @@ impl MdStream {
         p
     }
 
+    /// The text the pending display starting at `raw_start` is computed from.
+    ///
+    /// When `Options::max_pending_line_bytes` cut a code fence or math block inside its last
+    /// line, the block's opening line is put back in front, so the tail is still shown (and
+    /// closed) as code or math rather than balanced as prose.
+    fn pending_display_source(&self, kind: BlockKind, raw_start: usize) -> Cow<'_, str> {
+        let tail = &self.buffer[raw_start..];
+        let Some(first) = self.lines.get(self.current_block_start_line) else {
+            return Cow::Borrowed(tail);
+        };
+        let opening = first.start..first.end_with_newline();
+        if !matches!(kind, BlockKind::CodeFence | BlockKind::MathBlock)
+            || !first.has_newline
+            || raw_start < opening.end
+        {
+            return Cow::Borrowed(tail);
+        }
+        let mut source = String::with_capacity(opening.len() + tail.len());
+        source.push_str(&self.buffer[opening]);
+        source.push_str(tail);
+        Cow::Owned(source)
+    }
+
+    /// `raw` closed by the terminator, or verbatim for HTML blocks
+    /// (`Options::keep_html_raw_text_verbatim`).
+    fn terminated_display(&self, kind: BlockKind, raw: &str) -> String {
+        if kind == BlockKind::HtmlBlock && self.opts.keep_html_raw_text_verbatim {
+            return raw.to_string();
+        }
+        terminate_markdown(raw, &self.opts.terminator)
+    }
+
+    /// The pending code fence `raw` shortened for display
+    /// (`Options::pending_code_fence_display_max_lines`), if it needs to be.
+    fn truncated_pending_code_fence(&self, kind: BlockKind, raw: &str) -> Option<String> {
+        if kind != BlockKind::CodeFence {
+            return None;
+        }
+        truncated_code_fence_raw(raw, self.opts.pending_code_fence_display_max_lines?)
+    }
+
+    fn expands_code_tabs(&self, kind: BlockKind) -> bool {
+        self.opts.expand_tabs_in_code_display && kind == BlockKind::CodeFence
+    }
+
+    /// `raw` as the start of a code fence display (tabs expanded when configured).
+    fn code_display_base(&self, kind: BlockKind, raw: &str) -> String {
+        let mut display = String::with_capacity(raw.len());
+        if self.expands_code_tabs(kind) {
+            push_tab_expanded(&mut display, raw, self.opts.tab_width);
+        } else {
+            display.push_str(raw);
+        }
+        display
+    }
+
+    fn expand_code_display_tabs(&self, kind: BlockKind, display: String) -> String {
+        if !self.expands_code_tabs(kind) || !display.contains('\t') {
+            return display;
+        }
+        self.code_display_base(kind, &display)
+    }
+
     fn transform_pending_display(
         &mut self,
         kind: BlockKind,
@@ -1174,22 +1738,72 @@ impl MdStream {
         display
     }
 
+    /// Feed the next chunk of the stream.
+    ///
+    /// A byte order mark (`U+FEFF`) at the very start of the stream is dropped so the first line
+    /// is detected like any other. Other zero-width characters (e.g. `U+200B`) are kept as text.
     pub fn append(&mut self, chunk: &str) -> Update {
+        let mut timer = self.start_append_timer(chunk.len());
         let mut update = Update::empty();
         let mut ctx = AppendCtx::new(Some(&mut update.committed));
         self.append_core(chunk, &mut ctx);
+        let lines_processed = ctx.lines_processed;
+        let whitespace_fast_path = ctx.whitespace_fast_path;
         update.reset = ctx.reset;
         update.invalidated = ctx.invalidated;
+        update.reemitted = ctx.reemitted;
+        update.diagnostics = ctx.diagnostics;
+        update.finished_messages = ctx.finished_messages;
+        if let Some(timer) = &mut timer {
+            timer.start_termination();
+        }
         update.pending = self.current_pending_block();
+        self.finish_append_timer(
+            timer,
+            lines_processed,
+            update.committed.len(),
+            whitespace_fast_path,
+        );
+        self.notify_callbacks(
+            &update.finished_messages,
+            &update.committed,
+            update.pending.as_ref(),
+        );
+        update
+    }
+
+    /// Append several chunks and return one merged update (see `Update::merge`).
+    ///
+    /// Handy for replaying a batch of buffered deltas while still getting a coherent update.
+    pub fn append_all<I, S>(&mut self, chunks: I) -> Update
+    where
+        I: IntoIterator<Item = S>,
+        S: AsRef<str>,
+    {
+        let mut update = Update::empty();
+        for chunk in chunks {
+            update.merge(self.append(chunk.as_ref()));
+        }
         update
     }
 
     pub fn append_ref(&mut self, chunk: &str) -> UpdateRef<'_> {
+        let mut timer = self.start_append_timer(chunk.len());
         let committed_start = self.committed.len();
         let mut ctx = AppendCtx::new(None);
         self.append_core(chunk, &mut ctx);
         let committed_start = if ctx.reset { 0 } else { committed_start };
+        if let Some(timer) = &mut timer {
+            timer.start_termination();
+        }
         self.ensure_current_pending_display();
+        self.finish_append_timer(
+            timer,
+            ctx.lines_processed,
+            self.committed.len() - committed_start,
+            ctx.whitespace_fast_path,
+        );
+        self.notify_callbacks_ref(&ctx.finished_messages, committed_start);
         let pending = self.current_pending_ref_readonly();
         let committed = &self.committed[committed_start..];
         UpdateRef {
@@ -1197,14 +1811,50 @@ impl MdStream {
             pending,
             reset: ctx.reset,
             invalidated: ctx.invalidated,
+            reemitted: ctx.reemitted,
+            diagnostics: ctx.diagnostics,
+            finished_messages: ctx.finished_messages,
         }
     }
 
     fn append_core(&mut self, chunk: &str, ctx: &mut AppendCtx<'_>) {
+        if self
+            .opts
+            .message_terminator
+            .as_ref()
+            .is_some_and(|s| !s.is_empty())
+        {
+            self.append_with_message_terminator(chunk, ctx);
+            return;
+        }
+        self.append_chunk(chunk, ctx);
+    }
+
+    fn append_chunk(&mut self, chunk: &str, ctx: &mut AppendCtx<'_>) {
+        let chunk = if self.buffer_base_offset == 0 && self.buffer.is_empty() {
+            chunk.strip_prefix('\u{FEFF}').unwrap_or(chunk)
+        } else {
+            chunk
+        };
         if chunk.is_empty() && !self.pending_cr {
             return;
         }
 
+        if self.whitespace_append_skips_boundaries(chunk) {
+            ctx.whitespace_fast_path = true;
+            if !self.try_incremental_pending_display_append(chunk) {
+                self.pending_display_cache = None;
+                self.pending_display_cache_suffix = None;
+            }
+            // Whitespace cannot complete a `[^id]` marker; only keep the scan window current.
+            if self.opts.footnotes == FootnotesMode::SingleBlock && !self.footnotes_detected {
+                update_tail(&mut self.footnote_scan_tail, chunk, FOOTNOTE_SCAN_TAIL);
+            }
+            self.append_to_lines(chunk);
+            self.maybe_compact_buffer();
+            return;
+        }
+
         let footnotes_before = self.footnotes_detected;
         let chunk = self.normalize_newlines_cow(chunk);
 
@@ -1215,13 +1865,13 @@ impl MdStream {
             self.pending_display_cache_suffix = None;
         }
 
-        if !self.footnotes_detected {
+        // Only single-block mode consumes detection; other modes skip the per-chunk scan.
+        if self.opts.footnotes == FootnotesMode::SingleBlock && !self.footnotes_detected {
             if detect_footnotes(chunk.as_ref()) {
                 self.footnotes_detected = true;
             } else {
                 // Keep a small tail window to detect patterns across chunk boundaries.
-                const MAX_TAIL: usize = 256;
-                let chunk_prefix = take_prefix_at_char_boundary(chunk.as_ref(), MAX_TAIL);
+                let chunk_prefix = take_prefix_at_char_boundary(chunk.as_ref(), FOOTNOTE_SCAN_TAIL);
                 if !self.footnote_scan_tail.is_empty() && !chunk_prefix.is_empty() {
                     let mut combined =
                         String::with_capacity(self.footnote_scan_tail.len() + chunk_prefix.len());
@@ -1232,7 +1882,11 @@ impl MdStream {
                     }
                 }
                 if !self.footnotes_detected {
-                    update_tail(&mut self.footnote_scan_tail, chunk.as_ref(), MAX_TAIL);
+                    update_tail(
+                        &mut self.footnote_scan_tail,
+                        chunk.as_ref(),
+                        FOOTNOTE_SCAN_TAIL,
+                    );
                 }
             }
         }
@@ -1255,6 +1909,7 @@ impl MdStream {
             }
             self.process_line(self.processed_line, ctx);
             self.processed_line += 1;
+            ctx.lines_processed += 1;
         }
 
         // Even if the current last line has no newline yet, we may have enough information to
@@ -1264,10 +1919,40 @@ impl MdStream {
         self.maybe_compact_buffer();
     }
 
+    /// Whether `chunk` is only spaces/tabs that cannot move a block boundary: the tail line stays
+    /// blank, or the current mode never splits on an incomplete tail line. Trailing whitespace after
+    /// content is not covered since it can change `-`/`#`/table/setext detection.
+    fn whitespace_append_skips_boundaries(&self, chunk: &str) -> bool {
+        if self.pending_cr || !chunk.bytes().all(|b| b == b' ' || b == b'\t') {
+            return false;
+        }
+        if self.opts.footnotes == FootnotesMode::SingleBlock && self.footnotes_detected {
+            return true;
+        }
+        let mode_ignores_tail = match &self.current_mode {
+            BlockMode::CodeFence { .. } | BlockMode::CustomBoundary { .. } => true,
+            BlockMode::MathBlock { open_count } => open_count % 2 == 1,
+            BlockMode::HtmlBlock {
+                stack, in_comment, ..
+            } => *in_comment || !stack.is_empty(),
+            _ => false,
+        };
+        if mode_ignores_tail {
+            return true;
+        }
+        // Boundary plugins see the raw tail line, so leave blank lines to them.
+        self.boundary_plugins.is_empty()
+            && self
+                .lines
+                .last()
+                .is_none_or(|l| is_empty_line(&self.buffer[l.start..l.end]))
+    }
+
     fn reset_for_single_block_footnotes(&mut self, ctx: &mut AppendCtx<'_>) {
         ctx.reset = true;
 
         self.committed.clear();
+        #[cfg(feature = "std")]
         self.reference_usage_index.clear();
         self.pending_display_cache = None;
         self.pending_display_cache_suffix = None;
@@ -1284,7 +1969,86 @@ impl MdStream {
     }
 
     pub fn finalize(&mut self) -> Update {
-        if !self.pending_cr && self.buffer.len() == self.last_finalized_buffer_len {
+        // Held-back text was not a sentinel after all.
+        let mut tail = core::mem::take(&mut self.message_terminator_tail);
+        if self.opts.finalize_appends_newline && self.ends_mid_line(&tail) {
+            tail.push('\n');
+        }
+        let keep_open_container = self
+            .opts
+            .commit_on_finalize_returns_pending_if_open_container;
+        let update = if tail.is_empty() {
+            self.finalize_core(keep_open_container)
+        } else {
+            let mut update = Update::empty();
+            let mut ctx = AppendCtx::new(Some(&mut update.committed));
+            self.append_chunk(&tail, &mut ctx);
+            update.reset = ctx.reset;
+            update.invalidated = ctx.invalidated;
+            update.reemitted = ctx.reemitted;
+            update.diagnostics = ctx.diagnostics;
+            update.merge(self.finalize_core(keep_open_container));
+            update
+        };
+        self.notify_callbacks(
+            &update.finished_messages,
+            &update.committed,
+            update.pending.as_ref(),
+        );
+        update
+    }
+
+    /// Whether the pending block is a boundary-plugin container (e.g. `<thinking>`) whose closing
+    /// line has not been seen yet.
+    pub fn has_open_container(&self) -> bool {
+        self.active_boundary_plugin().is_some()
+    }
+
+    /// Index (in registration order) of the boundary plugin whose container the pending block is.
+    pub fn active_boundary_plugin(&self) -> Option<usize> {
+        if self.opts.footnotes == FootnotesMode::SingleBlock && self.footnotes_detected
+            || self.current_pending_info().is_none()
+        {
+            return None;
+        }
+        match self.current_mode {
+            BlockMode::CustomBoundary { plugin_index, .. } => Some(plugin_index),
+            BlockMode::Unknown => {
+                match self.start_mode_for_line(self.line_str(self.current_block_start_line)) {
+                    BlockMode::CustomBoundary { plugin_index, .. } => Some(plugin_index),
+                    _ => None,
+                }
+            }
+            _ => None,
+        }
+    }
+
+    /// `BoundaryPlugin::label` of the active boundary plugin, e.g. `thinking` inside a
+    /// `<thinking>` block. `None` outside a container or for a plugin without a label.
+    pub fn active_boundary_label(&self) -> Option<&str> {
+        let label = self
+            .boundary_plugins
+            .get(self.active_boundary_plugin()?)?
+            .label();
+        (!label.is_empty()).then_some(label)
+    }
+
+    /// Whether the input (buffer plus not-yet-appended `tail`) stops without a final newline.
+    fn ends_mid_line(&self, tail: &str) -> bool {
+        if !tail.is_empty() {
+            return !tail.ends_with('\n');
+        }
+        // A trailing '\r' is turned into a newline by `finalize_core`.
+        !self.pending_cr
+            && self.buffer.len() != self.last_finalized_buffer_len
+            && !self.buffer.is_empty()
+            && !self.buffer.ends_with('\n')
+    }
+
+    /// Commit what is left. With `keep_open_container`, an open boundary-plugin container stays
+    /// pending instead (`Options::commit_on_finalize_returns_pending_if_open_container`).
+    fn finalize_core(&mut self, keep_open_container: bool) -> Update {
+        if self.finalized_without_new_input() {
             return Update::empty();
         }
 
@@ -1297,25 +2061,36 @@ impl MdStream {
             self.pending_cr = false;
         }
 
+        if keep_open_container && self.has_open_container() {
+            update.pending = self.current_pending_block();
+            return update;
+        }
+
         if self.opts.footnotes == FootnotesMode::SingleBlock && self.footnotes_detected {
             if !self.buffer.is_empty() {
                 if self.buffer.trim().is_empty() {
                     update.pending = None;
+                    self.last_finalized_buffer_len = self.buffer.len();
                     return update;
                 }
-                let block = Block {
+                let mut block = Block {
                     id: BlockId(1),
                     status: BlockStatus::Committed,
                     kind: BlockKind::Unknown,
                     raw: self.buffer.clone(),
                     display: None,
                 };
+                if self.opts.commit_with_display {
+                    block.display = self.committed_display(block.kind, &block.raw);
+                }
                 self.push_committed_block(block, &mut ctx);
             }
             update.pending = None;
             self.maybe_compact_buffer();
             self.last_finalized_buffer_len = self.buffer.len();
             update.invalidated = ctx.invalidated;
+            update.reemitted = ctx.reemitted;
+            update.diagnostics = ctx.diagnostics;
             return update;
         }
 
@@ -1324,23 +2099,62 @@ impl MdStream {
             let start_off = self.lines[self.current_block_start_line].start;
             let end_off = self.buffer.len();
             if end_off > start_off {
-                // Commit the remaining pending block.
-                if matches!(self.current_mode, BlockMode::Unknown) {
-                    self.current_mode =
+                // Commit the remaining pending block. A start line without a newline was never
+                // processed; its mode may come from a partial prefix (e.g. `-` before `- b`).
+                if matches!(self.current_mode, BlockMode::Unknown)
+                    || !self.lines[self.current_block_start_line].has_newline
+                {
+                    let mode =
                         self.start_mode_for_line(self.line_str(self.current_block_start_line));
+                    self.set_mode(self.current_block_start_line, TraceSite::StartMode, mode);
+                }
+                // The last line may never have been processed (no trailing newline).
+                if matches!(self.current_mode, BlockMode::Paragraph)
+                    && self.definition_list_starts_at(end_line)
+                {
+                    self.set_mode(end_line, TraceSite::UpdateMode, BlockMode::DefinitionList);
+                }
+                if matches!(self.current_mode, BlockMode::Paragraph)
+                    && end_line > self.current_block_start_line
+                    && !self.lines[end_line].has_newline
+                    && setext_underline_char(self.line_str(end_line)).is_some()
+                    && !is_empty_line(self.line_str(end_line - 1))
+                {
+                    self.set_mode(end_line, TraceSite::UpdateMode, BlockMode::Heading);
                 }
                 let raw = self.buffer[start_off..end_off].to_string();
-                if raw.trim().is_empty() {
+                let blank = raw.trim().is_empty();
+                if blank && !self.opts.emit_blank_blocks {
                     update.pending = None;
+                    self.last_finalized_buffer_len = self.buffer.len();
                     return update;
                 }
-                let block = Block {
+                let mut block = Block {
                     id: self.current_block_id,
                     status: BlockStatus::Committed,
-                    kind: Self::kind_for_mode(&self.current_mode),
+                    kind: if blank {
+                        BlockKind::Blank
+                    } else {
+                        Self::kind_for_mode(&self.current_mode)
+                    },
                     raw,
                     display: None,
                 };
+                if self.opts.commit_with_display {
+                    block.display = self.committed_display(block.kind, &block.raw);
+                }
+                if self.opts.diagnostics {
+                    if let BlockMode::CodeFence {
+                        fence_char,
+                        fence_len,
+                    } = self.current_mode
+                    {
+                        self.diagnose_unterminated_code_fence(
+                            &block.raw, start_off, fence_char, fence_len, &mut ctx,
+                        );
+                    }
+                    self.diagnose_committed_block(&block, start_off, &mut ctx);
+                }
                 self.push_committed_block(block, &mut ctx);
                 // Reset to empty.
                 self.current_block_start_line = end_line + 1;
@@ -1350,6 +2164,8 @@ impl MdStream {
         self.maybe_compact_buffer();
         self.last_finalized_buffer_len = self.buffer.len();
         update.invalidated = ctx.invalidated;
+        update.reemitted = ctx.reemitted;
+        update.diagnostics = ctx.diagnostics;
         update
     }
 
@@ -1357,11 +2173,20 @@ impl MdStream {
         let committed_start = self.committed.len();
         let update = self.finalize();
         let committed_start = if update.reset { 0 } else { committed_start };
+        // Only set when an open container was kept pending.
+        let pending = update
+            .pending
+            .is_some()
+            .then(|| self.current_pending_ref_readonly())
+            .flatten();
         UpdateRef {
             committed: &self.committed[committed_start..],
-            pending: None,
+            pending,
             reset: update.reset,
             invalidated: update.invalidated,
+            reemitted: update.reemitted,
+            diagnostics: update.diagnostics,
+            finished_messages: update.finished_messages,
         }
     }
 
@@ -1392,10 +2217,18 @@ impl MdStream {
         self.footnote_scan_tail.clear();
         self.pending_cr = false;
         self.last_finalized_buffer_len = 0;
+        self.buffer_base_offset = 0;
+        self.message_terminator_tail.clear();
+        #[cfg(feature = "std")]
         self.reference_usage_index.clear();
     }
 }
 
+/// Split a complete document into blocks with a fresh stream (see `MdStream::parse_complete`).
+pub fn parse_blocks(text: &str, opts: Options) -> alloc::vec::IntoIter<Block> {
+    MdStream::new(opts).parse_complete(text)
+}
+
 impl Default for MdStream {
     fn default() -> Self {
         Self::new(Options::default())
@@ -0,0 +1,129 @@
+//! `MdStream::on_commit` / `on_pending`: push-style callbacks for simple sinks.
+
+use alloc::boxed::Box;
+
+use super::MdStream;
+use crate::types::{Block, BlockStatus, PendingBlockRef};
+
+#[cfg(feature = "sync")]
+pub(super) type CommitCallback = Box<dyn FnMut(&Block) + Send + Sync>;
+#[cfg(not(feature = "sync"))]
+pub(super) type CommitCallback = Box<dyn FnMut(&Block) + Send>;
+
+#[cfg(feature = "sync")]
+pub(super) type PendingCallback = Box<dyn FnMut(Option<&Block>) + Send + Sync>;
+#[cfg(not(feature = "sync"))]
+pub(super) type PendingCallback = Box<dyn FnMut(Option<&Block>) + Send>;
+
+impl MdStream {
+    /// Call `f` with every block committed by `append`/`finalize` (and their `_ref` variants),
+    /// in commit order. Replaces an earlier `on_commit` callback.
+    ///
+    /// Callbacks run synchronously inside those calls, before they return; the returned update
+    /// still carries the same blocks. Blocks of messages ended by `Options::message_terminator`
+    /// are reported first. A reset (`Update::reset`) is not reported.
+    #[cfg(feature = "sync")]
+    pub fn on_commit<F>(&mut self, f: F)
+    where
+        F: FnMut(&Block) + Send + Sync + 'static,
+    {
+        self.commit_callback = Some(Box::new(f));
+    }
+
+    /// Call `f` with every block committed by `append`/`finalize` (and their `_ref` variants),
+    /// in commit order. Replaces an earlier `on_commit` callback.
+    ///
+    /// Callbacks run synchronously inside those calls, before they return; the returned update
+    /// still carries the same blocks. Blocks of messages ended by `Options::message_terminator`
+    /// are reported first. A reset (`Update::reset`) is not reported.
+    #[cfg(not(feature = "sync"))]
+    pub fn on_commit<F>(&mut self, f: F)
+    where
+        F: FnMut(&Block) + Send + 'static,
+    {
+        self.commit_callback = Some(Box::new(f));
+    }
+
+    /// Call `f` with the pending block (or `None`) at the end of every `append`/`finalize` (and
+    /// their `_ref` variants), after the `on_commit` calls. Replaces an earlier `on_pending`
+    /// callback.
+    ///
+    /// Runs synchronously inside those calls, like `on_commit`.
+    #[cfg(feature = "sync")]
+    pub fn on_pending<F>(&mut self, f: F)
+    where
+        F: FnMut(Option<&Block>) + Send + Sync + 'static,
+    {
+        self.pending_callback = Some(Box::new(f));
+    }
+
+    /// Call `f` with the pending block (or `None`) at the end of every `append`/`finalize` (and
+    /// their `_ref` variants), after the `on_commit` calls. Replaces an earlier `on_pending`
+    /// callback.
+    ///
+    /// Runs synchronously inside those calls, like `on_commit`.
+    #[cfg(not(feature = "sync"))]
+    pub fn on_pending<F>(&mut self, f: F)
+    where
+        F: FnMut(Option<&Block>) + Send + 'static,
+    {
+        self.pending_callback = Some(Box::new(f));
+    }
+
+    /// Report the result of an owned `append`/`finalize` to the callbacks.
+    pub(super) fn notify_callbacks(
+        &mut self,
+        finished_messages: &[alloc::vec::Vec<Block>],
+        committed: &[Block],
+        pending: Option<&Block>,
+    ) {
+        if let Some(cb) = &mut self.commit_callback {
+            finished_messages
+                .iter()
+                .flatten()
+                .chain(committed)
+                .for_each(cb);
+        }
+        if let Some(cb) = &mut self.pending_callback {
+            cb(pending);
+        }
+    }
+
+    /// Like `notify_callbacks`, for `append_ref` whose committed blocks are
+    /// `self.committed[committed_start..]`.
+    pub(super) fn notify_callbacks_ref(
+        &mut self,
+        finished_messages: &[alloc::vec::Vec<Block>],
+        committed_start: usize,
+    ) {
+        if self.commit_callback.is_none() && self.pending_callback.is_none() {
+            return;
+        }
+        let mut commit_callback = self.commit_callback.take();
+        if let Some(cb) = &mut commit_callback {
+            finished_messages
+                .iter()
+                .flatten()
+                .chain(&self.committed[committed_start..])
+                .for_each(cb);
+        }
+        self.commit_callback = commit_callback;
+
+        let mut pending_callback = self.pending_callback.take();
+        if let Some(cb) = &mut pending_callback {
+            let pending = self.current_pending_ref_readonly().map(pending_block);
+            cb(pending.as_ref());
+        }
+        self.pending_callback = pending_callback;
+    }
+}
+
+fn pending_block(p: PendingBlockRef<'_>) -> Block {
+    Block {
+        id: p.id,
+        status: BlockStatus::Pending,
+        kind: p.kind,
+        raw: p.raw.into(),
+        display: p.display.map(Into::into),
+    }
+}
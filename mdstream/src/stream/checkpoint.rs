@@ -0,0 +1,118 @@
+use alloc::string::String;
+use alloc::vec::Vec;
+
+use super::lines::Line;
+#[cfg(feature = "std")]
+use super::refs::ReferenceUsageIndex;
+use super::{BlockMode, MdStream};
+use crate::types::{Block, BlockId};
+
+/// A snapshot of an `MdStream`'s parsing state, for speculative parsing (preview/undo).
+///
+/// Created by [`MdStream::checkpoint`] and applied with [`MdStream::restore`]. The snapshot copies
+/// the buffer and committed blocks, so it costs O(buffer) to take.
+///
+/// Pending transformers and boundary plugins are not captured: transformers keep their current
+/// state, and the active boundary plugin (if any) is re-fed the lines of the current block.
+#[derive(Debug, Clone)]
+pub struct MdStreamCheckpoint {
+    buffer: String,
+    lines: Vec<Line>,
+    committed: Vec<Block>,
+    processed_line: usize,
+    current_block_start_line: usize,
+    current_block_id: BlockId,
+    next_block_id: u64,
+    current_mode: BlockMode,
+    pending_display_cache: Option<String>,
+    pending_display_cache_suffix: Option<String>,
+    active_boundary_plugin: Option<usize>,
+    footnotes_detected: bool,
+    footnote_scan_tail: String,
+    pending_cr: bool,
+    last_finalized_buffer_len: usize,
+    buffer_base_offset: usize,
+    message_terminator_tail: String,
+    #[cfg(feature = "std")]
+    reference_usage_index: ReferenceUsageIndex,
+}
+
+impl MdStream {
+    /// Snapshot the parsing state so it can be restored after feeding speculative text.
+    pub fn checkpoint(&self) -> MdStreamCheckpoint {
+        MdStreamCheckpoint {
+            buffer: self.buffer.clone(),
+            lines: self.lines.clone(),
+            committed: self.committed.clone(),
+            processed_line: self.processed_line,
+            current_block_start_line: self.current_block_start_line,
+            current_block_id: self.current_block_id,
+            next_block_id: self.next_block_id,
+            current_mode: self.current_mode.clone(),
+            pending_display_cache: self.pending_display_cache.clone(),
+            pending_display_cache_suffix: self.pending_display_cache_suffix.clone(),
+            active_boundary_plugin: self.active_boundary_plugin,
+            footnotes_detected: self.footnotes_detected,
+            footnote_scan_tail: self.footnote_scan_tail.clone(),
+            pending_cr: self.pending_cr,
+            last_finalized_buffer_len: self.last_finalized_buffer_len,
+            buffer_base_offset: self.buffer_base_offset,
+            message_terminator_tail: self.message_terminator_tail.clone(),
+            #[cfg(feature = "std")]
+            reference_usage_index: self.reference_usage_index.clone(),
+        }
+    }
+
+    /// Restore a state taken with [`MdStream::checkpoint`] on this stream.
+    ///
+    /// Block IDs continue from the checkpoint, so IDs handed out by the discarded speculative
+    /// appends may be reused.
+    pub fn restore(&mut self, checkpoint: MdStreamCheckpoint) {
+        self.buffer = checkpoint.buffer;
+        self.lines = checkpoint.lines;
+        self.committed = checkpoint.committed;
+        self.processed_line = checkpoint.processed_line;
+        self.current_block_start_line = checkpoint.current_block_start_line;
+        self.current_block_id = checkpoint.current_block_id;
+        self.next_block_id = checkpoint.next_block_id;
+        self.current_mode = checkpoint.current_mode;
+        self.pending_display_cache = checkpoint.pending_display_cache;
+        self.pending_display_cache_suffix = checkpoint.pending_display_cache_suffix;
+        self.active_boundary_plugin = checkpoint.active_boundary_plugin;
+        self.footnotes_detected = checkpoint.footnotes_detected;
+        self.footnote_scan_tail = checkpoint.footnote_scan_tail;
+        self.pending_cr = checkpoint.pending_cr;
+        self.last_finalized_buffer_len = checkpoint.last_finalized_buffer_len;
+        self.buffer_base_offset = checkpoint.buffer_base_offset;
+        self.message_terminator_tail = checkpoint.message_terminator_tail;
+        #[cfg(feature = "std")]
+        {
+            self.reference_usage_index = checkpoint.reference_usage_index;
+        }
+        self.replay_boundary_plugin();
+    }
+
+    /// Rebuild the active boundary plugin's state from the lines of the current block.
+    fn replay_boundary_plugin(&mut self) {
+        for p in self.boundary_plugins.iter_mut() {
+            p.reset();
+        }
+        let BlockMode::CustomBoundary {
+            plugin_index,
+            started: true,
+        } = self.current_mode
+        else {
+            return;
+        };
+        let Some(plugin) = self.boundary_plugins.get_mut(plugin_index) else {
+            return;
+        };
+        for line_index in self.current_block_start_line..self.processed_line {
+            let line = self.lines[line_index].as_str(&self.buffer);
+            if line_index == self.current_block_start_line {
+                plugin.start(line);
+            }
+            let _ = plugin.update(line);
+        }
+    }
+}
@@ -1,3 +1,5 @@
+use alloc::string::ToString;
+
 use super::MdStream;
 use crate::options::FootnotesMode;
 
@@ -36,6 +38,7 @@ impl MdStream {
         while keep_from < self.buffer.len() && !self.buffer.is_char_boundary(keep_from) {
             keep_from += 1;
         }
+        self.buffer_base_offset += keep_from.min(self.buffer.len());
         if keep_from >= self.buffer.len() {
             self.buffer.clear();
         } else {
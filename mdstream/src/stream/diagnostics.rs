@@ -0,0 +1,120 @@
+use alloc::format;
+use alloc::string::String;
+use alloc::vec::Vec;
+use core::ops::Range;
+
+use super::html::MismatchedCloseTag;
+use super::{AppendCtx, MdStream, code_fence_block_is_closed};
+use crate::table::{is_table_delimiter_row, is_table_row, split_table_cells};
+use crate::types::{Block, BlockKind, Diagnostic, DiagnosticKind};
+
+impl MdStream {
+    fn push_diagnostic(
+        &self,
+        ctx: &mut AppendCtx<'_>,
+        range: Range<usize>,
+        kind: DiagnosticKind,
+        message: String,
+    ) {
+        // `range` is buffer-relative; report absolute stream offsets so compaction is invisible.
+        let base = self.buffer_base_offset;
+        ctx.diagnostics.push(Diagnostic {
+            byte_range: base + range.start..base + range.end,
+            kind,
+            message,
+        });
+    }
+
+    pub(super) fn diagnose_html_close_tags(
+        &self,
+        line_start: usize,
+        mismatched: Vec<MismatchedCloseTag>,
+        ctx: &mut AppendCtx<'_>,
+    ) {
+        for m in mismatched {
+            let message = match &m.expected {
+                Some(expected) => format!("closing tag </{}> does not match <{expected}>", m.name),
+                None => format!("closing tag </{}> has no matching opening tag", m.name),
+            };
+            self.push_diagnostic(
+                ctx,
+                line_start + m.range.start..line_start + m.range.end,
+                DiagnosticKind::MismatchedHtmlCloseTag,
+                message,
+            );
+        }
+    }
+
+    /// Run per-block checks on a block that is being committed at `start_off` (buffer-relative).
+    pub(super) fn diagnose_committed_block(
+        &self,
+        block: &Block,
+        start_off: usize,
+        ctx: &mut AppendCtx<'_>,
+    ) {
+        if block.kind != BlockKind::Table {
+            return;
+        }
+
+        let mut header_cells = None;
+        let mut prev: Option<&str> = None;
+        let mut offset = start_off;
+        for line in block.raw.split_inclusive('\n') {
+            let line_start = offset;
+            offset += line.len();
+            let text = line.strip_suffix('\n').unwrap_or(line);
+            if text.trim().is_empty() {
+                if header_cells.is_some() {
+                    break;
+                }
+                prev = None;
+                continue;
+            }
+
+            let expected = match header_cells {
+                Some(n) => n,
+                None if is_table_delimiter_row(text) && prev.is_some_and(is_table_row) => {
+                    let n = prev.map_or(0, |p| split_table_cells(p).len());
+                    header_cells = Some(n);
+                    n
+                }
+                None => {
+                    prev = Some(text);
+                    continue;
+                }
+            };
+
+            let cells = split_table_cells(text).len();
+            if cells != expected {
+                self.push_diagnostic(
+                    ctx,
+                    line_start..line_start + text.len(),
+                    DiagnosticKind::InconsistentTableColumns,
+                    format!("table row has {cells} cells, header has {expected}"),
+                );
+            }
+        }
+    }
+
+    /// Report a code fence that is still open at `finalize`.
+    pub(super) fn diagnose_unterminated_code_fence(
+        &self,
+        raw: &str,
+        start_off: usize,
+        fence_char: char,
+        fence_len: usize,
+        ctx: &mut AppendCtx<'_>,
+    ) {
+        // A closing fence without a trailing newline never reaches line processing.
+        if code_fence_block_is_closed(raw, fence_char, fence_len) {
+            return;
+        }
+        let fence: String = core::iter::repeat_n(fence_char, fence_len).collect();
+        self.push_diagnostic(
+            ctx,
+            start_off..start_off + raw.len(),
+            DiagnosticKind::UnterminatedCodeFence,
+            format!("code fence is not closed (expected `{fence}`)"),
+        );
+    }
+}
@@ -0,0 +1,160 @@
+//! `MdStream::replace_committed_block` and `MdStream::diff_replay`: feed edits of committed blocks
+//! back into the stream.
+
+use alloc::vec::Vec;
+use core::fmt;
+
+use super::{AppendCtx, MdStream};
+use crate::options::Options;
+use crate::types::{Block, BlockId, BlockStatus, Update};
+
+/// Why [`MdStream::replace_committed_block`] rejected an edit.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum ReplaceError {
+    /// No committed block has this id (it may still be pending, or gone after a reset).
+    UnknownBlock(BlockId),
+    /// The new text splits into `blocks` blocks instead of exactly one.
+    NotSingleBlock { blocks: usize },
+}
+
+impl fmt::Display for ReplaceError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            ReplaceError::UnknownBlock(id) => write!(f, "no committed block with id {}", id.0),
+            ReplaceError::NotSingleBlock { blocks } => {
+                write!(f, "edited text parses as {blocks} blocks, expected 1")
+            }
+        }
+    }
+}
+
+impl core::error::Error for ReplaceError {}
+
+impl MdStream {
+    /// Replace the text of a committed block, e.g. after the user edited it in an editor.
+    ///
+    /// `new_raw` is parsed on its own with the stream's options (and boundary plugins, unless one
+    /// is tracking the pending block) and must still form exactly one block; its kind may change.
+    /// Pending transformers do not run on the new `display`.
+    ///
+    /// The update lists the edited block in `invalidated` and carries it in `reemitted`, so
+    /// `DocumentState::apply` swaps it in place. With `ReferenceDefinitionsMode::Invalidate`, users of
+    /// reference definitions the old or new text contains are invalidated as well. The buffer and
+    /// the pending block are not touched.
+    pub fn replace_committed_block(
+        &mut self,
+        id: BlockId,
+        new_raw: &str,
+    ) -> Result<Update, ReplaceError> {
+        let idx = self
+            .committed
+            .binary_search_by_key(&id, |b| b.id)
+            .map_err(|_| ReplaceError::UnknownBlock(id))?;
+
+        let mut parsed = self.parse_detached(new_raw);
+        if parsed.len() != 1 {
+            return Err(ReplaceError::NotSingleBlock {
+                blocks: parsed.len(),
+            });
+        }
+        let parsed = parsed.remove(0);
+        let block = Block {
+            id,
+            status: BlockStatus::Committed,
+            kind: parsed.kind,
+            raw: parsed.raw,
+            display: parsed.display,
+        };
+
+        let mut update = Update::empty();
+        let mut ctx = AppendCtx::new(None);
+        ctx.invalidated.push(id);
+        ctx.reemitted.push(block.clone());
+        let old = core::mem::replace(&mut self.committed[idx], block);
+        #[cfg(feature = "std")]
+        {
+            let new = self.committed[idx].clone();
+            self.retrack_reference_definitions(&old, &new, &mut ctx);
+        }
+        #[cfg(not(feature = "std"))]
+        drop(old);
+
+        update.invalidated = ctx.invalidated;
+        update.reemitted = ctx.reemitted;
+        update.pending = self.current_pending_block();
+        Ok(update)
+    }
+
+    /// Re-parse an edited document and report only what changed since `prev`, the committed blocks
+    /// of an earlier parse of it (in order, as `DocumentState::committed` holds them).
+    ///
+    /// The stream is reset, then `text` is appended and finalized. Leading blocks with the same kind
+    /// and `raw` as in `prev` keep their old ids and are not emitted again. `committed` carries only
+    /// the blocks after that shared prefix, with fresh ids above every id in `prev`, and
+    /// `invalidated` lists the ids of the `prev` blocks they replace: drop those before appending
+    /// `committed` (`DocumentState::apply` keeps invalidated blocks). Afterwards the stream holds
+    /// the full new document, so `committed_block_by_id` and `replace_committed_block` see the
+    /// reused ids.
+    ///
+    /// `on_commit` sees only the blocks of `committed` (with their final ids), and `on_pending`
+    /// is called once with `None`.
+    pub fn diff_replay(&mut self, prev: &[Block], text: &str) -> Update {
+        self.reset();
+        // The re-parse would report every block, reused ones included, under throwaway ids.
+        let commit_callback = self.commit_callback.take();
+        let pending_callback = self.pending_callback.take();
+        let mut blocks: Vec<Block> = self.parse_complete(text).collect();
+        self.commit_callback = commit_callback;
+        self.pending_callback = pending_callback;
+        let shared = prev
+            .iter()
+            .zip(&blocks)
+            .take_while(|(old, new)| old.kind == new.kind && old.raw == new.raw)
+            .count();
+
+        let mut next_id = prev.iter().map(|b| b.id.0).max().unwrap_or(0) + 1;
+        for (block, old) in blocks[..shared].iter_mut().zip(prev) {
+            block.id = old.id;
+        }
+        for block in &mut blocks[shared..] {
+            block.id = BlockId(next_id);
+            next_id += 1;
+        }
+        self.current_block_id = BlockId(next_id);
+        self.next_block_id = next_id + 1;
+        self.committed = blocks;
+        #[cfg(feature = "std")]
+        self.reindex_reference_usages();
+
+        let mut update = Update::empty();
+        update.committed = self.committed[shared..].to_vec();
+        update.invalidated = prev[shared..].iter().map(|b| b.id).collect();
+        self.notify_callbacks(&[], &update.committed, None);
+        update
+    }
+
+    /// Split `text` with a scratch stream using this stream's options.
+    fn parse_detached(&mut self, text: &str) -> Vec<Block> {
+        let opts = Options {
+            message_terminator: None,
+            diagnostics: false,
+            #[cfg(feature = "std")]
+            instrument: None,
+            ..self.opts.clone()
+        };
+        let mut scratch = MdStream::new(opts);
+        // Plugins are stateful: only lend them while none is tracking the pending block.
+        let lend_plugins = self.active_boundary_plugin.is_none();
+        if lend_plugins {
+            scratch.boundary_plugins = core::mem::take(&mut self.boundary_plugins);
+        }
+        let blocks = scratch.parse_complete(text).collect();
+        if lend_plugins {
+            self.boundary_plugins = core::mem::take(&mut scratch.boundary_plugins);
+            for p in &mut self.boundary_plugins {
+                p.reset();
+            }
+        }
+        blocks
+    }
+}
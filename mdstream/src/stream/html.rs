@@ -1,3 +1,6 @@
+use alloc::string::String;
+use alloc::vec::Vec;
+
 pub(super) fn html_block_start_state(line: &str) -> Option<(Vec<String>, bool)> {
     // Best-effort HTML block start (block-level):
     // - up to 3 leading spaces
@@ -20,7 +23,7 @@ pub(super) fn html_block_start_state(line: &str) -> Option<(Vec<String>, bool)>
 }
 
 #[derive(Debug, Clone)]
-enum HtmlTag {
+pub(crate) enum HtmlTag {
     Opening { name: String, self_closing: bool },
     Closing { name: String },
     CommentOpen,
@@ -53,7 +56,7 @@ fn is_void_html_tag(name: &str) -> bool {
     )
 }
 
-fn parse_tag_at(s: &str, lt_index: usize) -> Option<(HtmlTag, &str)> {
+pub(crate) fn parse_tag_at(s: &str, lt_index: usize) -> Option<(HtmlTag, &str)> {
     // Parse a tag starting at '<' (at byte offset lt_index within s).
     let bytes = s.as_bytes();
     if lt_index >= bytes.len() || bytes[lt_index] != b'<' {
@@ -113,7 +116,41 @@ fn parse_tag_at(s: &str, lt_index: usize) -> Option<(HtmlTag, &str)> {
     ))
 }
 
-fn apply_tag_to_stack(tag: &HtmlTag, rest: &str, stack: &mut Vec<String>, in_comment: &mut bool) {
+/// Whether `line` has text after its last tag or comment end, as in `<div>x</div> more`.
+pub(super) fn has_text_after_last_tag(line: &str) -> bool {
+    let mut tail = line;
+    let mut s = line;
+    while let Some(lt) = s.find('<') {
+        match parse_tag_at(&s[lt..], 0) {
+            Some((_, rest)) => {
+                tail = rest;
+                s = rest;
+            }
+            None => s = &s[lt + 1..],
+        }
+    }
+    if let Some(pos) = tail.rfind("-->") {
+        tail = &tail[pos + 3..];
+    }
+    !tail.trim().is_empty()
+}
+
+/// A closing tag that did not match the innermost open tag.
+#[derive(Debug, Clone)]
+pub(super) struct MismatchedCloseTag {
+    /// Byte range of the closing tag, relative to the line.
+    pub(super) range: core::ops::Range<usize>,
+    pub(super) name: String,
+    pub(super) expected: Option<String>,
+}
+
+/// Returns `false` if `tag` is a closing tag that does not match the top of the stack.
+fn apply_tag_to_stack(
+    tag: &HtmlTag,
+    rest: &str,
+    stack: &mut Vec<String>,
+    in_comment: &mut bool,
+) -> bool {
     match tag {
         HtmlTag::CommentOpen => {
             // If the comment closes on the same line, do not enter comment mode.
@@ -131,17 +168,25 @@ fn apply_tag_to_stack(tag: &HtmlTag, rest: &str, stack: &mut Vec<String>, in_com
                 stack.pop();
             } else {
                 // Best-effort: do not attempt arbitrary stack rewrites.
+                return false;
             }
         }
     }
+    true
 }
 
-pub(super) fn update_html_block_state(line: &str, stack: &mut Vec<String>, in_comment: &mut bool) {
+/// Update the HTML block state with `line`, returning any mismatched closing tags found on it.
+pub(super) fn update_html_block_state(
+    line: &str,
+    stack: &mut Vec<String>,
+    in_comment: &mut bool,
+) -> Vec<MismatchedCloseTag> {
+    let mut mismatched = Vec::new();
     let mut s = line;
     loop {
         if *in_comment {
             let Some(pos) = s.find("-->") else {
-                return;
+                return mismatched;
             };
             *in_comment = false;
             s = &s[pos + 3..];
@@ -149,7 +194,7 @@ pub(super) fn update_html_block_state(line: &str, stack: &mut Vec<String>, in_co
         }
 
         let Some(lt_rel) = s.find('<') else {
-            return;
+            return mismatched;
         };
         let lt = lt_rel;
         let after = &s[lt..];
@@ -157,7 +202,18 @@ pub(super) fn update_html_block_state(line: &str, stack: &mut Vec<String>, in_co
             s = &s[lt + 1..];
             continue;
         };
-        apply_tag_to_stack(&tag, rest, stack, in_comment);
+        if !apply_tag_to_stack(&tag, rest, stack, in_comment) {
+            // A rejected closing tag leaves the stack untouched.
+            if let HtmlTag::Closing { name } = tag {
+                let start = line.len() - after.len();
+                let end = line.len() - rest.len();
+                mismatched.push(MismatchedCloseTag {
+                    range: start..end,
+                    name,
+                    expected: stack.last().cloned(),
+                });
+            }
+        }
 
         // Continue scanning after the parsed tag opener/closer.
         s = rest;
@@ -0,0 +1,71 @@
+//! `Options::instrument` support. Without `std` there is no clock, so timers are never started.
+
+use super::MdStream;
+
+#[cfg(feature = "std")]
+pub(super) struct AppendTimer {
+    start: std::time::Instant,
+    chunk_bytes: usize,
+    termination_start: Option<std::time::Instant>,
+}
+
+#[cfg(not(feature = "std"))]
+pub(super) struct AppendTimer;
+
+impl AppendTimer {
+    /// Mark the start of pending display work.
+    pub(super) fn start_termination(&mut self) {
+        #[cfg(feature = "std")]
+        {
+            self.termination_start = Some(std::time::Instant::now());
+        }
+    }
+}
+
+#[cfg(feature = "std")]
+fn nanos(d: std::time::Duration) -> u64 {
+    u64::try_from(d.as_nanos()).unwrap_or(u64::MAX)
+}
+
+impl MdStream {
+    /// Start timing an `append` call; `None` unless `Options::instrument` is set.
+    #[cfg_attr(not(feature = "std"), allow(unused_variables))]
+    pub(super) fn start_append_timer(&self, chunk_bytes: usize) -> Option<AppendTimer> {
+        #[cfg(feature = "std")]
+        {
+            self.opts.instrument.as_ref()?;
+            Some(AppendTimer {
+                start: std::time::Instant::now(),
+                chunk_bytes,
+                termination_start: None,
+            })
+        }
+        #[cfg(not(feature = "std"))]
+        None
+    }
+
+    #[cfg_attr(not(feature = "std"), allow(unused_variables))]
+    pub(super) fn finish_append_timer(
+        &self,
+        timer: Option<AppendTimer>,
+        lines_processed: usize,
+        committed: usize,
+        whitespace_fast_path: bool,
+    ) {
+        #[cfg(feature = "std")]
+        {
+            let (Some(timer), Some(hook)) = (timer, self.opts.instrument.as_ref()) else {
+                return;
+            };
+            let termination_nanos = timer.termination_start.map_or(0, |t| nanos(t.elapsed()));
+            hook(crate::types::AppendTiming {
+                chunk_bytes: timer.chunk_bytes,
+                lines_processed,
+                committed,
+                whitespace_fast_path,
+                termination_nanos,
+                total_nanos: nanos(timer.start.elapsed()),
+            });
+        }
+    }
+}
@@ -1,4 +1,5 @@
-use std::borrow::Cow;
+use alloc::borrow::Cow;
+use alloc::string::{String, ToString};
 
 use super::MdStream;
 
@@ -102,6 +103,22 @@ impl MdStream {
         Cow::Owned(out)
     }
 
+    /// Start offset of the pending snapshot for a block starting at `start_off`.
+    ///
+    /// Only a newline-free last line longer than `Options::max_pending_line_bytes` is cut; the
+    /// snapshot then starts inside that line, so earlier lines of the block are left out too.
+    pub(super) fn bounded_pending_start(&self, start_off: usize) -> usize {
+        let Some(limit) = self.opts.max_pending_line_bytes else {
+            return start_off;
+        };
+        let last_line_start = self.lines.last().map_or(0, |l| l.start).max(start_off);
+        let tail = &self.buffer[last_line_start..];
+        if tail.len() <= limit {
+            return start_off;
+        }
+        self.buffer.len() - take_suffix_at_char_boundary(tail, limit).len()
+    }
+
     pub(super) fn append_to_lines(&mut self, chunk: &str) {
         let start_offset = self.buffer.len();
         self.buffer.push_str(chunk);
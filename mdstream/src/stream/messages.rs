@@ -0,0 +1,50 @@
+use alloc::string::ToString;
+
+use super::{AppendCtx, MdStream};
+
+/// Length of the longest suffix of `text` that is a proper prefix of `sentinel`.
+fn partial_sentinel_len(text: &str, sentinel: &str) -> usize {
+    let max = (sentinel.len() - 1).min(text.len());
+    (1..=max)
+        .rev()
+        .find(|&k| {
+            sentinel.is_char_boundary(k)
+                && text.is_char_boundary(text.len() - k)
+                && text.ends_with(&sentinel[..k])
+        })
+        .unwrap_or(0)
+}
+
+impl MdStream {
+    /// `append` with `Options::message_terminator`: split the input at sentinels, finalizing and
+    /// resetting the stream at each one.
+    pub(super) fn append_with_message_terminator(&mut self, chunk: &str, ctx: &mut AppendCtx<'_>) {
+        let sentinel = self.opts.message_terminator.clone().unwrap_or_default();
+        let mut text = core::mem::take(&mut self.message_terminator_tail);
+        text.push_str(chunk);
+
+        let mut rest = text.as_str();
+        while let Some(pos) = rest.find(&sentinel) {
+            let message_start = self.committed.len();
+            self.append_chunk(&rest[..pos], ctx);
+            let finalized = self.finalize_core(false);
+            ctx.diagnostics.extend(finalized.diagnostics);
+            // Everything committed since the call (or the previous sentinel) belongs to the
+            // finished message, not to the new one.
+            ctx.finished_messages
+                .push(self.committed[message_start.min(self.committed.len())..].to_vec());
+            if let Some(out) = ctx.committed_out.as_deref_mut() {
+                out.clear();
+            }
+            ctx.invalidated.clear();
+            ctx.reemitted.clear();
+            ctx.reset = true;
+            self.reset();
+            rest = &rest[pos + sentinel.len()..];
+        }
+
+        let held = partial_sentinel_len(rest, &sentinel);
+        self.append_chunk(&rest[..rest.len() - held], ctx);
+        self.message_terminator_tail = rest[rest.len() - held..].to_string();
+    }
+}
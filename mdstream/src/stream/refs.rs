@@ -1,8 +1,188 @@
-use std::collections::HashSet;
+use std::collections::{HashMap, HashSet, VecDeque};
 
-use crate::reference::normalize_reference_label;
+use super::{AppendCtx, MdStream};
+use crate::options::ReferenceDefinitionsMode;
+use crate::reference::{extract_reference_definition_label, normalize_reference_label};
+use crate::types::{Block, BlockId, BlockKind};
 
-pub(super) fn extract_reference_usages(text: &str) -> HashSet<String> {
+/// Label -> committed blocks that (likely) use it, for reference-definition invalidation.
+///
+/// With a label cap, the oldest labels (by first insertion) are evicted. Evicted labels simply
+/// stop producing invalidations, which only degrades the optimization, never parsing.
+///
+/// A label keeps one id per committed block using it. With `Options::invalidation_max_lookback`,
+/// ids that fell out of the window (which can never be invalidated again) are pruned whenever the
+/// label is used again, so each label holds at most about `lookback + 1` ids. Without a lookback
+/// a label's ids grow with the blocks using it, like `MdStream::committed` itself.
+#[derive(Debug, Default, Clone)]
+pub(super) struct ReferenceUsageIndex {
+    usages: HashMap<String, HashSet<BlockId>>,
+    order: VecDeque<String>,
+}
+
+impl ReferenceUsageIndex {
+    /// Record that block `id` uses `label`, dropping the label's ids below `min_id`.
+    pub(super) fn insert(
+        &mut self,
+        label: String,
+        id: BlockId,
+        max_labels: Option<usize>,
+        min_id: BlockId,
+    ) {
+        if let Some(ids) = self.usages.get_mut(&label) {
+            ids.retain(|&used| used >= min_id);
+            ids.insert(id);
+            return;
+        }
+        if max_labels == Some(0) {
+            return;
+        }
+        if let Some(max) = max_labels {
+            while self.order.len() >= max {
+                let Some(oldest) = self.order.pop_front() else {
+                    break;
+                };
+                self.usages.remove(&oldest);
+            }
+        }
+        self.order.push_back(label.clone());
+        self.usages.entry(label).or_default().insert(id);
+    }
+
+    /// Forget every usage recorded for `id` (labels stay in the eviction order).
+    pub(super) fn remove_block(&mut self, id: BlockId) {
+        for ids in self.usages.values_mut() {
+            ids.remove(&id);
+        }
+    }
+
+    pub(super) fn get(&self, label: &str) -> Option<&HashSet<BlockId>> {
+        self.usages.get(label)
+    }
+
+    pub(super) fn clear(&mut self) {
+        self.usages.clear();
+        self.order.clear();
+    }
+}
+
+impl MdStream {
+    /// Index reference usages of a block being committed, and invalidate earlier users of any
+    /// labels it defines.
+    pub(super) fn track_reference_definitions(&mut self, block: &Block, ctx: &mut AppendCtx<'_>) {
+        self.index_reference_usages(block);
+
+        // Emit invalidations when new reference definitions arrive.
+        if self.opts.reference_definitions == ReferenceDefinitionsMode::Invalidate {
+            let labels = defined_labels(block);
+            self.invalidate_reference_users(labels, block.id, ctx);
+        }
+    }
+
+    /// Re-index a committed block replaced by `new`, and invalidate users of labels defined by
+    /// either version (a definition may have been added, changed, or removed).
+    pub(super) fn retrack_reference_definitions(
+        &mut self,
+        old: &Block,
+        new: &Block,
+        ctx: &mut AppendCtx<'_>,
+    ) {
+        self.reference_usage_index.remove_block(old.id);
+        self.index_reference_usages(new);
+
+        if self.opts.reference_definitions == ReferenceDefinitionsMode::Invalidate {
+            let mut labels = defined_labels(old);
+            labels.extend(defined_labels(new));
+            self.invalidate_reference_users(labels, new.id, ctx);
+        }
+    }
+
+    /// Rebuild the usage index from scratch after committed block ids changed.
+    pub(super) fn reindex_reference_usages(&mut self) {
+        self.reference_usage_index.clear();
+        let committed = core::mem::take(&mut self.committed);
+        for block in &committed {
+            self.index_reference_usages(block);
+        }
+        self.committed = committed;
+    }
+
+    /// Index usages for invalidation-based adapters.
+    fn index_reference_usages(&mut self, block: &Block) {
+        if block.kind == BlockKind::CodeFence || !block.raw.contains('[') {
+            return;
+        }
+        let min_id = self.invalidation_min_id();
+        for label in extract_reference_usages(&block.raw) {
+            self.reference_usage_index.insert(
+                label,
+                block.id,
+                self.opts.max_reference_labels,
+                min_id,
+            );
+        }
+    }
+
+    fn invalidate_reference_users(
+        &self,
+        labels: HashSet<String>,
+        definer: BlockId,
+        ctx: &mut AppendCtx<'_>,
+    ) {
+        let min_id = self.invalidation_min_id();
+        let mut invalidated = HashSet::new();
+        for label in labels {
+            if let Some(ids) = self.reference_usage_index.get(&label) {
+                for id in ids {
+                    if *id != definer && *id >= min_id {
+                        invalidated.insert(*id);
+                    }
+                }
+            }
+        }
+        if invalidated.is_empty() {
+            return;
+        }
+        let mut ids: Vec<BlockId> = invalidated.into_iter().collect();
+        ids.sort_by_key(|id| id.0);
+        if self.opts.reemit_invalidated {
+            ctx.reemitted.extend(
+                ids.iter()
+                    .filter_map(|id| self.committed_block_by_id(*id))
+                    .cloned(),
+            );
+        }
+        ctx.invalidated.extend(ids);
+    }
+
+    /// Oldest block id still within `Options::invalidation_max_lookback`.
+    fn invalidation_min_id(&self) -> BlockId {
+        let Some(lookback) = self.opts.invalidation_max_lookback else {
+            return BlockId(0);
+        };
+        if lookback == 0 {
+            return BlockId(u64::MAX);
+        }
+        self.committed
+            .len()
+            .checked_sub(lookback)
+            .map_or(BlockId(0), |idx| self.committed[idx].id)
+    }
+}
+
+/// Labels of the reference definitions in `block` (none inside code fences).
+fn defined_labels(block: &Block) -> HashSet<String> {
+    if block.kind == BlockKind::CodeFence || !block.raw.contains("]:") {
+        return HashSet::new();
+    }
+    block
+        .raw
+        .split('\n')
+        .filter_map(extract_reference_definition_label)
+        .collect()
+}
+
+fn extract_reference_usages(text: &str) -> HashSet<String> {
     // Best-effort extractor for reference-style link labels:
     // - [text][label]
     // - [label][]
@@ -76,3 +256,30 @@ pub(super) fn extract_reference_usages(text: &str) -> HashSet<String> {
     }
     out
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn label_ids_below_min_id_are_pruned_on_insert() {
+        let mut index = ReferenceUsageIndex::default();
+        for id in 1..=100 {
+            // A lookback of 3 blocks: everything older can no longer be invalidated.
+            let min_id = BlockId(id.max(3) - 2);
+            index.insert("ref".to_string(), BlockId(id), None, min_id);
+        }
+        let mut ids: Vec<u64> = index.get("ref").unwrap().iter().map(|id| id.0).collect();
+        ids.sort_unstable();
+        assert_eq!(ids, vec![98, 99, 100]);
+    }
+
+    #[test]
+    fn label_ids_are_kept_without_min_id() {
+        let mut index = ReferenceUsageIndex::default();
+        for id in 1..=100 {
+            index.insert("ref".to_string(), BlockId(id), None, BlockId(0));
+        }
+        assert_eq!(index.get("ref").unwrap().len(), 100);
+    }
+}
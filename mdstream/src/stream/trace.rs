@@ -0,0 +1,46 @@
+//! `Options::trace` support: report block-mode changes (`trace` feature).
+
+#[cfg(feature = "trace")]
+pub(super) use crate::types::ModeChangeSite as TraceSite;
+
+/// Stand-in so mode changes can name their site when tracing is compiled out.
+#[cfg(not(feature = "trace"))]
+#[derive(Debug, Clone, Copy)]
+pub(super) enum TraceSite {
+    StartMode,
+    UpdateMode,
+    Commit,
+}
+
+#[cfg(feature = "trace")]
+impl super::MdStream {
+    pub(super) fn trace_mode_change(
+        &self,
+        line_index: usize,
+        site: TraceSite,
+        to: &super::BlockMode,
+    ) {
+        use alloc::format;
+        use alloc::string::ToString;
+
+        let Some(hook) = self.opts.trace.as_ref() else {
+            return;
+        };
+        let from = format!("{:?}", self.current_mode);
+        let to = format!("{to:?}");
+        if from == to {
+            return;
+        }
+        let line = self
+            .lines
+            .get(line_index)
+            .map_or_else(Default::default, |l| l.as_str(&self.buffer).to_string());
+        hook(&crate::types::ModeChange {
+            site,
+            line_index,
+            line,
+            from,
+            to,
+        });
+    }
+}
@@ -4,6 +4,8 @@ pub struct CodeFenceHeader<'a> {
     pub fence_len: usize,
     /// Entire info string (trimmed), excluding fence markers.
     pub info: &'a str,
+    /// Info string exactly as written after the fence run (untrimmed).
+    pub info_raw: &'a str,
     /// First token of `info`, lowercased if ASCII. Empty means "no language".
     pub language: Option<&'a str>,
 }
@@ -40,7 +42,8 @@ pub fn parse_code_fence_header(line: &str) -> Option<CodeFenceHeader<'_>> {
         return None;
     }
 
-    let info = s[fence_len..].trim();
+    let info_raw = &s[fence_len..];
+    let info = info_raw.trim();
     let language = info
         .split_whitespace()
         .next()
@@ -50,6 +53,7 @@ pub fn parse_code_fence_header(line: &str) -> Option<CodeFenceHeader<'_>> {
         fence_char,
         fence_len,
         info,
+        info_raw,
         language,
     })
 }
@@ -59,6 +63,25 @@ pub fn parse_code_fence_header_from_block(text: &str) -> Option<CodeFenceHeader<
     parse_code_fence_header(first_line)
 }
 
+/// Strip trailing whitespace-only lines (and the final line break) from `text`.
+///
+/// Interior content, including trailing spaces on the last content line, is preserved. Returns a
+/// subslice, so no allocation happens.
+pub fn trim_trailing_blank_lines(text: &str) -> &str {
+    let mut end = text.len();
+    loop {
+        let body = &text[..end];
+        let line_start = body.rfind('\n').map_or(0, |i| i + 1);
+        if !body[line_start..].trim().is_empty() {
+            return body;
+        }
+        if line_start == 0 {
+            return "";
+        }
+        end = line_start - 1;
+    }
+}
+
 pub fn is_code_fence_closing_line(line: &str, fence_char: char, fence_len: usize) -> bool {
     // Mirrors `src/stream.rs` fence_end behavior, but exported for consumers.
     let mut s = line;
@@ -0,0 +1,93 @@
+//! GFM table row helpers shared by block splitting and analysis.
+
+use alloc::vec::Vec;
+use core::ops::Range;
+
+use crate::inline::closing_backticks;
+
+/// Byte offsets of cell separators: `|` that is neither escaped (`\|`) nor inside a code span.
+fn separator_positions(line: &str) -> Vec<usize> {
+    let bytes = line.as_bytes();
+    let mut out = Vec::new();
+    let mut i = 0usize;
+    while i < bytes.len() {
+        match bytes[i] {
+            b'\\' => {
+                i += 2;
+                continue;
+            }
+            b'`' => {
+                let start = i;
+                while i < bytes.len() && bytes[i] == b'`' {
+                    i += 1;
+                }
+                let run = i - start;
+                // An unclosed backtick run is literal text.
+                if let Some(close) = closing_backticks(bytes, i, run) {
+                    i = close + run;
+                }
+                continue;
+            }
+            b'|' => out.push(i),
+            _ => {}
+        }
+        i += 1;
+    }
+    out
+}
+
+/// Split a table row into trimmed cells, honoring `\|` escapes and inline code spans.
+///
+/// Optional leading/trailing pipes do not produce empty edge cells: `| a | b |` yields `["a", "b"]`.
+pub(crate) fn split_table_cells(line: &str) -> Vec<&str> {
+    table_cell_ranges(line)
+        .into_iter()
+        .map(|r| &line[r])
+        .collect()
+}
+
+/// Byte ranges in `line` of the cells `split_table_cells` returns.
+pub(crate) fn table_cell_ranges(line: &str) -> Vec<Range<usize>> {
+    let offset = line.len() - line.trim_start().len();
+    let s = line.trim();
+    let seps = separator_positions(s);
+    let mut cells = Vec::with_capacity(seps.len() + 1);
+    let mut start = 0usize;
+    for &sep in &seps {
+        cells.push(start..sep);
+        start = sep + 1;
+    }
+    cells.push(start..s.len());
+
+    if seps.first() == Some(&0) {
+        cells.remove(0);
+    }
+    if !cells.is_empty() && seps.last().is_some_and(|&p| p + 1 == s.len()) {
+        cells.pop();
+    }
+    cells
+        .into_iter()
+        .map(|r| {
+            let cell = &s[r.clone()];
+            let start = r.start + (cell.len() - cell.trim_start().len());
+            let end = r.end - (cell.len() - cell.trim_end().len());
+            offset + start..offset + end.max(start)
+        })
+        .collect()
+}
+
+/// Whether the line has at least one real cell separator (a candidate header row).
+pub(crate) fn is_table_row(line: &str) -> bool {
+    !separator_positions(line.trim()).is_empty()
+}
+
+/// Whether the line is a delimiter row: every cell is `-` runs with optional alignment colons.
+pub(crate) fn is_table_delimiter_row(line: &str) -> bool {
+    let cells = split_table_cells(line);
+    !cells.is_empty()
+        && cells.iter().all(|cell| {
+            let c = cell.strip_prefix(':').unwrap_or(cell);
+            let c = c.strip_suffix(':').unwrap_or(c);
+            !c.is_empty() && c.bytes().all(|b| b == b'-')
+        })
+}
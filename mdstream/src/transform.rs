@@ -1,3 +1,5 @@
+use alloc::string::{String, ToString};
+
 use crate::types::BlockKind;
 
 #[derive(Debug, Clone, Copy)]
@@ -136,3 +138,202 @@ impl PendingTransformer for IncompleteImageDropTransformer {
         Some(out)
     }
 }
+
+/// Typographic replacements for prose display: curly quotes, `--` to `—`, and `...` to `…`.
+///
+/// Only `Paragraph`, `Heading`, `List` and `BlockQuote` blocks are touched. Code spans, code fences
+/// nested in lists/quotes, autolinks/inline HTML, link destinations and backslash escapes are left
+/// as-is. `raw` is never changed.
+///
+/// As a `PendingTransformer` it applies to the pending display, and to committed blocks too when
+/// `Options::commit_with_display` is enabled. `apply` runs it on any text, e.g. a committed `raw`.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct TypographyTransformer {
+    /// `"x"` -> `“x”` and `'x'` / `it's` -> `‘x’` / `it’s`.
+    pub smart_quotes: bool,
+    /// `--` and `---` -> `—`.
+    pub dashes: bool,
+    /// `...` -> `…`.
+    pub ellipses: bool,
+}
+
+impl Default for TypographyTransformer {
+    fn default() -> Self {
+        Self {
+            smart_quotes: true,
+            dashes: true,
+            ellipses: true,
+        }
+    }
+}
+
+fn is_typography_kind(kind: BlockKind) -> bool {
+    matches!(
+        kind,
+        BlockKind::Paragraph | BlockKind::Heading | BlockKind::List | BlockKind::BlockQuote
+    )
+}
+
+/// Line content after blockquote markers and an optional list marker.
+fn strip_container_prefix(line: &str) -> &str {
+    let s = line.trim_start_matches([' ', '\t', '>']);
+    for marker in ["- ", "* ", "+ "] {
+        if let Some(rest) = s.strip_prefix(marker) {
+            return rest.trim_start();
+        }
+    }
+    let digits = s.bytes().take_while(u8::is_ascii_digit).count();
+    if digits > 0 {
+        if let Some(rest) = s[digits..]
+            .strip_prefix(". ")
+            .or_else(|| s[digits..].strip_prefix(") "))
+        {
+            return rest.trim_start();
+        }
+    }
+    s
+}
+
+fn fence_marker(body: &str) -> Option<(u8, usize)> {
+    let first = *body.as_bytes().first()?;
+    if first != b'`' && first != b'~' {
+        return None;
+    }
+    let len = body.bytes().take_while(|&b| b == first).count();
+    (len >= 3).then_some((first, len))
+}
+
+/// Setext underlines and thematic breaks (`---`, `***`, `===`) must keep their characters.
+fn is_rule_line(body: &str) -> bool {
+    let t = body.trim();
+    !t.is_empty()
+        && t.bytes()
+            .all(|b| matches!(b, b'-' | b'=' | b'*' | b'_' | b' '))
+}
+
+fn opens_quote(prev: Option<char>, next: Option<char>) -> bool {
+    let prev_allows = prev.is_none_or(|c| {
+        c.is_whitespace() || matches!(c, '(' | '[' | '{' | '-' | '—' | '"' | '\'' | '“' | '‘')
+    });
+    prev_allows && next.is_some_and(|c| !c.is_whitespace())
+}
+
+impl TypographyTransformer {
+    /// Returns the replaced text for prose `kind`s, or `None` if nothing changed.
+    pub fn apply(&self, kind: BlockKind, text: &str) -> Option<String> {
+        if !is_typography_kind(kind) || !(self.smart_quotes || self.dashes || self.ellipses) {
+            return None;
+        }
+        let mut out = String::with_capacity(text.len() + 8);
+        let mut fence: Option<(u8, usize)> = None;
+        let mut code_run: Option<usize> = None;
+        for line in text.split_inclusive('\n') {
+            let body = strip_container_prefix(line);
+            if let Some((ch, len)) = fence {
+                out.push_str(line);
+                if fence_marker(body).is_some_and(|(c, l)| c == ch && l >= len)
+                    && body.trim_start_matches(ch as char).trim().is_empty()
+                {
+                    fence = None;
+                }
+                continue;
+            }
+            if code_run.is_none() {
+                if let Some(marker) = fence_marker(body) {
+                    fence = Some(marker);
+                    out.push_str(line);
+                    continue;
+                }
+                if is_rule_line(body) {
+                    out.push_str(line);
+                    continue;
+                }
+            }
+            self.apply_line(line, &mut code_run, &mut out);
+        }
+        if out == text { None } else { Some(out) }
+    }
+
+    fn apply_line(&self, line: &str, code_run: &mut Option<usize>, out: &mut String) {
+        let bytes = line.as_bytes();
+        let mut i = 0usize;
+        while i < bytes.len() {
+            if let Some(run) = *code_run {
+                match crate::inline::closing_backticks(bytes, i, run) {
+                    Some(close) => {
+                        out.push_str(&line[i..close + run]);
+                        i = close + run;
+                        *code_run = None;
+                    }
+                    None => {
+                        out.push_str(&line[i..]);
+                        return;
+                    }
+                }
+                continue;
+            }
+            let rest = &line[i..];
+            let c = rest.chars().next().expect("non-empty rest");
+            match c {
+                '\\' => {
+                    let escaped = rest[1..].chars().next().map_or(0, char::len_utf8);
+                    out.push_str(&rest[..1 + escaped]);
+                    i += 1 + escaped;
+                }
+                '`' => {
+                    let run = rest.bytes().take_while(|&b| b == b'`').count();
+                    out.push_str(&rest[..run]);
+                    i += run;
+                    *code_run = Some(run);
+                }
+                '<' if rest[1..]
+                    .starts_with(|n: char| n.is_ascii_alphabetic() || n == '/' || n == '!') =>
+                {
+                    let end = rest.find('>').map_or(rest.len(), |p| p + 1);
+                    out.push_str(&rest[..end]);
+                    i += end;
+                }
+                ']' if rest[1..].starts_with('(') => {
+                    let end = rest.find(')').map_or(rest.len(), |p| p + 1);
+                    out.push_str(&rest[..end]);
+                    i += end;
+                }
+                '"' | '\'' if self.smart_quotes => {
+                    let prev = line[..i].chars().next_back();
+                    let next = rest[1..].chars().next();
+                    let curly = match (c, opens_quote(prev, next)) {
+                        ('"', true) => '“',
+                        ('"', false) => '”',
+                        (_, true) => '‘',
+                        (_, false) => '’',
+                    };
+                    out.push(curly);
+                    i += 1;
+                }
+                '-' if self.dashes => {
+                    let run = rest.bytes().take_while(|&b| b == b'-').count();
+                    if run == 2 || run == 3 {
+                        out.push('—');
+                    } else {
+                        out.push_str(&rest[..run]);
+                    }
+                    i += run;
+                }
+                '.' if self.ellipses && rest.starts_with("...") => {
+                    out.push('…');
+                    i += 3;
+                }
+                _ => {
+                    out.push(c);
+                    i += c.len_utf8();
+                }
+            }
+        }
+    }
+}
+
+impl PendingTransformer for TypographyTransformer {
+    fn transform(&mut self, input: PendingTransformInput<'_>) -> Option<String> {
+        self.apply(input.kind, input.display)
+    }
+}
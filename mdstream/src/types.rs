@@ -1,6 +1,10 @@
-use std::fmt;
+use alloc::string::{String, ToString};
+use alloc::vec::Vec;
+use core::fmt;
+use core::ops::Range;
 
 #[derive(Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Hash)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 pub struct BlockId(pub u64);
 
 impl fmt::Debug for BlockId {
@@ -10,12 +14,14 @@ impl fmt::Debug for BlockId {
 }
 
 #[derive(Debug, Clone, Copy, PartialEq, Eq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 pub enum BlockStatus {
     Committed,
     Pending,
 }
 
 #[derive(Debug, Clone, Copy, PartialEq, Eq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 pub enum BlockKind {
     Paragraph,
     Heading,
@@ -27,16 +33,27 @@ pub enum BlockKind {
     HtmlBlock,
     MathBlock,
     FootnoteDefinition,
+    /// Pandoc-style definition list (requires `Options::definition_lists`).
+    DefinitionList,
+    /// Blank lines no other block owns (requires `Options::emit_blank_blocks`).
+    Blank,
     Unknown,
 }
 
 #[derive(Debug, Clone, PartialEq, Eq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 pub struct Block {
     pub id: BlockId,
     pub status: BlockStatus,
     pub kind: BlockKind,
     pub raw: String,
     /// Optional display string for pending blocks (remend-like termination, JSON repair, etc.).
+    ///
+    /// Committed blocks only carry one when `Options::commit_with_display` is enabled.
+    #[cfg_attr(
+        feature = "serde",
+        serde(default, skip_serializing_if = "Option::is_none")
+    )]
     pub display: Option<String>,
 }
 
@@ -45,6 +62,16 @@ impl Block {
         self.display.as_deref().unwrap_or(&self.raw)
     }
 
+    /// `raw` without trailing blank lines (e.g. `"A\n\n"` -> `"A"`).
+    pub fn raw_trimmed(&self) -> &str {
+        crate::syntax::trim_trailing_blank_lines(&self.raw)
+    }
+
+    /// [`Block::display_or_raw`] without trailing blank lines.
+    pub fn display_trimmed(&self) -> &str {
+        crate::syntax::trim_trailing_blank_lines(self.display_or_raw())
+    }
+
     pub fn code_fence_header(&self) -> Option<crate::syntax::CodeFenceHeader<'_>> {
         if self.kind != BlockKind::CodeFence {
             return None;
@@ -55,6 +82,33 @@ impl Block {
     pub fn code_fence_language(&self) -> Option<&str> {
         self.code_fence_header().and_then(|h| h.language)
     }
+
+    /// Trimmed info string of a code fence (e.g. `rust,ignore`).
+    pub fn code_fence_info(&self) -> Option<&str> {
+        self.code_fence_header().map(|h| h.info)
+    }
+
+    /// Untrimmed info string of a code fence, exactly as written after the fence markers.
+    pub fn code_fence_info_raw(&self) -> Option<&str> {
+        self.code_fence_header().map(|h| h.info_raw)
+    }
+
+    /// Whether the block can be rendered (e.g. syntax-highlighted) as its final kind yet.
+    ///
+    /// `false` for a pending code fence without its closing fence and pending math with an odd
+    /// number of `$$`, the same checks as `BlockHintAnalyzer`; `true` otherwise.
+    pub fn is_renderable(&self) -> bool {
+        if self.status != BlockStatus::Pending {
+            return true;
+        }
+        match self.kind {
+            BlockKind::CodeFence => crate::analyze::code_fence_is_closed(&self.raw),
+            BlockKind::MathBlock => {
+                crate::analyze::count_double_dollars_unescaped(&self.raw) % 2 == 0
+            }
+            _ => true,
+        }
+    }
 }
 
 /// A borrowed view of the current pending block.
@@ -78,9 +132,83 @@ impl<'a> PendingBlockRef<'a> {
     pub fn display_or_raw(&self) -> &'a str {
         self.display.unwrap_or(self.raw)
     }
+
+    /// [`PendingBlockRef::display_or_raw`] without trailing blank lines.
+    pub fn display_trimmed(&self) -> &'a str {
+        crate::syntax::trim_trailing_blank_lines(self.display_or_raw())
+    }
 }
 
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub enum DiagnosticKind {
+    /// An HTML closing tag that does not match the innermost open tag.
+    MismatchedHtmlCloseTag,
+    /// A fenced code block that was still open when the stream was finalized.
+    UnterminatedCodeFence,
+    /// A table row whose cell count differs from the header row.
+    InconsistentTableColumns,
+}
+
+/// A non-fatal structural diagnostic.
+///
+/// Diagnostics are only collected when `Options::diagnostics` is enabled. They never change how
+/// the stream splits blocks; parsing stays best-effort.
 #[derive(Debug, Clone, PartialEq, Eq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct Diagnostic {
+    /// Byte range in the (newline-normalized) input stream, counted from the first appended byte.
+    pub byte_range: Range<usize>,
+    pub kind: DiagnosticKind,
+    pub message: String,
+}
+
+/// Where a block-mode change reported to `Options::trace` happened.
+#[cfg(feature = "trace")]
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum ModeChangeSite {
+    /// A line opened a block (`start_mode_for_line`).
+    StartMode,
+    /// A line upgraded the open block, e.g. a paragraph to a table or setext heading.
+    UpdateMode,
+    /// A block was committed, ending at `line_index`.
+    Commit,
+}
+
+/// A block-mode change, reported to `Options::trace` (`trace` feature).
+///
+/// `from`/`to` are `Debug` renderings of the splitter's internal mode. They are meant for reading
+/// while debugging block boundaries and may change between versions.
+#[cfg(feature = "trace")]
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct ModeChange {
+    pub site: ModeChangeSite,
+    /// Index of the line in the stream's line table (restarts after buffer compaction).
+    pub line_index: usize,
+    pub line: String,
+    pub from: String,
+    pub to: String,
+}
+
+/// Per-`append` measurements reported to `Options::instrument`.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub struct AppendTiming {
+    /// Length of the appended chunk in bytes.
+    pub chunk_bytes: usize,
+    /// Complete lines run through block splitting during this call.
+    pub lines_processed: usize,
+    /// Blocks committed by this call.
+    pub committed: usize,
+    /// The chunk was only spaces/tabs and skipped block boundary re-evaluation.
+    pub whitespace_fast_path: bool,
+    /// Time spent building the pending display (terminator and pending transformers).
+    pub termination_nanos: u64,
+    /// Time spent in the whole call.
+    pub total_nanos: u64,
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 pub struct Update {
     pub committed: Vec<Block>,
     pub pending: Option<Block>,
@@ -93,6 +221,31 @@ pub struct Update {
     ///
     /// Note: populated in post-MVP invalidation mode.
     pub invalidated: Vec<BlockId>,
+    /// Copies of the `invalidated` committed blocks (only with `Options::reemit_invalidated`).
+    ///
+    /// Their `raw` is unchanged, but their rendering may differ now (e.g. a late reference
+    /// definition); re-render them in place by id. `apply_to` replaces them for you.
+    #[cfg_attr(
+        feature = "serde",
+        serde(default, skip_serializing_if = "Vec::is_empty")
+    )]
+    pub reemitted: Vec<Block>,
+    /// Structural diagnostics found by the triggering call (empty unless `Options::diagnostics`).
+    #[cfg_attr(
+        feature = "serde",
+        serde(default, skip_serializing_if = "Vec::is_empty")
+    )]
+    pub diagnostics: Vec<Diagnostic>,
+    /// Messages ended by `Options::message_terminator` during the triggering call, oldest first.
+    ///
+    /// Each entry holds the blocks committed for that message in this call (including what
+    /// `finalize` would have committed). When non-empty, `reset` is set and `committed`/`pending`
+    /// belong to the new message.
+    #[cfg_attr(
+        feature = "serde",
+        serde(default, skip_serializing_if = "Vec::is_empty")
+    )]
+    pub finished_messages: Vec<Vec<Block>>,
 }
 
 #[derive(Debug, Clone, PartialEq, Eq)]
@@ -107,14 +260,17 @@ pub struct AppliedUpdate {
 ///   blocks newly committed by the triggering call.
 /// - `pending` borrows from the stream buffer and/or the stream's pending display cache.
 ///
-/// This is not suitable for sending across threads/tasks. Use [`UpdateRef::to_owned`] to convert
-/// to an owned [`Update`] if needed.
+/// This is not suitable for sending across threads/tasks (or serializing). Use
+/// [`UpdateRef::to_owned`] to convert to an owned [`Update`] if needed.
 #[derive(Debug, Clone, PartialEq, Eq)]
 pub struct UpdateRef<'a> {
     pub committed: &'a [Block],
     pub pending: Option<PendingBlockRef<'a>>,
     pub reset: bool,
     pub invalidated: Vec<BlockId>,
+    pub reemitted: Vec<Block>,
+    pub diagnostics: Vec<Diagnostic>,
+    pub finished_messages: Vec<Vec<Block>>,
 }
 
 impl<'a> UpdateRef<'a> {
@@ -123,6 +279,9 @@ impl<'a> UpdateRef<'a> {
             && self.pending.is_none()
             && !self.reset
             && self.invalidated.is_empty()
+            && self.reemitted.is_empty()
+            && self.diagnostics.is_empty()
+            && self.finished_messages.is_empty()
     }
 
     pub fn to_owned(&self) -> Update {
@@ -137,6 +296,9 @@ impl<'a> UpdateRef<'a> {
             }),
             reset: self.reset,
             invalidated: self.invalidated.clone(),
+            reemitted: self.reemitted.clone(),
+            diagnostics: self.diagnostics.clone(),
+            finished_messages: self.finished_messages.clone(),
         }
     }
 }
@@ -148,6 +310,9 @@ impl Update {
             pending: None,
             reset: false,
             invalidated: Vec::new(),
+            reemitted: Vec::new(),
+            diagnostics: Vec::new(),
+            finished_messages: Vec::new(),
         }
     }
 
@@ -156,12 +321,53 @@ impl Update {
             && self.pending.is_none()
             && !self.reset
             && self.invalidated.is_empty()
+            && self.reemitted.is_empty()
+            && self.diagnostics.is_empty()
+            && self.finished_messages.is_empty()
     }
 
     pub fn blocks(&self) -> impl Iterator<Item = &Block> {
         self.committed.iter().chain(self.pending.iter())
     }
 
+    /// Fold a later update into this one, as if both had been produced by a single call.
+    ///
+    /// Committed blocks are concatenated, the later pending block wins, and invalidated IDs (and
+    /// re-emitted blocks) are unioned. If `next` is a reset, everything committed/invalidated
+    /// before it is dropped and
+    /// the merged update is a reset too. Diagnostics and finished messages are always kept; when
+    /// `next` ends a message (`Options::message_terminator`), the blocks committed here before it
+    /// move to the front of that message.
+    pub fn merge(&mut self, mut next: Update) {
+        if next.reset {
+            if let Some(first) = next.finished_messages.first_mut() {
+                let mut earlier = core::mem::take(&mut self.committed);
+                earlier.append(first);
+                *first = earlier;
+            }
+            self.reset = true;
+            self.committed = next.committed;
+            self.invalidated = next.invalidated;
+            self.reemitted = next.reemitted;
+        } else {
+            self.committed.extend(next.committed);
+            for id in next.invalidated {
+                if !self.invalidated.contains(&id) {
+                    self.invalidated.push(id);
+                }
+            }
+            for block in next.reemitted {
+                match self.reemitted.iter_mut().find(|b| b.id == block.id) {
+                    Some(existing) => *existing = block,
+                    None => self.reemitted.push(block),
+                }
+            }
+        }
+        self.pending = next.pending;
+        self.diagnostics.extend(next.diagnostics);
+        self.finished_messages.extend(next.finished_messages);
+    }
+
     pub fn apply_to(
         self,
         committed: &mut Vec<Block>,
@@ -172,6 +378,11 @@ impl Update {
             *pending = None;
         }
         committed.extend(self.committed);
+        for block in self.reemitted {
+            if let Some(existing) = committed.iter_mut().find(|b| b.id == block.id) {
+                *existing = block;
+            }
+        }
         *pending = self.pending;
         AppliedUpdate {
             reset: self.reset,
@@ -0,0 +1,85 @@
+//! `CommittedWriter`: write the committed part of a stream to an `io::Write` as it arrives.
+
+use std::io;
+
+use crate::types::{Block, Update, UpdateRef};
+
+/// Writes the `raw` of every committed block, in order, to an underlying writer.
+///
+/// Feed it every `Update` (or `UpdateRef`) of one stream. Since committed blocks never change,
+/// the written bytes are always the concatenation of the committed raws so far, which makes the
+/// stream a pass-through log that still yields block events.
+///
+/// The output is append-only, so the writer cannot follow a stream that takes back text:
+/// - A `reset` that ends messages (`Options::message_terminator`) is fine: the blocks of the
+///   finished messages are written first, then the new message continues after them.
+/// - Any other `reset` (e.g. switching into single-block footnote mode, or `MdStream::reset`)
+///   fails with `io::ErrorKind::InvalidData` once bytes were written, and nothing of that update
+///   is written. Use `FootnotesMode::Invalidate` to avoid footnote resets. Before the first byte a
+///   reset is harmless and ignored.
+/// - `invalidated`/`reemitted` blocks are not rewritten (their `raw` is unchanged, except after
+///   `MdStream::replace_committed_block`, whose edit the output does not reflect).
+#[derive(Debug)]
+pub struct CommittedWriter<W: io::Write> {
+    inner: W,
+    bytes_written: u64,
+}
+
+impl<W: io::Write> CommittedWriter<W> {
+    pub fn new(inner: W) -> Self {
+        Self {
+            inner,
+            bytes_written: 0,
+        }
+    }
+
+    /// Write the blocks `update` commits (including those of finished messages).
+    pub fn write_update(&mut self, update: &Update) -> io::Result<()> {
+        self.write_parts(update.reset, &update.finished_messages, &update.committed)
+    }
+
+    /// Like [`CommittedWriter::write_update`], for the borrowed updates of `MdStream::append_ref`.
+    pub fn write_update_ref(&mut self, update: &UpdateRef<'_>) -> io::Result<()> {
+        self.write_parts(update.reset, &update.finished_messages, update.committed)
+    }
+
+    /// Total bytes written to the underlying writer.
+    pub fn bytes_written(&self) -> u64 {
+        self.bytes_written
+    }
+
+    pub fn flush(&mut self) -> io::Result<()> {
+        self.inner.flush()
+    }
+
+    pub fn get_ref(&self) -> &W {
+        &self.inner
+    }
+
+    pub fn get_mut(&mut self) -> &mut W {
+        &mut self.inner
+    }
+
+    pub fn into_inner(self) -> W {
+        self.inner
+    }
+
+    fn write_parts(
+        &mut self,
+        reset: bool,
+        finished_messages: &[Vec<Block>],
+        committed: &[Block],
+    ) -> io::Result<()> {
+        if reset && finished_messages.is_empty() && self.bytes_written > 0 {
+            return Err(io::Error::new(
+                io::ErrorKind::InvalidData,
+                "stream reset after committed text was written",
+            ));
+        }
+        for block in finished_messages.iter().flatten().chain(committed) {
+            self.inner.write_all(block.raw.as_bytes())?;
+            self.bytes_written += block.raw.len() as u64;
+        }
+        Ok(())
+    }
+}
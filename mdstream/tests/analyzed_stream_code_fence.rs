@@ -17,6 +17,7 @@ fn analyzed_stream_emits_pending_and_committed_meta_for_code_fences() {
             meta: CodeFenceMeta {
                 info: "mermaid".to_string(),
                 language: Some("mermaid".to_string()),
+                language_lower: Some("mermaid".to_string()),
                 class: CodeFenceClass::Mermaid,
             }
         })
@@ -35,6 +36,21 @@ fn analyzed_stream_emits_pending_and_committed_meta_for_code_fences() {
     assert!(s.meta_for(u3.update.committed[0].id).is_some());
 }
 
+#[test]
+fn code_fence_meta_keeps_language_case_and_offers_lowercase() {
+    let mut s = AnalyzedStream::new(Options::default(), CodeFenceAnalyzer);
+    let u = s.append("```Rust\nfn main() {}\n```\n");
+    let meta = &u.committed_meta[0].meta;
+    assert_eq!(meta.language.as_deref(), Some("Rust"));
+    assert_eq!(meta.language_lower.as_deref(), Some("rust"));
+    assert_eq!(meta.class, CodeFenceClass::Other);
+
+    let u = s.append("```\nplain\n```\n");
+    let meta = &u.committed_meta[0].meta;
+    assert_eq!(meta.language, None);
+    assert_eq!(meta.language_lower, None);
+}
+
 #[test]
 fn tuple_analyzer_can_be_chained() {
     #[derive(Default)]
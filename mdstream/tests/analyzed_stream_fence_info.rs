@@ -0,0 +1,74 @@
+use mdstream::{
+    AnalyzedStream, BlockKind, FenceBoundaryPlugin, FenceInfo, FenceInfoAnalyzer, Options,
+};
+
+fn stream() -> AnalyzedStream<FenceInfoAnalyzer> {
+    let plugin = FenceBoundaryPlugin::triple_colon();
+    let mut s = AnalyzedStream::new(Options::default(), FenceInfoAnalyzer::for_plugin(&plugin));
+    s.inner_mut().push_boundary_plugin(plugin);
+    s
+}
+
+#[test]
+fn captures_name_and_attributes_from_opening_line() {
+    let mut s = stream();
+    let u1 = s.append(":::tip{#id}\nUse the force.\n");
+    let pending = u1.pending_meta.expect("pending meta");
+    assert_eq!(
+        pending.meta,
+        FenceInfo {
+            name: Some("tip".to_string()),
+            rest: "{#id}".to_string(),
+        }
+    );
+
+    let u2 = s.append(":::\n\nAfter\n");
+    assert_eq!(u2.update.committed.len(), 1);
+    let block = &u2.update.committed[0];
+    assert_eq!(block.raw, ":::tip{#id}\nUse the force.\n:::\n");
+    assert_eq!(
+        s.meta_for(block.id),
+        Some(&FenceInfo {
+            name: Some("tip".to_string()),
+            rest: "{#id}".to_string(),
+        })
+    );
+    assert_eq!(u2.committed_meta.len(), 1);
+    assert!(u2.pending_meta.is_none());
+}
+
+#[test]
+fn spaced_name_title_and_bare_marker() {
+    let mut a = FenceInfoAnalyzer::default();
+    let info = |a: &mut FenceInfoAnalyzer, raw: &str| {
+        use mdstream::BlockAnalyzer;
+        let mut s = mdstream::MdStream::new(Options::default());
+        let block = s.append(raw).pending.expect("pending");
+        a.analyze_block(&block)
+    };
+    assert_eq!(
+        info(&mut a, "  ::::  warning  Be careful \nx\n"),
+        Some(FenceInfo {
+            name: Some("warning".to_string()),
+            rest: "Be careful".to_string(),
+        })
+    );
+    assert_eq!(
+        info(&mut a, ":::{.note}\nx\n"),
+        Some(FenceInfo {
+            name: None,
+            rest: "{.note}".to_string(),
+        })
+    );
+    assert_eq!(info(&mut a, "::\nx\n"), None);
+    assert_eq!(info(&mut a, "Plain text\n"), None);
+}
+
+#[test]
+fn code_fences_are_ignored() {
+    let plugin = FenceBoundaryPlugin::new('~', 3);
+    let mut s = AnalyzedStream::new(Options::default(), FenceInfoAnalyzer::for_plugin(&plugin));
+    let u = s.append("~~~rust\nfn main() {}\n~~~\n\nAfter\n");
+    assert_eq!(u.update.committed[0].kind, BlockKind::CodeFence);
+    assert!(u.committed_meta.is_empty());
+}
@@ -0,0 +1,73 @@
+use mdstream::{AnalyzedStream, BlockKind, FootnoteAnalyzer, FootnoteMeta, FootnotesMode, Options};
+
+fn stream() -> AnalyzedStream<FootnoteAnalyzer> {
+    let opts = Options {
+        footnotes: FootnotesMode::Invalidate,
+        ..Default::default()
+    };
+    AnalyzedStream::new(opts, FootnoteAnalyzer)
+}
+
+fn meta(label: &str, content: &str) -> FootnoteMeta {
+    FootnoteMeta {
+        label: label.to_string(),
+        content: content.to_string(),
+    }
+}
+
+#[test]
+fn single_line_definition() {
+    let mut s = stream();
+    let u1 = s.append("Text[^1].\n\n[^1]: The note.\n");
+    let pending = u1.pending_meta.expect("pending meta");
+    assert_eq!(pending.meta, meta("1", "The note."));
+
+    let u2 = s.finalize();
+    let block = u2
+        .update
+        .committed
+        .iter()
+        .find(|b| b.kind == BlockKind::FootnoteDefinition)
+        .expect("footnote block");
+    assert_eq!(s.meta_for(block.id), Some(&meta("1", "The note.")));
+}
+
+#[test]
+fn multi_line_definition_is_dedented() {
+    let mut s = stream();
+    let u1 = s.append("[^long-note]: First line\n    second line\n\n\tNew paragraph\n\nAfter\n");
+    let u2 = s.finalize();
+    let footnote = u1
+        .update
+        .committed
+        .iter()
+        .chain(u2.update.committed.iter())
+        .find(|b| b.kind == BlockKind::FootnoteDefinition)
+        .map(|b| b.id)
+        .expect("footnote block");
+    assert_eq!(
+        s.meta_for(footnote),
+        Some(&meta(
+            "long-note",
+            "First line\nsecond line\n\nNew paragraph"
+        ))
+    );
+}
+
+#[test]
+fn other_blocks_and_single_block_mode_have_no_meta() {
+    let mut s = stream();
+    let u = s.append("# Title\n\nPlain paragraph\n\n");
+    assert!(u.committed_meta.is_empty());
+
+    let mut single = AnalyzedStream::new(Options::default(), FootnoteAnalyzer);
+    single.append("Text[^1].\n\n[^1]: The note.\n");
+    let u = single.finalize();
+    assert!(
+        u.update
+            .committed
+            .iter()
+            .all(|b| b.kind == BlockKind::Unknown)
+    );
+    assert!(u.committed_meta.is_empty());
+}
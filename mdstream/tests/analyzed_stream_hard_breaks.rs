@@ -0,0 +1,39 @@
+use mdstream::{AnalyzedStream, HardBreakAnalyzer, Options};
+
+fn break_offsets(text: &str) -> Vec<Vec<usize>> {
+    let mut s = AnalyzedStream::new(Options::default(), HardBreakAnalyzer);
+    let mut out = Vec::new();
+    for u in [s.append(text), s.finalize()] {
+        out.extend(u.committed_meta.into_iter().map(|m| m.meta.break_offsets));
+    }
+    out
+}
+
+#[test]
+fn detects_trailing_spaces_and_backslash_breaks() {
+    let text = "one  \ntwo\\\nthree\n";
+    assert_eq!(break_offsets(text), vec![vec![5, 10]]);
+    assert_eq!(&text[5..6], "\n");
+    assert_eq!(&text[10..11], "\n");
+}
+
+#[test]
+fn single_space_and_escaped_backslash_are_not_breaks() {
+    assert_eq!(
+        break_offsets("one \ntwo\\\\\nthree\n"),
+        vec![Vec::<usize>::new()]
+    );
+}
+
+#[test]
+fn break_at_end_of_block_is_ignored() {
+    assert_eq!(
+        break_offsets("last line  \n\n# Heading\\\n"),
+        vec![Vec::<usize>::new(), Vec::new()]
+    );
+}
+
+#[test]
+fn ignores_other_block_kinds() {
+    assert!(break_offsets("```\ncode  \nmore\n```\n").is_empty());
+}
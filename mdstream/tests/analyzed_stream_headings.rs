@@ -0,0 +1,63 @@
+use mdstream::{AnalyzedStream, HeadingAnalyzer, Options};
+
+fn slugs(text: &str) -> Vec<String> {
+    let mut s = AnalyzedStream::new(Options::default(), HeadingAnalyzer::default());
+    let mut out = Vec::new();
+    for u in [s.append(text), s.finalize()] {
+        out.extend(u.committed_meta.into_iter().map(|m| m.meta.slug));
+    }
+    out
+}
+
+#[test]
+fn duplicate_headings_get_suffixes() {
+    assert_eq!(
+        slugs("# Intro\n\nText\n\n## Intro\n\n### Intro\n"),
+        vec!["intro", "intro-1", "intro-2"]
+    );
+}
+
+#[test]
+fn slug_strips_markup_and_punctuation() {
+    let mut s = AnalyzedStream::new(Options::default(), HeadingAnalyzer::default());
+    let u = s.append("## Hello, **`World`**! [Docs](http://x) my_var ##\n");
+    let meta = &u.committed_meta[0].meta;
+    assert_eq!(meta.level, 2);
+    assert_eq!(meta.text, "Hello, World! Docs my_var");
+    assert_eq!(meta.slug, "hello-world-docs-my_var");
+
+    let u = s.append("Setext *title*\n---\n");
+    let meta = &u.committed_meta[0].meta;
+    assert_eq!(meta.level, 2);
+    assert_eq!(meta.slug, "setext-title");
+}
+
+#[test]
+fn generated_suffix_does_not_collide_with_literal_heading() {
+    assert_eq!(slugs("# A\n# A\n# A 1\n"), vec!["a", "a-1", "a-1-1"]);
+}
+
+#[test]
+fn pending_heading_does_not_reserve_slug() {
+    let mut s = AnalyzedStream::new(Options::default(), HeadingAnalyzer::default());
+    let u = s.append("# Intro");
+    assert_eq!(u.pending_meta.unwrap().meta.slug, "intro");
+    let u = s.append(" ");
+    assert_eq!(u.pending_meta.unwrap().meta.slug, "intro");
+    let u = s.append("\n# Intro\n");
+    let got: Vec<_> = u
+        .committed_meta
+        .iter()
+        .map(|m| m.meta.slug.as_str())
+        .collect();
+    assert_eq!(got, vec!["intro", "intro-1"]);
+}
+
+#[test]
+fn reset_clears_slug_state() {
+    let mut s = AnalyzedStream::new(Options::default(), HeadingAnalyzer::default());
+    s.append("# Intro\n");
+    s.reset();
+    let u = s.append("# Intro\n");
+    assert_eq!(u.committed_meta[0].meta.slug, "intro");
+}
@@ -0,0 +1,37 @@
+use mdstream::{AnalyzedStream, HtmlBlockAnalyzer, HtmlBlockMeta, Options};
+
+fn html_meta(text: &str) -> Vec<HtmlBlockMeta> {
+    let mut s = AnalyzedStream::new(Options::default(), HtmlBlockAnalyzer);
+    let mut out = Vec::new();
+    for u in [s.append(text), s.finalize()] {
+        out.extend(u.committed_meta.into_iter().map(|m| m.meta));
+    }
+    out
+}
+
+#[test]
+fn distinguishes_comments_from_elements() {
+    let meta = html_meta("<!-- hidden\nnote -->\n\nText\n\n<DIV class=\"x\">\n<p>hi</p>\n</div>\n");
+    assert_eq!(
+        meta,
+        vec![
+            HtmlBlockMeta {
+                is_comment: true,
+                root_tag: None,
+            },
+            HtmlBlockMeta {
+                is_comment: false,
+                root_tag: Some("div".to_string()),
+            },
+        ]
+    );
+}
+
+#[test]
+fn pending_html_block_reports_root_tag() {
+    let mut s = AnalyzedStream::new(Options::default(), HtmlBlockAnalyzer);
+    let u = s.append("<details>\n<summary>More");
+    let meta = u.pending_meta.expect("pending meta").meta;
+    assert_eq!(meta.root_tag.as_deref(), Some("details"));
+    assert!(!meta.is_comment);
+}
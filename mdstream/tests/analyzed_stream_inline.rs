@@ -0,0 +1,99 @@
+use mdstream::{AnalyzedStream, FootnotesMode, InlineAnalyzer, InlineMeta, Options};
+
+fn inline_meta(text: &str) -> Vec<InlineMeta> {
+    let mut s = AnalyzedStream::new(Options::default(), InlineAnalyzer);
+    let mut out = Vec::new();
+    for u in [s.append(text), s.finalize()] {
+        out.extend(u.committed_meta.into_iter().map(|m| m.meta));
+    }
+    out
+}
+
+fn links(text: &str) -> Vec<(&str, Option<String>, Option<String>)> {
+    let meta = inline_meta(text);
+    assert_eq!(meta.len(), 1);
+    meta.into_iter()
+        .next()
+        .unwrap()
+        .links
+        .into_iter()
+        .map(|l| (&text[l.text_range], l.url, l.reference))
+        .collect()
+}
+
+#[test]
+fn inline_links() {
+    assert_eq!(
+        links("See [the docs](https://docs.rs \"Docs\") and [local](<a b.md>).\n"),
+        vec![
+            ("the docs", Some("https://docs.rs".to_string()), None),
+            ("local", Some("a b.md".to_string()), None),
+        ]
+    );
+    // Parentheses inside the destination are balanced.
+    assert_eq!(
+        links("[wiki](https://en.wikipedia.org/wiki/Rust_(language))"),
+        vec![(
+            "wiki",
+            Some("https://en.wikipedia.org/wiki/Rust_(language)".to_string()),
+            None
+        )]
+    );
+}
+
+#[test]
+fn reference_links() {
+    assert_eq!(
+        links("Full [text][Label], collapsed [Name][] and shortcut [Short].\n"),
+        vec![
+            ("text", None, Some("Label".to_string())),
+            ("Name", None, Some("Name".to_string())),
+            ("Short", None, Some("Short".to_string())),
+        ]
+    );
+}
+
+#[test]
+fn code_spans_and_skipped_brackets() {
+    let text = "Use `a[0](x)` and ``b ` c``, not ![img](p.png), or \\[esc\\].\n";
+    let meta = inline_meta(text);
+    assert_eq!(meta.len(), 1);
+    let spans: Vec<&str> = meta[0]
+        .code_spans
+        .iter()
+        .map(|r| &text[r.clone()])
+        .collect();
+    assert_eq!(spans, vec!["`a[0](x)`", "``b ` c``"]);
+    assert!(meta[0].links.is_empty());
+}
+
+#[test]
+fn footnote_references_are_not_links() {
+    let opts = Options {
+        footnotes: FootnotesMode::Invalidate,
+        ..Default::default()
+    };
+    let mut s = AnalyzedStream::new(opts, InlineAnalyzer);
+    s.append("Claim[^1] and [link](u).\n");
+    let u = s.finalize();
+    let links = &u.committed_meta[0].meta.links;
+    assert_eq!(links.len(), 1);
+    assert_eq!(links[0].url.as_deref(), Some("u"));
+}
+
+#[test]
+fn reference_definitions_and_code_blocks_are_not_links() {
+    assert!(links("[label]: https://example.com\n").is_empty());
+    assert!(inline_meta("```\n[a](b)\n```\n").is_empty());
+}
+
+#[test]
+fn list_items_are_analyzed() {
+    assert_eq!(
+        links("- [one](1)\n- [two](2)\n"),
+        vec![
+            ("one", Some("1".to_string()), None),
+            ("two", Some("2".to_string()), None),
+        ]
+    );
+}
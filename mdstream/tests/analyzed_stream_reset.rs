@@ -0,0 +1,45 @@
+use mdstream::{AnalyzedStream, Block, BlockAnalyzer, BlockId, Options};
+
+#[derive(Debug, Default)]
+struct CountingAnalyzer {
+    seen: usize,
+    resets: usize,
+}
+
+impl BlockAnalyzer for CountingAnalyzer {
+    type Meta = usize;
+
+    fn analyze_block(&mut self, _block: &Block) -> Option<Self::Meta> {
+        self.seen += 1;
+        Some(self.seen)
+    }
+
+    fn reset(&mut self) {
+        self.seen = 0;
+        self.resets += 1;
+    }
+}
+
+#[test]
+fn footnote_reset_clears_analyzer_state_and_committed_meta() {
+    let mut s = AnalyzedStream::new(Options::default(), CountingAnalyzer::default());
+
+    let u1 = s.append("A\n\nB\n\nC");
+    assert!(!u1.update.reset);
+    assert_eq!(u1.committed_meta.len(), 2);
+    assert!(s.meta_for(BlockId(1)).is_some());
+    assert!(s.meta_for(BlockId(2)).is_some());
+    assert_eq!(s.analyzer().resets, 0);
+
+    // Entering single-block footnote mode resets the document mid-stream.
+    let u2 = s.append(" with a note[^1].\n");
+    assert!(u2.update.reset);
+    assert_eq!(s.analyzer().resets, 1);
+    assert!(u2.committed_meta.is_empty());
+    assert!(s.meta_for(BlockId(2)).is_none());
+
+    // The analyzer restarted from a clean state: only the new pending block was analyzed.
+    let pending = u2.pending_meta.expect("pending meta");
+    assert_eq!(pending.id, BlockId(1));
+    assert_eq!(pending.meta, 1);
+}
@@ -0,0 +1,63 @@
+use mdstream::{AnalyzedStream, Options, TableAnalyzer, TableMeta};
+
+fn analyze(text: &str) -> TableMeta {
+    let mut s = AnalyzedStream::new(Options::default(), TableAnalyzer);
+    let mut u = s.append(text);
+    let f = s.finalize();
+    u.committed_meta.extend(f.committed_meta);
+    assert_eq!(u.committed_meta.len(), 1, "{text:?}");
+    u.committed_meta.remove(0).meta
+}
+
+fn cells<'a>(text: &'a str, row: &[core::ops::Range<usize>]) -> Vec<&'a str> {
+    row.iter().map(|r| &text[r.clone()]).collect()
+}
+
+#[test]
+fn maps_header_and_body_cells() {
+    let text = "| Name | Qty |\n|:-----|----:|\n| apple | 3 |\n|  pear  |  12 |\n";
+    let meta = analyze(text);
+    assert_eq!(meta.columns, 2);
+    assert_eq!(meta.header, vec![2..6, 9..12]);
+    assert_eq!(cells(text, &meta.header), ["Name", "Qty"]);
+    assert_eq!(meta.rows.len(), 2);
+    assert_eq!(cells(text, &meta.rows[0]), ["apple", "3"]);
+    assert_eq!(cells(text, &meta.rows[1]), ["pear", "12"]);
+}
+
+#[test]
+fn escaped_pipes_and_code_spans_stay_in_their_cell() {
+    let text = "| Expr | Note |\n|---|---|\n| a \\| b | `x | y` |\n";
+    let meta = analyze(text);
+    assert_eq!(cells(text, &meta.rows[0]), ["a \\| b", "`x | y`"]);
+}
+
+#[test]
+fn ragged_rows_keep_the_cells_they_have() {
+    let text = "| A | B | C |\n|---|---|---|\n| 1 |\n| 1 | 2 | 3 | 4 |\nx | y\n\n";
+    let meta = analyze(text);
+    assert_eq!(meta.columns, 3);
+    assert_eq!(meta.rows.len(), 3);
+    assert_eq!(cells(text, &meta.rows[0]), ["1"]);
+    assert_eq!(cells(text, &meta.rows[1]), ["1", "2", "3", "4"]);
+    assert_eq!(cells(text, &meta.rows[2]), ["x", "y"]);
+}
+
+#[test]
+fn empty_cells_are_empty_ranges() {
+    let text = "| A | B |\n|---|---|\n|   | 2 |\n";
+    let meta = analyze(text);
+    let first = meta.rows[0][0].clone();
+    assert!(first.is_empty());
+    assert_eq!(cells(text, &meta.rows[0]), ["", "2"]);
+}
+
+#[test]
+fn pending_table_meta_before_any_row() {
+    let mut s = AnalyzedStream::new(Options::default(), TableAnalyzer);
+    let u = s.append("| A | B |\n|---|---|\n");
+    let meta = u.pending_meta.expect("pending table meta").meta;
+    assert_eq!(meta.columns, 2);
+    assert_eq!(meta.header.len(), 2);
+    assert!(meta.rows.is_empty());
+}
@@ -37,7 +37,8 @@ fn tagged_block_analyzer_extracts_tag_and_content() {
 #[test]
 fn tagged_block_analyzer_ignores_non_standalone_closing_tag() {
     let mut s = AnalyzedStream::new(Options::default(), TaggedBlockAnalyzer::default());
-    let u = s.append("<thinking>\na</thinking> trailing\n");
+    // Text after the closing tag keeps the HTML block open until a blank line.
+    let u = s.append("<thinking>\na</thinking> trailing\n\nAfter");
     // `<thinking>` is treated as an HTML block by the stream; verify the analyzer still extracts meta.
     let m = u
         .committed_meta
@@ -0,0 +1,65 @@
+use mdstream::{AnalyzedStream, Options, WikiLink, WikiLinkAnalyzer, WikiLinkMeta};
+
+fn analyze(text: &str) -> WikiLinkMeta {
+    let mut s = AnalyzedStream::new(Options::default(), WikiLinkAnalyzer);
+    let mut u = s.append(text);
+    let f = s.finalize();
+    u.committed_meta.extend(f.committed_meta);
+    assert_eq!(u.committed_meta.len(), 1, "{text:?}");
+    u.committed_meta.remove(0).meta
+}
+
+#[test]
+fn bare_and_aliased_wikilinks() {
+    let text = "See [[Page Name]] and [[Other Page|the alias]].\n";
+    let meta = analyze(text);
+    assert_eq!(
+        meta.links,
+        vec![
+            WikiLink {
+                target: "Page Name".to_string(),
+                alias: None,
+                range: 4..17,
+            },
+            WikiLink {
+                target: "Other Page".to_string(),
+                alias: Some("the alias".to_string()),
+                range: 22..46,
+            },
+        ]
+    );
+    assert_eq!(
+        &text[meta.links[1].range.clone()],
+        "[[Other Page|the alias]]"
+    );
+}
+
+#[test]
+fn wikilink_in_code_span_is_ignored() {
+    let meta = analyze("Use `[[Not A Link]]` literally, but [[Real]] counts.\n");
+    let targets: Vec<&str> = meta.links.iter().map(|l| l.target.as_str()).collect();
+    assert_eq!(targets, vec!["Real"]);
+}
+
+#[test]
+fn reference_links_embeds_and_broken_brackets_are_not_wikilinks() {
+    for text in [
+        "A [ref][label] link.\n",
+        "Embed ![[image.png]] here.\n",
+        "Escaped \\[[x]] text.\n",
+        "Unclosed [[Page\nName]] across lines.\n",
+        "Nested [[a [b] c]] and empty [[ ]].\n",
+    ] {
+        assert!(analyze(text).links.is_empty(), "{text:?}");
+    }
+}
+
+#[test]
+fn pending_block_reports_closed_links_only() {
+    let mut s = AnalyzedStream::new(Options::default(), WikiLinkAnalyzer);
+    let u = s.append("- [[Done|ok]] and [[Half");
+    let pending = u.pending_meta.expect("pending meta").meta;
+    assert_eq!(pending.links.len(), 1);
+    assert_eq!(pending.links[0].target, "Done");
+    assert_eq!(pending.links[0].alias.as_deref(), Some("ok"));
+}
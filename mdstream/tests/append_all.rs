@@ -0,0 +1,71 @@
+use mdstream::{BlockId, MdStream, Options, Update};
+
+#[test]
+fn append_all_matches_single_combined_append() {
+    let mut batched = MdStream::new(Options::default());
+    let merged = batched.append_all(["# H", "i\n\n", "Body"]);
+
+    let mut single = MdStream::new(Options::default());
+    let combined = single.append("# Hi\n\nBody");
+
+    assert_eq!(merged.committed, combined.committed);
+    assert_eq!(merged.pending, combined.pending);
+    assert!(!merged.reset);
+    assert_eq!(merged.committed.len(), 1);
+    assert_eq!(
+        merged.pending.as_ref().map(|p| p.raw.as_str()),
+        Some("Body")
+    );
+}
+
+#[test]
+fn append_all_accepts_owned_chunks() {
+    let mut s = MdStream::new(Options::default());
+    let chunks: Vec<String> = vec!["A\n\n".into(), "B".into()];
+    let u = s.append_all(chunks);
+    assert_eq!(u.committed.len(), 1);
+    assert_eq!(u.committed[0].raw, "A\n\n");
+}
+
+#[test]
+fn merge_drops_state_before_reset() {
+    let mut s = MdStream::new(Options::default());
+    // The footnote reference switches the stream into single-block mode mid-batch.
+    let u = s.append_all(["A\n\nB\n\n", "C[^1]\n"]);
+    assert!(u.reset);
+    assert!(u.committed.is_empty());
+    let pending = u.pending.expect("pending");
+    assert_eq!(pending.id, BlockId(1));
+    assert_eq!(pending.raw, "A\n\nB\n\nC[^1]\n");
+}
+
+#[test]
+fn merge_unions_invalidated_ids() {
+    let mut a = Update::empty();
+    a.invalidated = vec![BlockId(1), BlockId(2)];
+    let mut b = Update::empty();
+    b.invalidated = vec![BlockId(2), BlockId(3)];
+    a.merge(b);
+    assert_eq!(a.invalidated, vec![BlockId(1), BlockId(2), BlockId(3)]);
+}
+
+#[test]
+fn merge_keeps_earlier_blocks_of_a_finished_message() {
+    let opts = Options {
+        message_terminator: Some("<EOM>".to_string()),
+        ..Options::default()
+    };
+    let mut s = MdStream::new(opts);
+    let mut u = s.append("First para\n\nSecond");
+    u.merge(s.append(" para\n\n<EOM>next"));
+
+    assert!(u.reset);
+    let finished: Vec<Vec<&str>> = u
+        .finished_messages
+        .iter()
+        .map(|m| m.iter().map(|b| b.raw.as_str()).collect())
+        .collect();
+    assert_eq!(finished, vec![vec!["First para\n\n", "Second para\n\n"]]);
+    assert!(u.committed.is_empty());
+    assert_eq!(u.pending.map(|p| p.raw), Some("next".to_string()));
+}
@@ -0,0 +1,79 @@
+mod support;
+
+use mdstream::{BlockKind, Options};
+use support::{chunk_chars, chunk_whole, collect_final_blocks};
+
+fn blocks(text: &str, opts: Options) -> Vec<(BlockKind, String)> {
+    let whole = collect_final_blocks(chunk_whole(text), opts.clone());
+    assert_eq!(collect_final_blocks(chunk_chars(text), opts), whole);
+    whole
+}
+
+#[test]
+fn one_to_six_hashes_open_a_heading() {
+    for level in [1, 6] {
+        let text = format!("Intro\n{} Title\nAfter\n", "#".repeat(level));
+        assert_eq!(
+            blocks(&text, Options::default()),
+            vec![
+                (BlockKind::Paragraph, "Intro\n".to_string()),
+                (BlockKind::Heading, format!("{} Title\n", "#".repeat(level))),
+                (BlockKind::Paragraph, "After\n".to_string()),
+            ],
+            "level {level}"
+        );
+    }
+}
+
+#[test]
+fn seven_hashes_are_paragraph_text() {
+    assert_eq!(
+        blocks("Intro\n####### text\nmore\n", Options::default()),
+        vec![(
+            BlockKind::Paragraph,
+            "Intro\n####### text\nmore\n".to_string()
+        )]
+    );
+    assert_eq!(
+        blocks("####### text\n", Options::default()),
+        vec![(BlockKind::Paragraph, "####### text\n".to_string())]
+    );
+}
+
+#[test]
+fn hashes_without_space_are_not_a_heading() {
+    assert_eq!(
+        blocks("Intro\n#no-space\n##tag\n", Options::default()),
+        vec![(
+            BlockKind::Paragraph,
+            "Intro\n#no-space\n##tag\n".to_string()
+        )]
+    );
+}
+
+#[test]
+fn empty_heading_at_line_end() {
+    assert_eq!(
+        blocks("Intro\n##\nAfter\n", Options::default()),
+        vec![
+            (BlockKind::Paragraph, "Intro\n".to_string()),
+            (BlockKind::Heading, "##\n".to_string()),
+            (BlockKind::Paragraph, "After\n".to_string()),
+        ]
+    );
+}
+
+#[test]
+fn heading_max_level_is_configurable() {
+    let opts = Options {
+        heading_max_level: 3,
+        ..Options::default()
+    };
+    assert_eq!(
+        blocks("### Three\n#### Four\n", opts),
+        vec![
+            (BlockKind::Heading, "### Three\n".to_string()),
+            (BlockKind::Paragraph, "#### Four\n".to_string()),
+        ]
+    );
+}
@@ -0,0 +1,125 @@
+mod support;
+
+use mdstream::{BlockKind, MdStream, Options};
+
+fn owned(blocks: &[(BlockKind, &str)]) -> Vec<(BlockKind, String)> {
+    blocks.iter().map(|(k, r)| (*k, r.to_string())).collect()
+}
+
+fn chunkings(text: &str) -> Vec<Vec<String>> {
+    let mut all = vec![
+        support::chunk_whole(text),
+        support::chunk_lines(text),
+        support::chunk_chars(text),
+    ];
+    all.extend((0..16).map(|trial| support::chunk_pseudo_random(text, "blank_lines", trial, 5)));
+    all
+}
+
+fn assert_blocks(text: &str, opts: &Options, expected: &[(BlockKind, &str)]) {
+    for chunks in chunkings(text) {
+        let got = support::collect_final_blocks(chunks.clone(), opts.clone());
+        assert_eq!(got, owned(expected), "{text:?} chunks={chunks:?}");
+    }
+}
+
+fn blank_blocks() -> Options {
+    Options {
+        emit_blank_blocks: true,
+        ..Options::default()
+    }
+}
+
+#[test]
+fn blank_lines_stay_with_blocks_that_end_at_a_blank_line() {
+    use BlockKind::*;
+    let opts = Options::default();
+    assert_blocks("A\n\nB", &opts, &[(Paragraph, "A\n\n"), (Paragraph, "B")]);
+    assert_blocks(
+        "A\n\n\n\nB",
+        &opts,
+        &[(Paragraph, "A\n\n\n\n"), (Paragraph, "B")],
+    );
+    assert_blocks(
+        "- a\n\n\n\nB",
+        &opts,
+        &[(List, "- a\n\n\n\n"), (Paragraph, "B")],
+    );
+    assert_blocks("A\n\n\n\n", &opts, &[(Paragraph, "A\n\n\n\n")]);
+}
+
+#[test]
+fn blank_lines_after_self_closing_blocks_are_dropped_by_default() {
+    use BlockKind::*;
+    let opts = Options::default();
+    assert_blocks(
+        "# H\n\n\n\nB",
+        &opts,
+        &[(Heading, "# H\n"), (Paragraph, "B")],
+    );
+    assert_blocks(
+        "```\nx\n```\n\n\n\nB",
+        &opts,
+        &[(CodeFence, "```\nx\n```\n"), (Paragraph, "B")],
+    );
+    assert_blocks("\n\n\nA", &opts, &[(Paragraph, "A")]);
+    assert_blocks("# H\n\n\n", &opts, &[(Heading, "# H\n")]);
+}
+
+#[test]
+fn emit_blank_blocks_keeps_unowned_blank_lines() {
+    use BlockKind::*;
+    let opts = blank_blocks();
+    // Owned blank lines are unchanged.
+    assert_blocks(
+        "A\n\n\n\nB",
+        &opts,
+        &[(Paragraph, "A\n\n\n\n"), (Paragraph, "B")],
+    );
+    assert_blocks(
+        "# H\n\n\n\nB",
+        &opts,
+        &[(Heading, "# H\n"), (Blank, "\n\n\n"), (Paragraph, "B")],
+    );
+    assert_blocks(
+        "```\nx\n```\n\n\nB",
+        &opts,
+        &[
+            (CodeFence, "```\nx\n```\n"),
+            (Blank, "\n\n"),
+            (Paragraph, "B"),
+        ],
+    );
+    assert_blocks("\n \nA", &opts, &[(Blank, "\n \n"), (Paragraph, "A")]);
+    assert_blocks("# H\n\n\n", &opts, &[(Heading, "# H\n"), (Blank, "\n\n")]);
+}
+
+#[test]
+fn emit_blank_blocks_reassembles_the_input() {
+    let docs = [
+        "\n\n# Title\n\n\n\nIntro\n\n\n- a\n- b\n\n\n\n```rs\nfn main() {}\n```\n\n\n---\n\n\nTail\n\n",
+        "A\n\n\n\nB\n\n\n\n",
+        "$$\nx\n$$\n\n\n<div>\nhi\n</div>\n\n\nEnd",
+    ];
+    for doc in docs {
+        for chunks in chunkings(doc) {
+            let raws = support::collect_final_raw(chunks, blank_blocks());
+            assert_eq!(raws.concat(), doc);
+        }
+    }
+}
+
+#[test]
+fn pending_blank_run_is_reported_as_blank() {
+    let mut s = MdStream::new(blank_blocks());
+    let u = s.append("# H\n\n\n");
+    let pending = u.pending.expect("pending");
+    assert_eq!(pending.kind, BlockKind::Blank);
+    assert_eq!(pending.raw, "\n\n");
+
+    let u = s.append("B");
+    assert_eq!(u.committed.len(), 1);
+    assert_eq!(u.committed[0].kind, BlockKind::Blank);
+    assert_eq!(u.committed[0].id, pending.id);
+    assert_eq!(u.pending.unwrap().kind, BlockKind::Paragraph);
+}
@@ -0,0 +1,62 @@
+mod support;
+
+use mdstream::{Block, BlockFilter, BlockKind, MdStream, Options};
+use support::{chunk_chars, chunk_lines, chunk_whole};
+
+const DOC: &str = "# Title\n\nIntro text.\n\n```rust\nfn main() {}\n```\n\n- item\n- item\n\n~~~\nplain\n~~~\n\nOutro.\n";
+
+fn is_code(block: &Block) -> bool {
+    block.kind == BlockKind::CodeFence
+}
+
+fn run(chunks: Vec<String>) -> (Vec<Block>, Vec<Option<Block>>) {
+    let mut f = BlockFilter::new(Options::default(), is_code);
+    let mut committed = Vec::new();
+    let mut pendings = Vec::new();
+    for chunk in &chunks {
+        let u = f.append(chunk);
+        committed.extend(u.committed);
+        pendings.push(u.pending);
+    }
+    let u = f.finalize();
+    committed.extend(u.committed);
+    assert!(u.pending.is_none());
+    (committed, pendings)
+}
+
+#[test]
+fn extracts_only_code_fences_from_mixed_document() {
+    for chunks in [chunk_whole(DOC), chunk_lines(DOC), chunk_chars(DOC)] {
+        let (committed, pendings) = run(chunks);
+        let raws: Vec<&str> = committed.iter().map(|b| b.raw.as_str()).collect();
+        assert_eq!(
+            raws,
+            vec!["```rust\nfn main() {}\n```\n", "~~~\nplain\n~~~\n"]
+        );
+        assert!(pendings.iter().flatten().all(is_code));
+    }
+}
+
+#[test]
+fn parsing_matches_the_unfiltered_stream() {
+    let mut plain = MdStream::new(Options::default());
+    let mut all = plain.append(DOC).committed;
+    all.extend(plain.finalize().committed);
+    let expected: Vec<Block> = all.into_iter().filter(is_code).collect();
+
+    let (committed, _) = run(chunk_lines(DOC));
+    assert_eq!(committed, expected);
+}
+
+#[test]
+fn pending_is_forwarded_only_when_it_matches() {
+    let mut f = BlockFilter::new(Options::default(), is_code);
+    let u = f.append("Some paragraph");
+    assert!(u.pending.is_none());
+    assert_eq!(f.inner_mut().snapshot_blocks().len(), 1);
+
+    let u = f.append("\n\n```py\nprint(1)");
+    let pending = u.pending.expect("code fence pending");
+    assert_eq!(pending.kind, BlockKind::CodeFence);
+    assert!(u.committed.is_empty());
+}
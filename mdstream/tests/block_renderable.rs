@@ -0,0 +1,40 @@
+use mdstream::{BlockKind, BlockStatus, MdStream, Options};
+
+#[test]
+fn pending_unclosed_code_fence_is_not_renderable() {
+    let mut s = MdStream::new(Options::default());
+    let p = s.append("```rs\nfn main() {\n").pending.unwrap();
+    assert_eq!(p.kind, BlockKind::CodeFence);
+    assert!(!p.is_renderable());
+
+    // The closing fence typed without its newline yet: still pending, but complete.
+    let p = s.append("}\n```").pending.unwrap();
+    assert_eq!(p.status, BlockStatus::Pending);
+    assert!(p.is_renderable());
+
+    let u = s.append("\n");
+    assert!(u.committed[0].is_renderable());
+}
+
+#[test]
+fn pending_unbalanced_math_is_not_renderable() {
+    let mut s = MdStream::new(Options::default());
+    let p = s.append("$$\nx^2\n").pending.unwrap();
+    assert_eq!(p.kind, BlockKind::MathBlock);
+    assert!(!p.is_renderable());
+    assert!(s.append("$$").pending.unwrap().is_renderable());
+}
+
+#[test]
+fn other_blocks_are_renderable() {
+    let mut s = MdStream::new(Options::default());
+    let p = s.append("Some *partial").pending.unwrap();
+    assert!(p.is_renderable());
+
+    // A committed fence is final even without a closing fence (finalized mid-block).
+    let mut s = MdStream::new(Options::default());
+    s.append("```\ncode\n");
+    let u = s.finalize();
+    assert_eq!(u.committed[0].kind, BlockKind::CodeFence);
+    assert!(u.committed[0].is_renderable());
+}
@@ -0,0 +1,46 @@
+use mdstream::{MdStream, Options, trim_trailing_blank_lines};
+
+#[test]
+fn trims_trailing_blank_lines_only() {
+    assert_eq!(trim_trailing_blank_lines("A\n\n"), "A");
+    assert_eq!(trim_trailing_blank_lines("A\n \n\t\n"), "A");
+    assert_eq!(trim_trailing_blank_lines("A\n\nB\n"), "A\n\nB");
+    assert_eq!(
+        trim_trailing_blank_lines("line break  \n\n"),
+        "line break  "
+    );
+    assert_eq!(trim_trailing_blank_lines("A"), "A");
+    assert_eq!(trim_trailing_blank_lines("\n\n"), "");
+    assert_eq!(trim_trailing_blank_lines(""), "");
+}
+
+#[test]
+fn trimmed_views_borrow_from_the_block() {
+    let mut s = MdStream::new(Options::default());
+    let u = s.append("# Title\n\nSome **bold\n\n");
+    let heading = &u.committed[0];
+    assert_eq!(heading.raw_trimmed(), "# Title");
+    assert!(std::ptr::eq(
+        heading.raw_trimmed().as_ptr(),
+        heading.raw.as_ptr()
+    ));
+
+    let u = s.append("```rs\nfn main() {}\n```\n\n");
+    let para = &u.committed[0];
+    assert_eq!(para.raw_trimmed(), "Some **bold");
+    assert_eq!(u.committed[1].raw_trimmed(), "```rs\nfn main() {}\n```");
+}
+
+#[test]
+fn display_trimmed_uses_pending_display() {
+    let mut s = MdStream::new(Options::default());
+    let u = s.append("Some **bold");
+    let p = u.pending.expect("pending");
+    assert_eq!(p.raw_trimmed(), "Some **bold");
+    assert_eq!(p.display_trimmed(), "Some **bold**");
+
+    let mut s = MdStream::new(Options::default());
+    let u = s.append_ref("```rs\nfn main() {}\n");
+    let p = u.pending.expect("pending");
+    assert_eq!(p.display_trimmed(), "```rs\nfn main() {}\n```");
+}
@@ -1,6 +1,6 @@
 mod support;
 
-use mdstream::{MdStream, Options, TagBoundaryPlugin};
+use mdstream::{BoundaryUpdate, FnBoundaryPlugin, MdStream, Options, TagBoundaryPlugin};
 
 #[test]
 fn thinking_tag_container_is_single_block() {
@@ -60,3 +60,112 @@ fn tag_plugin_reset_clears_state() {
     assert_eq!(u.committed[0].raw, "A\n\n");
     assert_eq!(u.pending.as_ref().unwrap().raw, "B\n");
 }
+
+#[test]
+fn thinking_tag_opened_and_closed_on_one_line_is_single_block() {
+    let markdown = "<thinking>done</thinking>\nAfter\n";
+    for chunks in [
+        support::chunk_whole(markdown),
+        support::chunk_lines(markdown),
+        support::chunk_chars(markdown),
+    ] {
+        let blocks = support::collect_final_raw_with_stream(
+            chunks,
+            MdStream::new(Options::default()).with_boundary_plugin(TagBoundaryPlugin::thinking()),
+        );
+        assert_eq!(
+            blocks,
+            vec![
+                "<thinking>done</thinking>\n".to_string(),
+                "After\n".to_string(),
+            ]
+        );
+    }
+}
+
+#[test]
+fn self_closing_thinking_tag_is_zero_content_block() {
+    let markdown = "<thinking/>\nAfter\n";
+    for chunks in [
+        support::chunk_whole(markdown),
+        support::chunk_chars(markdown),
+    ] {
+        let blocks = support::collect_final_raw_with_stream(
+            chunks,
+            MdStream::new(Options::default()).with_boundary_plugin(TagBoundaryPlugin::thinking()),
+        );
+        assert_eq!(
+            blocks,
+            vec!["<thinking/>\n".to_string(), "After\n".to_string()]
+        );
+    }
+}
+
+#[test]
+fn inline_close_requires_standalone_end_by_default() {
+    // The closing tag is followed by more text, so the block keeps going until a standalone end.
+    let mut s =
+        MdStream::new(Options::default()).with_boundary_plugin(TagBoundaryPlugin::thinking());
+    let u = s.append("<thinking>a</thinking> more\nB\n</thinking>\nAfter\n");
+    assert_eq!(u.committed.len(), 1);
+    assert_eq!(
+        u.committed[0].raw,
+        "<thinking>a</thinking> more\nB\n</thinking>\n"
+    );
+}
+
+#[test]
+fn active_boundary_label_is_reported_inside_the_block() {
+    let mut s = MdStream::new(Options::default())
+        .with_boundary_plugin(TagBoundaryPlugin::new("tool_call"))
+        .with_boundary_plugin(TagBoundaryPlugin::thinking());
+
+    s.append("Intro\n\n");
+    assert_eq!(s.active_boundary_plugin(), None);
+    assert_eq!(s.active_boundary_label(), None);
+
+    // Known as soon as the opening tag is the pending block, before its line completes.
+    s.append("<thinking>");
+    assert_eq!(s.active_boundary_plugin(), Some(1));
+    assert_eq!(s.active_boundary_label(), Some("thinking"));
+
+    s.append("\nstep 1\n\nstep 2\n");
+    assert_eq!(s.active_boundary_label(), Some("thinking"));
+
+    s.append("</thinking>\nAfter");
+    assert_eq!(s.active_boundary_plugin(), None);
+    assert_eq!(s.active_boundary_label(), None);
+
+    s.append("\n\n<tool_call>\n{}\n");
+    assert_eq!(s.active_boundary_plugin(), Some(0));
+    assert_eq!(s.active_boundary_label(), Some("tool_call"));
+
+    s.finalize();
+    assert_eq!(s.active_boundary_plugin(), None);
+}
+
+#[test]
+fn unlabeled_plugin_reports_index_but_no_label() {
+    let plugin = || {
+        FnBoundaryPlugin::new(
+            |line| line.starts_with("@@@"),
+            |line| {
+                if line.trim_end() == "@@@end" {
+                    BoundaryUpdate::Close
+                } else {
+                    BoundaryUpdate::Continue
+                }
+            },
+        )
+    };
+
+    let mut s = MdStream::new(Options::default()).with_boundary_plugin(plugin());
+    s.append("@@@\nbody\n");
+    assert_eq!(s.active_boundary_plugin(), Some(0));
+    assert_eq!(s.active_boundary_label(), None);
+
+    let mut s =
+        MdStream::new(Options::default()).with_boundary_plugin(plugin().with_label("directive"));
+    s.append("@@@\nbody\n");
+    assert_eq!(s.active_boundary_label(), Some("directive"));
+}
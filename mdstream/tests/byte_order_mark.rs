@@ -0,0 +1,62 @@
+mod support;
+
+use mdstream::{BlockKind, MdStream, Options};
+
+#[test]
+fn leading_bom_does_not_hide_a_heading() {
+    let mut s = MdStream::new(Options::default());
+    let u = s.append("\u{FEFF}# Title\n");
+    assert_eq!(u.committed.len(), 1);
+    assert_eq!(u.committed[0].kind, BlockKind::Heading);
+    assert_eq!(u.committed[0].raw, "# Title\n");
+}
+
+#[test]
+fn bom_is_stripped_regardless_of_chunking() {
+    let text = "\u{FEFF}```rs\r\nfn main() {}\r\n```\r\n\r\nafter\r\n";
+    let expected = vec![
+        (
+            BlockKind::CodeFence,
+            "```rs\nfn main() {}\n```\n".to_string(),
+        ),
+        (BlockKind::Paragraph, "after\n".to_string()),
+    ];
+    for chunks in [
+        support::chunk_whole(text),
+        support::chunk_chars(text),
+        support::chunk_lines(text),
+        vec![
+            "\u{FEFF}".to_string(),
+            "```rs\r".to_string(),
+            "\nfn main() {}\r\n```\r\n\r\nafter\r\n".to_string(),
+        ],
+    ] {
+        let blocks = support::collect_final_blocks(chunks, Options::default());
+        assert_eq!(blocks, expected);
+    }
+}
+
+#[test]
+fn bom_later_in_the_stream_and_zero_width_spaces_are_kept() {
+    let mut s = MdStream::new(Options::default());
+    s.append("intro\n\n");
+    let u = s.append("\u{FEFF}# not stripped\n");
+    assert_eq!(u.pending.expect("pending").raw, "\u{FEFF}# not stripped\n");
+
+    let mut s = MdStream::new(Options::default());
+    let u = s.append("\u{200B}# Title\n");
+    assert!(u.committed.is_empty());
+    let pending = u.pending.expect("pending");
+    assert_eq!(pending.kind, BlockKind::Paragraph);
+    assert_eq!(pending.raw, "\u{200B}# Title\n");
+}
+
+#[test]
+fn bom_is_stripped_again_after_reset() {
+    let mut s = MdStream::new(Options::default());
+    s.append("old\n");
+    s.reset();
+    let u = s.append("\u{FEFF}# Title\n");
+    assert_eq!(u.committed[0].kind, BlockKind::Heading);
+    assert_eq!(u.committed[0].raw, "# Title\n");
+}
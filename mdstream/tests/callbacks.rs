@@ -0,0 +1,102 @@
+use std::sync::{Arc, Mutex};
+
+use mdstream::{BlockKind, MdStream, Options};
+
+type Log = Arc<Mutex<Vec<String>>>;
+
+fn stream_with_log() -> (MdStream, Log) {
+    let log: Log = Arc::default();
+    let mut s = MdStream::new(Options::default());
+    let commits = Arc::clone(&log);
+    s.on_commit(move |b| {
+        commits
+            .lock()
+            .unwrap()
+            .push(format!("commit {} {:?} {:?}", b.id.0, b.kind, b.raw));
+    });
+    let pending = Arc::clone(&log);
+    s.on_pending(move |b| {
+        pending
+            .lock()
+            .unwrap()
+            .push(b.map_or("pending -".to_string(), |b| format!("pending {:?}", b.raw)));
+    });
+    (s, log)
+}
+
+#[test]
+fn callbacks_fire_in_order_during_append_and_finalize() {
+    let (mut s, log) = stream_with_log();
+
+    let u = s.append("# Title\n\nPara");
+    assert_eq!(
+        *log.lock().unwrap(),
+        vec![
+            "commit 1 Heading \"# Title\\n\"".to_string(),
+            "pending \"Para\"".to_string(),
+        ]
+    );
+    // The update still carries the same blocks.
+    assert_eq!(u.committed.len(), 1);
+    assert_eq!(u.committed[0].kind, BlockKind::Heading);
+
+    log.lock().unwrap().clear();
+    s.append("graph\n\n- a");
+    s.finalize();
+    assert_eq!(
+        *log.lock().unwrap(),
+        vec![
+            "commit 3 Paragraph \"Paragraph\\n\\n\"".to_string(),
+            "pending \"- a\"".to_string(),
+            "commit 4 List \"- a\"".to_string(),
+            "pending -".to_string(),
+        ]
+    );
+}
+
+#[test]
+fn callbacks_fire_for_ref_variants() {
+    let (mut s, log) = stream_with_log();
+    let u = s.append_ref("A\n\nB");
+    assert_eq!(u.committed.len(), 1);
+    s.finalize_ref();
+    assert_eq!(
+        *log.lock().unwrap(),
+        vec![
+            "commit 1 Paragraph \"A\\n\\n\"".to_string(),
+            "pending \"B\"".to_string(),
+            "commit 2 Paragraph \"B\"".to_string(),
+            "pending -".to_string(),
+        ]
+    );
+}
+
+#[test]
+fn registering_again_replaces_the_callback() {
+    let first: Log = Arc::default();
+    let mut s = MdStream::new(Options::default());
+    let sink = Arc::clone(&first);
+    s.on_commit(move |b| sink.lock().unwrap().push(b.raw.clone()));
+    s.on_commit(|_| {});
+    s.append("A\n\nB\n\n");
+    assert!(first.lock().unwrap().is_empty());
+}
+
+#[test]
+fn diff_replay_reports_only_the_changed_blocks() {
+    let prev: Vec<_> = MdStream::new(Options::default())
+        .parse_complete("A\n\nB\n\nC\n")
+        .collect();
+    let (mut s, log) = stream_with_log();
+    let u = s.diff_replay(&prev, "A\n\nB\n\nX\n");
+
+    assert_eq!(u.committed.len(), 1);
+    let x = &u.committed[0];
+    assert_eq!(
+        *log.lock().unwrap(),
+        vec![
+            format!("commit {} Paragraph \"X\\n\"", x.id.0),
+            "pending -".to_string(),
+        ]
+    );
+}
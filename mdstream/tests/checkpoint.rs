@@ -0,0 +1,51 @@
+use mdstream::{MdStream, Options, TagBoundaryPlugin, Update};
+
+fn run(s: &mut MdStream, chunks: &[&str]) -> Vec<Update> {
+    let mut out: Vec<Update> = chunks.iter().map(|c| s.append(c)).collect();
+    out.push(s.finalize());
+    out
+}
+
+#[test]
+fn restore_discards_speculative_appends() {
+    let mut s = MdStream::new(Options::default());
+    s.append("# Title\n\nPara");
+    let cp = s.checkpoint();
+
+    let spec = s.append("graph\n\n```rs\nfn speculative() {}\n");
+    assert_eq!(spec.committed.len(), 1);
+
+    s.restore(cp);
+    assert_eq!(s.buffer(), "# Title\n\nPara");
+    let got = run(&mut s, &["graph two\n\nTail"]);
+
+    let mut reference = MdStream::new(Options::default());
+    reference.append("# Title\n\nPara");
+    let expected = run(&mut reference, &["graph two\n\nTail"]);
+    assert_eq!(got, expected);
+}
+
+#[test]
+fn restore_rebuilds_active_boundary_plugin_state() {
+    let new_stream =
+        || MdStream::new(Options::default()).with_boundary_plugin(TagBoundaryPlugin::thinking());
+
+    let mut s = new_stream();
+    s.append("<thinking>\nstep one\n");
+    let cp = s.checkpoint();
+    // Speculatively close the tag block, then roll back.
+    let spec = s.append("</thinking>\n");
+    assert_eq!(spec.committed.len(), 1);
+    s.restore(cp);
+
+    let got = run(&mut s, &["step two\n</thinking>\nAfter"]);
+
+    let mut reference = new_stream();
+    reference.append("<thinking>\nstep one\n");
+    let expected = run(&mut reference, &["step two\n</thinking>\nAfter"]);
+    assert_eq!(got, expected);
+    assert_eq!(
+        got[0].committed[0].raw,
+        "<thinking>\nstep one\nstep two\n</thinking>\n"
+    );
+}
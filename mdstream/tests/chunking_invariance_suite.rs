@@ -1,6 +1,6 @@
 mod support;
 
-use mdstream::Options;
+use mdstream::{BlockKind, MdStream, Options};
 
 fn assert_invariant(case_name: &str, markdown: &str, opts: Options, trials: u64, max_bytes: usize) {
     let expected = support::collect_final_blocks(support::chunk_whole(markdown), opts.clone());
@@ -101,3 +101,105 @@ fn chunking_invariance_handles_crlf_split_across_chunks() {
     );
     assert_eq!(blocks_split_crlf, expected);
 }
+
+#[test]
+fn consecutive_headings_chunking_invariance() {
+    let opts = Options::default();
+    for (name, markdown) in [
+        ("headings_no_blank", "# A\n## B\n### C\n"),
+        ("headings_unterminated", "# A\n## B\n### C"),
+        ("headings_between_text", "Para\n# A\n## B\ntext\n"),
+    ] {
+        assert_invariant(name, markdown, opts.clone(), 32, 6);
+    }
+
+    let expected = vec![
+        (BlockKind::Heading, "# A\n".to_string()),
+        (BlockKind::Heading, "## B\n".to_string()),
+        (BlockKind::Heading, "### C\n".to_string()),
+    ];
+    let blocks = support::collect_final_blocks(support::chunk_chars("# A\n## B\n### C\n"), opts);
+    assert_eq!(blocks, expected);
+}
+
+#[test]
+fn consecutive_headings_commit_in_order_with_sequential_ids() {
+    let markdown = "# A\n## B\n### C\n";
+    for chunks in [
+        support::chunk_whole(markdown),
+        support::chunk_lines(markdown),
+        support::chunk_chars(markdown),
+    ] {
+        let mut s = MdStream::new(Options::default());
+        let mut ids = Vec::new();
+        for chunk in &chunks {
+            ids.extend(s.append(chunk).committed.iter().map(|b| b.id.0));
+        }
+        ids.extend(s.finalize().committed.iter().map(|b| b.id.0));
+        assert_eq!(ids, vec![1, 2, 3], "chunks={chunks:?}");
+    }
+}
+
+#[test]
+fn consecutive_code_fences_chunking_invariance() {
+    let opts = Options::default();
+    for (name, markdown) in [
+        ("fences_no_blank", "```a\nx\n```\n```b\ny\n```\n"),
+        ("fences_unterminated", "```a\nx\n```\n```b\ny\n```"),
+        (
+            "fences_mixed_markers",
+            "~~~a\nx\n~~~\n```b\ny\n```\nAfter\n",
+        ),
+    ] {
+        assert_invariant(name, markdown, opts.clone(), 32, 6);
+    }
+
+    let expected = vec![
+        (BlockKind::CodeFence, "```a\nx\n```\n".to_string()),
+        (BlockKind::CodeFence, "```b\ny\n```\n".to_string()),
+    ];
+    let markdown = "```a\nx\n```\n```b\ny\n```\n";
+    for chunks in [
+        support::chunk_whole(markdown),
+        support::chunk_lines(markdown),
+        support::chunk_chars(markdown),
+    ] {
+        let blocks = support::collect_final_blocks(chunks, opts.clone());
+        assert_eq!(blocks, expected);
+    }
+}
+
+#[test]
+fn pending_kind_after_single_line_block_is_chunking_invariant() {
+    // The line after a committed heading/thematic break must not inherit its mode.
+    for markdown in [
+        "# T\n    code",
+        "***\n    code",
+        "# T\n<div>",
+        "# T\n$$",
+        "# T\n- a",
+    ] {
+        let pending_kind = |chunks: Vec<String>| {
+            let mut s = MdStream::new(Options::default());
+            let mut kind = None;
+            for chunk in &chunks {
+                kind = s.append(chunk).pending.map(|p| p.kind);
+            }
+            kind
+        };
+        let expected = pending_kind(support::chunk_whole(markdown));
+        assert_ne!(expected, Some(BlockKind::Heading), "{markdown:?}");
+        assert_eq!(
+            pending_kind(support::chunk_chars(markdown)),
+            expected,
+            "{markdown:?} chunker=chars"
+        );
+        for t in 0..16 {
+            assert_eq!(
+                pending_kind(support::chunk_pseudo_random(markdown, "pending_kind", t, 3)),
+                expected,
+                "{markdown:?} chunker=rand t={t}"
+            );
+        }
+    }
+}
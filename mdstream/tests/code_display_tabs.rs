@@ -0,0 +1,65 @@
+use mdstream::{BlockKind, MdStream, Options};
+
+fn opts() -> Options {
+    Options {
+        expand_tabs_in_code_display: true,
+        ..Default::default()
+    }
+}
+
+#[test]
+fn pending_code_fence_display_expands_tabs_but_raw_keeps_them() {
+    let mut s = MdStream::new(opts());
+    s.append("```go\nfunc main() {\n");
+    let u = s.append("\tx := 1\n\tif x {\n\t\tab\tc\n");
+    let pending = u.pending.expect("pending");
+    assert_eq!(pending.kind, BlockKind::CodeFence);
+    assert_eq!(
+        pending.raw,
+        "```go\nfunc main() {\n\tx := 1\n\tif x {\n\t\tab\tc\n"
+    );
+    assert_eq!(
+        pending.display.as_deref(),
+        Some("```go\nfunc main() {\n    x := 1\n    if x {\n        ab  c\n")
+    );
+
+    // Incremental updates continue from the current column.
+    let u = s.append("a\tb");
+    let display = u.pending.expect("pending").display.expect("display");
+    assert!(display.ends_with("\na   b"), "{display:?}");
+}
+
+#[test]
+fn append_ref_display_expands_tabs_incrementally() {
+    let mut s = MdStream::new(opts());
+    for chunk in ["```\n", "\ta", "\tb\n", "c\td\n"] {
+        let u = s.append_ref(chunk);
+        let pending = u.pending.expect("pending");
+        assert!(!pending.display.expect("display").contains('\t'));
+    }
+    let u = s.append_ref("");
+    let pending = u.pending.expect("pending");
+    assert_eq!(pending.raw, "```\n\ta\tb\nc\td\n");
+    assert_eq!(pending.display, Some("```\n    a   b\nc   d\n```\n"));
+}
+
+#[test]
+fn committed_display_and_other_blocks() {
+    let mut s = MdStream::new(Options {
+        commit_with_display: true,
+        tab_width: 2,
+        ..opts()
+    });
+    let mut u = s.append("```\n\tx\n```\n\nPara\twith tab\n");
+    u.merge(s.finalize());
+    assert_eq!(u.committed.len(), 2);
+    assert_eq!(u.committed[0].raw, "```\n\tx\n```\n");
+    assert_eq!(u.committed[0].display.as_deref(), Some("```\n  x\n```\n"));
+    // Only code fences are expanded.
+    assert_eq!(u.committed[1].display, None);
+
+    let mut s = MdStream::new(Options::default());
+    let u = s.append("```\n\tx\n");
+    let pending = u.pending.expect("pending");
+    assert!(pending.display.expect("display").contains("\tx"));
+}
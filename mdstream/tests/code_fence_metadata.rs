@@ -40,3 +40,34 @@ fn returns_none_for_non_code_fence_blocks() {
     assert_eq!(b.code_fence_language(), None);
     assert!(b.code_fence_header().is_none());
 }
+
+#[test]
+fn raw_info_round_trips_untrimmed() {
+    let b = Block {
+        id: BlockId(1),
+        status: BlockStatus::Committed,
+        kind: BlockKind::CodeFence,
+        raw: "```  rust,ignore {.numberLines}  \nfn main() {}\n```\n".to_string(),
+        display: None,
+    };
+    assert_eq!(
+        b.code_fence_info_raw(),
+        Some("  rust,ignore {.numberLines}  ")
+    );
+    assert_eq!(b.code_fence_info(), Some("rust,ignore {.numberLines}"));
+    assert_eq!(b.code_fence_language(), Some("rust,ignore"));
+}
+
+#[test]
+fn raw_info_is_empty_without_info_string() {
+    let b = Block {
+        id: BlockId(1),
+        status: BlockStatus::Committed,
+        kind: BlockKind::CodeFence,
+        raw: "~~~~\ncode\n~~~~\n".to_string(),
+        display: None,
+    };
+    assert_eq!(b.code_fence_info_raw(), Some(""));
+    assert_eq!(b.code_fence_info(), Some(""));
+    assert_eq!(b.code_fence_language(), None);
+}
@@ -0,0 +1,69 @@
+mod support;
+
+use mdstream::{BlockKind, MdStream, Options};
+use support::{chunk_chars, chunk_whole, collect_final_blocks};
+
+fn blocks(text: &str, opts: Options) -> Vec<(BlockKind, String)> {
+    let whole = collect_final_blocks(chunk_whole(text), opts.clone());
+    assert_eq!(collect_final_blocks(chunk_chars(text), opts), whole);
+    whole
+}
+
+fn min4() -> Options {
+    Options {
+        code_fence_min_len: 4,
+        ..Options::default()
+    }
+}
+
+const NESTED: &str = "````md\n```rust\nfn main() {}\n```\n````\nAfter\n";
+
+#[test]
+fn longer_fence_is_not_closed_by_inner_fence() {
+    for opts in [Options::default(), min4()] {
+        assert_eq!(
+            blocks(NESTED, opts),
+            vec![
+                (
+                    BlockKind::CodeFence,
+                    "````md\n```rust\nfn main() {}\n```\n````\n".to_string()
+                ),
+                (BlockKind::Paragraph, "After\n".to_string()),
+            ]
+        );
+    }
+}
+
+#[test]
+fn pending_inner_fence_stays_in_the_outer_block() {
+    let mut s = MdStream::new(min4());
+    let u = s.append("````md\n```rust\nfn main() {}\n```\n");
+    assert!(u.committed.is_empty());
+    let pending = u.pending.expect("pending fence");
+    assert_eq!(pending.kind, BlockKind::CodeFence);
+    assert_eq!(pending.raw, "````md\n```rust\nfn main() {}\n```\n");
+}
+
+#[test]
+fn shorter_fences_do_not_open_a_code_block() {
+    assert_eq!(
+        blocks("```\ncode\n```\n", min4()),
+        vec![(BlockKind::Paragraph, "```\ncode\n```\n".to_string())]
+    );
+    assert_eq!(
+        blocks("~~~~\ncode\n~~~~\n", min4()),
+        vec![(BlockKind::CodeFence, "~~~~\ncode\n~~~~\n".to_string())]
+    );
+}
+
+#[test]
+fn values_below_three_act_as_three() {
+    let opts = Options {
+        code_fence_min_len: 1,
+        ..Options::default()
+    };
+    assert_eq!(
+        blocks("``\ncode\n``\n", opts),
+        vec![(BlockKind::Paragraph, "``\ncode\n``\n".to_string())]
+    );
+}
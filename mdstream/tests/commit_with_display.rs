@@ -0,0 +1,49 @@
+use mdstream::{BlockKind, MdStream, Options};
+
+fn display_opts() -> Options {
+    Options {
+        commit_with_display: true,
+        ..Default::default()
+    }
+}
+
+#[test]
+fn committed_blocks_have_no_display_by_default() {
+    let mut s = MdStream::new(Options::default());
+    s.append("Some **bold");
+    let u = s.finalize();
+    assert_eq!(u.committed.len(), 1);
+    assert!(u.committed[0].display.is_none());
+}
+
+#[test]
+fn finalize_keeps_terminated_display() {
+    let mut s = MdStream::new(display_opts());
+    s.append("Some **bold");
+    let u = s.finalize();
+    assert_eq!(u.committed.len(), 1);
+    assert_eq!(u.committed[0].raw, "Some **bold");
+    assert_eq!(u.committed[0].display.as_deref(), Some("Some **bold**"));
+}
+
+#[test]
+fn finalize_closes_unterminated_code_fence_display() {
+    let mut s = MdStream::new(display_opts());
+    s.append("```rs\nfn main() {}\n");
+    let u = s.finalize();
+    assert_eq!(u.committed.len(), 1);
+    let block = &u.committed[0];
+    assert_eq!(block.kind, BlockKind::CodeFence);
+    assert_eq!(block.display.as_deref(), Some("```rs\nfn main() {}\n```\n"));
+}
+
+#[test]
+fn display_is_omitted_when_equal_to_raw() {
+    let mut s = MdStream::new(display_opts());
+    let u = s.append("Plain paragraph.\n\n```rs\nfn main() {}\n```\n\nNext");
+    assert_eq!(u.committed.len(), 2);
+    assert!(u.committed.iter().all(|b| b.display.is_none()));
+    let u = s.finalize();
+    assert_eq!(u.committed.len(), 1);
+    assert!(u.committed[0].display.is_none());
+}
@@ -0,0 +1,37 @@
+use mdstream::{BlockId, BlockKind, MdStream, Options};
+
+#[test]
+fn first_and_last_committed_block() {
+    let mut s = MdStream::new(Options::default());
+    assert!(s.first_committed_block().is_none());
+    assert!(s.last_committed_block().is_none());
+
+    s.append("# Title\n\nPara\n\n```rust\nfn main() {}\n```\n\nTail");
+    assert_eq!(s.first_committed_block().unwrap().kind, BlockKind::Heading);
+    let last = s.last_committed_block().unwrap();
+    assert_eq!(last.kind, BlockKind::CodeFence);
+    assert!(last.raw.starts_with("```rust"));
+}
+
+#[test]
+fn committed_block_by_id_after_several_commits() {
+    let mut s = MdStream::new(Options::default());
+    let mut ids = Vec::new();
+    for chunk in ["A\n\n", "B\n\n", "- c\n\n", "D\n\n", "E"] {
+        ids.extend(s.append(chunk).committed.iter().map(|b| b.id));
+    }
+    assert_eq!(ids.len(), 4);
+
+    for id in &ids {
+        assert_eq!(s.committed_block_by_id(*id).unwrap().id, *id);
+    }
+    assert_eq!(s.committed_block_by_id(ids[2]).unwrap().raw, "- c\n\n");
+
+    // The pending block and unknown ids are not committed.
+    let pending = s.snapshot_blocks().pop().unwrap();
+    assert!(s.committed_block_by_id(pending.id).is_none());
+    assert!(s.committed_block_by_id(BlockId(999)).is_none());
+
+    s.reset();
+    assert!(s.committed_block_by_id(ids[0]).is_none());
+}
@@ -0,0 +1,60 @@
+mod support;
+
+use mdstream::{MdStream, Options};
+use support::{chunk_chars, chunk_lines, chunk_pseudo_random};
+
+const DOC: &str = "# Title\n\nFirst paragraph\nstill first.\n\n- a\n- b\n\n```rust\nlet x = 1;\n```\n\n| a | b |\n|---|---|\n| 1 | 2 |\n\nLast line without newline";
+
+fn assert_progress(opts: Options, chunks: Vec<String>) {
+    let mut s = MdStream::new(opts);
+    let mut last = 0usize;
+    let mut fed = 0usize;
+    for chunk in &chunks {
+        s.append(chunk);
+        fed += chunk.len();
+        let offset = s.committed_byte_offset();
+        assert!(offset >= last, "offset went back from {last} to {offset}");
+        assert!(offset <= fed);
+        last = offset;
+    }
+    s.finalize();
+    assert_eq!(s.committed_byte_offset(), DOC.len());
+}
+
+#[test]
+fn committed_offset_is_monotonic_and_reaches_total() {
+    assert_progress(Options::default(), chunk_lines(DOC));
+    assert_progress(Options::default(), chunk_chars(DOC));
+    assert_progress(
+        Options::default(),
+        chunk_pseudo_random(DOC, "progress", 1, 9),
+    );
+}
+
+#[test]
+fn committed_offset_survives_buffer_compaction() {
+    let opts = Options {
+        max_buffer_bytes: Some(16),
+        ..Default::default()
+    };
+    assert_progress(opts, chunk_lines(DOC));
+}
+
+#[test]
+fn offset_tracks_commits_and_buffered_len_tracks_input() {
+    let mut s = MdStream::new(Options::default());
+    s.append("Para one\n\nPara two");
+    assert_eq!(s.committed_byte_offset(), "Para one\n\n".len());
+    assert_eq!(s.buffered_byte_len(), "Para one\n\nPara two".len());
+
+    s.append("\n\n   ");
+    s.finalize();
+    assert_eq!(
+        s.committed_byte_offset(),
+        "Para one\n\nPara two\n\n   ".len()
+    );
+
+    s.reset();
+    assert_eq!(s.committed_byte_offset(), 0);
+    assert_eq!(s.buffered_byte_len(), 0);
+}
@@ -0,0 +1,85 @@
+#![cfg(feature = "std")]
+
+mod support;
+
+use mdstream::{CommittedWriter, FootnotesMode, MdStream, Options, Update};
+use support::{chunk_chars, chunk_lines, chunk_pseudo_random};
+
+const TEXT: &str = "# Title\n\nSome *text*\nmore\n\n- a\n- b\n\n```rust\nfn main() {}\n```\n\n| a | b |\n|---|---|\n| 1 | 2 |\n\ntail";
+
+fn push_raws(raws: &mut String, update: &Update) {
+    let blocks = update.finished_messages.iter().flatten();
+    raws.extend(blocks.chain(&update.committed).map(|b| b.raw.as_str()));
+}
+
+fn write_all(chunks: Vec<String>, opts: Options) -> (Vec<u8>, String) {
+    let mut stream = MdStream::new(opts);
+    let mut writer = CommittedWriter::new(Vec::new());
+    let mut raws = String::new();
+    for chunk in &chunks {
+        let update = stream.append(chunk);
+        writer.write_update(&update).unwrap();
+        push_raws(&mut raws, &update);
+    }
+    let update = stream.finalize();
+    writer.write_update(&update).unwrap();
+    push_raws(&mut raws, &update);
+    assert_eq!(writer.bytes_written(), raws.len() as u64);
+    (writer.into_inner(), raws)
+}
+
+#[test]
+fn writes_the_concatenated_committed_raws() {
+    for chunks in [
+        chunk_lines(TEXT),
+        chunk_chars(TEXT),
+        chunk_pseudo_random(TEXT, "writer", 0, 7),
+    ] {
+        let (bytes, raws) = write_all(chunks, Options::default());
+        assert_eq!(String::from_utf8(bytes).unwrap(), raws);
+        assert!(raws.starts_with("# Title\n"));
+        assert!(raws.ends_with("tail"));
+    }
+}
+
+#[test]
+fn borrowed_updates_write_the_same_bytes() {
+    let mut stream = MdStream::new(Options::default());
+    let mut writer = CommittedWriter::new(Vec::new());
+    for chunk in chunk_chars(TEXT) {
+        let update = stream.append_ref(&chunk);
+        writer.write_update_ref(&update).unwrap();
+    }
+    writer.write_update(&stream.finalize()).unwrap();
+    let (expected, _) = write_all(chunk_chars(TEXT), Options::default());
+    assert_eq!(writer.into_inner(), expected);
+}
+
+#[test]
+fn finished_messages_are_written_before_the_next_message() {
+    let opts = Options {
+        message_terminator: Some("<|eot|>".to_string()),
+        ..Options::default()
+    };
+    let (bytes, raws) = write_all(chunk_chars("First\n\nmessage<|eot|>Second\n"), opts);
+    assert_eq!(String::from_utf8(bytes).unwrap(), raws);
+    assert_eq!(raws, "First\n\nmessageSecond\n");
+}
+
+#[test]
+fn reset_after_written_text_is_an_error() {
+    let opts = Options {
+        footnotes: FootnotesMode::SingleBlock,
+        ..Options::default()
+    };
+    let mut stream = MdStream::new(opts);
+    let mut writer = CommittedWriter::new(Vec::new());
+    writer
+        .write_update(&stream.append("Intro\n\nMore\n\n"))
+        .unwrap();
+    assert!(writer.bytes_written() > 0);
+    let update = stream.append("See [^1].\n");
+    assert!(update.reset);
+    let err = writer.write_update(&update).unwrap_err();
+    assert_eq!(err.kind(), std::io::ErrorKind::InvalidData);
+}
@@ -0,0 +1,61 @@
+use std::sync::Arc;
+use std::sync::atomic::{AtomicUsize, Ordering};
+
+use mdstream::{FnPendingTransformer, MdStream, Options, PendingTransformInput};
+
+fn counting_stream(compute_pending_display: bool) -> (MdStream, Arc<AtomicUsize>) {
+    let calls = Arc::new(AtomicUsize::new(0));
+    let counter = calls.clone();
+    let s = MdStream::new(Options {
+        compute_pending_display,
+        ..Default::default()
+    })
+    .with_pending_transformer(FnPendingTransformer(move |_: PendingTransformInput<'_>| {
+        counter.fetch_add(1, Ordering::Relaxed);
+        None
+    }));
+    (s, calls)
+}
+
+#[test]
+fn disabled_pending_display_is_none_and_skips_transformers() {
+    let (mut s, calls) = counting_stream(false);
+    for chunk in [
+        "Some **bo",
+        "ld\n\n",
+        "```rs\nfn",
+        " main() {\n",
+        "[link](http://x",
+    ] {
+        if let Some(pending) = s.append(chunk).pending {
+            assert_eq!(pending.display, None);
+        }
+        if let Some(pending) = s.append_ref("").pending {
+            assert_eq!(pending.display, None);
+        }
+    }
+    let pending = s.append(" tail").pending.expect("pending");
+    assert!(pending.raw.ends_with("[link](http://x tail"));
+    assert_eq!(calls.load(Ordering::Relaxed), 0);
+
+    // Committed output is unaffected.
+    let mut a = MdStream::new(Options::default());
+    let mut b = MdStream::new(Options {
+        compute_pending_display: false,
+        ..Default::default()
+    });
+    let text = "# T\n\nSome **bold\n\n```\ncode\n```\n\nEnd";
+    let mut ca = a.append(text).committed;
+    ca.extend(a.finalize().committed);
+    let mut cb = b.append(text).committed;
+    cb.extend(b.finalize().committed);
+    assert_eq!(ca, cb);
+}
+
+#[test]
+fn enabled_pending_display_runs_transformers() {
+    let (mut s, calls) = counting_stream(true);
+    let pending = s.append("Some **bo").pending.expect("pending");
+    assert_eq!(pending.display.as_deref(), Some("Some **bo**"));
+    assert!(calls.load(Ordering::Relaxed) > 0);
+}
@@ -0,0 +1,94 @@
+mod support;
+
+use mdstream::{BlockKind, Options};
+
+fn dl_opts() -> Options {
+    Options {
+        definition_lists: true,
+        ..Default::default()
+    }
+}
+
+fn assert_chunking_invariant(case_name: &str, markdown: &str, opts: Options) {
+    let expected = support::collect_final_blocks(support::chunk_whole(markdown), opts.clone());
+    let lines = support::collect_final_blocks(support::chunk_lines(markdown), opts.clone());
+    assert_eq!(lines, expected, "case={case_name} chunker=lines");
+    let chars = support::collect_final_blocks(support::chunk_chars(markdown), opts.clone());
+    assert_eq!(chars, expected, "case={case_name} chunker=chars");
+    for t in 0..16 {
+        let rand = support::collect_final_blocks(
+            support::chunk_pseudo_random(markdown, case_name, t, 16),
+            opts.clone(),
+        );
+        assert_eq!(rand, expected, "case={case_name} chunker=rand t={t}");
+    }
+}
+
+#[test]
+fn definition_lists_are_paragraphs_by_default() {
+    let text = "Term\n: Definition\n\nAfter\n";
+    let blocks = support::collect_final_blocks(support::chunk_whole(text), Options::default());
+    assert_eq!(blocks[0].0, BlockKind::Paragraph);
+}
+
+#[test]
+fn detects_term_with_multiple_definitions() {
+    let text = "Intro\n\nApple\n: A fruit.\n: A company.\n\nAfter\n";
+    let blocks = support::collect_final_blocks(support::chunk_whole(text), dl_opts());
+    assert_eq!(
+        blocks,
+        vec![
+            (BlockKind::Paragraph, "Intro\n\n".to_string()),
+            (
+                BlockKind::DefinitionList,
+                "Apple\n: A fruit.\n: A company.\n\n".to_string()
+            ),
+            (BlockKind::Paragraph, "After\n".to_string()),
+        ]
+    );
+    assert_chunking_invariant("multiple_definitions", text, dl_opts());
+}
+
+#[test]
+fn keeps_compact_terms_and_multi_line_definitions_together() {
+    let text = "Term 1\n: First line\n  continued here.\n\n  Second paragraph.\nTerm 2\n:   Other\n\n# Next\n";
+    let blocks = support::collect_final_blocks(support::chunk_whole(text), dl_opts());
+    assert_eq!(blocks.len(), 2);
+    assert_eq!(blocks[0].0, BlockKind::DefinitionList);
+    assert_eq!(
+        blocks[0].1,
+        "Term 1\n: First line\n  continued here.\n\n  Second paragraph.\nTerm 2\n:   Other\n\n"
+    );
+    assert_eq!(blocks[1].0, BlockKind::Heading);
+    assert_chunking_invariant("compact_multi_line", text, dl_opts());
+}
+
+#[test]
+fn loose_terms_after_blank_line_start_a_new_block() {
+    let text = "A\n: one\n\nB\n: two\n";
+    let blocks = support::collect_final_blocks(support::chunk_whole(text), dl_opts());
+    assert_eq!(
+        blocks,
+        vec![
+            (BlockKind::DefinitionList, "A\n: one\n\n".to_string()),
+            (BlockKind::DefinitionList, "B\n: two\n".to_string()),
+        ]
+    );
+    assert_chunking_invariant("loose_terms", text, dl_opts());
+}
+
+#[test]
+fn detects_definition_list_without_trailing_newline() {
+    let text = "Para\n\nTerm\n: Definition";
+    let blocks = support::collect_final_blocks(support::chunk_whole(text), dl_opts());
+    assert_eq!(blocks[1].0, BlockKind::DefinitionList);
+    assert_chunking_invariant("no_trailing_newline", text, dl_opts());
+}
+
+#[test]
+fn multi_line_paragraph_is_not_a_term() {
+    let text = "Line one\nline two\n: not a definition\n";
+    let blocks = support::collect_final_blocks(support::chunk_whole(text), dl_opts());
+    assert_eq!(blocks.len(), 1);
+    assert_eq!(blocks[0].0, BlockKind::Paragraph);
+}
@@ -0,0 +1,92 @@
+use mdstream::{DiagnosticKind, MdStream, Options};
+
+fn diagnostics_opts() -> Options {
+    Options {
+        diagnostics: true,
+        ..Default::default()
+    }
+}
+
+#[test]
+fn diagnostics_are_empty_by_default() {
+    let mut s = MdStream::new(Options::default());
+    let u = s.append("<div>\n</span>\n</div>\n\n```rs\nfn main() {}\n");
+    assert!(u.diagnostics.is_empty());
+    let u = s.finalize();
+    assert!(u.diagnostics.is_empty());
+}
+
+#[test]
+fn reports_mismatched_html_close_tag() {
+    let text = "<div>\n</span>\n</div>\n";
+    let mut s = MdStream::new(diagnostics_opts());
+    let u = s.append(text);
+    assert_eq!(u.committed.len(), 1);
+    assert_eq!(u.diagnostics.len(), 1);
+    let d = &u.diagnostics[0];
+    assert_eq!(d.kind, DiagnosticKind::MismatchedHtmlCloseTag);
+    assert_eq!(&text[d.byte_range.clone()], "</span>");
+    assert!(d.message.contains("span"));
+}
+
+#[test]
+fn reports_unterminated_code_fence_at_finalize() {
+    let text = "Intro\n\n```rs\nfn main() {}\n";
+    let mut s = MdStream::new(diagnostics_opts());
+    let u = s.append(text);
+    assert!(u.diagnostics.is_empty());
+
+    let u = s.finalize();
+    assert_eq!(u.diagnostics.len(), 1);
+    let d = &u.diagnostics[0];
+    assert_eq!(d.kind, DiagnosticKind::UnterminatedCodeFence);
+    assert_eq!(&text[d.byte_range.clone()], "```rs\nfn main() {}\n");
+}
+
+#[test]
+fn closing_fence_without_newline_is_not_reported() {
+    let mut s = MdStream::new(diagnostics_opts());
+    s.append("```rs\nfn main() {}\n```");
+    let u = s.finalize();
+    assert!(u.diagnostics.is_empty());
+}
+
+#[test]
+fn reports_table_rows_with_inconsistent_columns() {
+    let text = "| a | b |\n|---|---|\n| 1 | 2 |\n| 3 |\n\nAfter\n";
+    let mut s = MdStream::new(diagnostics_opts());
+    let u = s.append(text);
+    assert_eq!(u.diagnostics.len(), 1);
+    let d = &u.diagnostics[0];
+    assert_eq!(d.kind, DiagnosticKind::InconsistentTableColumns);
+    assert_eq!(&text[d.byte_range.clone()], "| 3 |");
+}
+
+#[test]
+fn diagnostic_offsets_survive_buffer_compaction() {
+    let opts = Options {
+        diagnostics: true,
+        max_buffer_bytes: Some(32),
+        ..Default::default()
+    };
+    let mut s = MdStream::new(opts);
+    let mut text = String::new();
+    for i in 0..10 {
+        let chunk = format!("# Heading {i}\n\n");
+        s.append(&chunk);
+        text.push_str(&chunk);
+    }
+    let chunk = "<div>\n</p>\n</div>\n";
+    let u = s.append(chunk);
+    text.push_str(chunk);
+    assert_eq!(u.diagnostics.len(), 1);
+    assert_eq!(&text[u.diagnostics[0].byte_range.clone()], "</p>");
+}
+
+#[test]
+fn table_cells_respect_escapes_and_code_spans() {
+    let text = "| a | `x|y` |\n|---|---|\n| 1 | 2 \\| 3 |\n\nAfter\n";
+    let mut s = MdStream::new(diagnostics_opts());
+    let u = s.append(text);
+    assert!(u.diagnostics.is_empty(), "{:?}", u.diagnostics);
+}
@@ -0,0 +1,122 @@
+use mdstream::{Block, MdStream, Options};
+
+fn parse(text: &str) -> Vec<Block> {
+    MdStream::new(Options::default())
+        .parse_complete(text)
+        .collect()
+}
+
+fn raws(blocks: &[Block]) -> Vec<&str> {
+    blocks.iter().map(|b| b.raw.as_str()).collect()
+}
+
+/// Apply a `diff_replay` update to the previous blocks the way a renderer would.
+fn apply(prev: &[Block], update: &mdstream::Update) -> Vec<Block> {
+    let mut blocks: Vec<Block> = prev
+        .iter()
+        .filter(|b| !update.invalidated.contains(&b.id))
+        .cloned()
+        .collect();
+    blocks.extend(update.committed.iter().cloned());
+    blocks
+}
+
+#[test]
+fn identical_replay_emits_nothing() {
+    let text = "# Title\n\nBody\n\n- a\n- b\n";
+    let prev = parse(text);
+    let mut s = MdStream::new(Options::default());
+    let u = s.diff_replay(&prev, text);
+    assert!(u.committed.is_empty());
+    assert!(u.invalidated.is_empty());
+    assert!(!u.reset);
+    assert!(u.pending.is_none());
+    assert_eq!(s.first_committed_block().map(|b| b.id), Some(prev[0].id));
+}
+
+#[test]
+fn append_only_replay_reuses_prefix_ids() {
+    let prev = parse("# Title\n\nBody\n\nTail");
+    let text = "# Title\n\nBody\n\nTail grows.\n\nNew paragraph\n";
+    let mut s = MdStream::new(Options::default());
+    let u = s.diff_replay(&prev, text);
+
+    // Only the (changed) last block and what follows it are emitted.
+    assert_eq!(u.invalidated, vec![prev[2].id]);
+    assert_eq!(
+        raws(&u.committed),
+        vec!["Tail grows.\n\n", "New paragraph\n"]
+    );
+    assert!(u.committed.iter().all(|b| b.id > prev[2].id));
+
+    let merged = apply(&prev, &u);
+    assert_eq!(raws(&merged), raws(&parse(text)));
+    assert_eq!(merged[0].id, prev[0].id);
+    assert_eq!(merged[1].id, prev[1].id);
+}
+
+#[test]
+fn mid_edit_invalidates_everything_after_the_change() {
+    let prev = parse("# Title\n\nFirst\n\nSecond\n\nThird\n");
+    let text = "# Title\n\nFirst, edited\n\nSecond\n\nThird\n";
+    let mut s = MdStream::new(Options::default());
+    let u = s.diff_replay(&prev, text);
+
+    assert_eq!(
+        u.invalidated,
+        prev[1..].iter().map(|b| b.id).collect::<Vec<_>>()
+    );
+    assert_eq!(
+        raws(&u.committed),
+        vec!["First, edited\n\n", "Second\n\n", "Third\n"]
+    );
+    assert_eq!(raws(&apply(&prev, &u)), raws(&parse(text)));
+}
+
+#[test]
+fn replay_with_fewer_blocks_only_invalidates() {
+    let prev = parse("A\n\nB\n\nC\n");
+    let mut s = MdStream::new(Options::default());
+    let u = s.diff_replay(&prev, "A\n\n");
+    assert!(u.committed.is_empty());
+    assert_eq!(u.invalidated, vec![prev[1].id, prev[2].id]);
+}
+
+#[test]
+fn stream_holds_the_replayed_document_with_reused_ids() {
+    let prev = parse("A\n\nB\n\n");
+    let mut s = MdStream::new(Options::default());
+    let u = s.diff_replay(&prev, "A\n\nB, edited\n\n");
+    let edited = u.committed[0].id;
+    assert!(edited > prev[1].id);
+    assert_eq!(s.committed_block_by_id(prev[0].id).unwrap().raw, "A\n\n");
+    assert_eq!(
+        s.committed_block_by_id(edited).unwrap().raw,
+        "B, edited\n\n"
+    );
+    assert!(s.committed_block_by_id(prev[1].id).is_none());
+
+    s.replace_committed_block(prev[0].id, "A2\n\n").unwrap();
+    assert_eq!(s.first_committed_block().unwrap().raw, "A2\n\n");
+}
+
+#[cfg(feature = "std")]
+#[test]
+fn replay_reindexes_reference_usages() {
+    use mdstream::ReferenceDefinitionsMode;
+
+    let opts = Options {
+        reference_definitions: ReferenceDefinitionsMode::Invalidate,
+        ..Options::default()
+    };
+    let prev: Vec<Block> = MdStream::new(opts.clone())
+        .parse_complete("See [docs][d].\n\nMore\n\n")
+        .collect();
+    let mut s = MdStream::new(opts);
+    let u = s.diff_replay(&prev, "See [docs][d].\n\nMore, edited\n\n");
+    let more = u.committed[0].id;
+    let u = s
+        .replace_committed_block(more, "[d]: https://example.com\n\n")
+        .unwrap();
+    assert_eq!(u.invalidated, vec![more, prev[0].id]);
+}
@@ -1,4 +1,4 @@
-use mdstream::{Block, BlockId, BlockKind, BlockStatus, DocumentState, Update};
+use mdstream::{Block, BlockId, BlockKind, BlockStatus, DocumentState, MdStream, Options, Update};
 
 #[test]
 fn document_state_applies_updates_in_order() {
@@ -21,6 +21,9 @@ fn document_state_applies_updates_in_order() {
         }),
         reset: false,
         invalidated: Vec::new(),
+        reemitted: Vec::new(),
+        diagnostics: Vec::new(),
+        finished_messages: Vec::new(),
     };
     let applied1 = state.apply(u1);
     assert!(!applied1.reset);
@@ -40,6 +43,9 @@ fn document_state_applies_updates_in_order() {
         pending: None,
         reset: false,
         invalidated: vec![BlockId(1)],
+        reemitted: Vec::new(),
+        diagnostics: Vec::new(),
+        finished_messages: Vec::new(),
     };
     let applied2 = state.apply(u2);
     assert!(!applied2.reset);
@@ -72,6 +78,9 @@ fn document_state_reset_clears_previous_blocks() {
         }),
         reset: false,
         invalidated: Vec::new(),
+        reemitted: Vec::new(),
+        diagnostics: Vec::new(),
+        finished_messages: Vec::new(),
     });
 
     let applied = state.apply(Update {
@@ -85,9 +94,38 @@ fn document_state_reset_clears_previous_blocks() {
         pending: None,
         reset: true,
         invalidated: Vec::new(),
+        reemitted: Vec::new(),
+        diagnostics: Vec::new(),
+        finished_messages: Vec::new(),
     });
     assert!(applied.reset);
     assert_eq!(state.committed().len(), 1);
     assert_eq!(state.committed()[0].raw, "X\n");
     assert!(state.pending().is_none());
 }
+
+#[test]
+fn document_state_blocks_yield_committed_then_pending() {
+    let mut state = DocumentState::new();
+    assert!(state.is_empty());
+    assert_eq!(state.len(), 0);
+    assert_eq!(state.blocks().count(), 0);
+
+    let mut s = MdStream::new(Options::default());
+    state.apply(s.append("# Title\n\nBody\n\nTail"));
+    assert!(!state.is_empty());
+    assert_eq!(state.len(), 3);
+    let raws: Vec<&str> = state.blocks().map(|b| b.raw.as_str()).collect();
+    assert_eq!(raws, vec!["# Title\n", "Body\n\n", "Tail"]);
+    assert_eq!(
+        state.blocks().last().map(|b| b.status),
+        Some(BlockStatus::Pending)
+    );
+
+    state.apply(s.finalize());
+    assert_eq!(state.len(), state.committed().len());
+    assert!(state.pending().is_none());
+
+    state.clear();
+    assert!(state.is_empty());
+}
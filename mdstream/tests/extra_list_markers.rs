@@ -0,0 +1,77 @@
+mod support;
+
+use mdstream::{BlockKind, MdStream, Options};
+use support::{chunk_chars, chunk_lines, chunk_pseudo_random, chunk_whole, collect_final_blocks};
+
+fn bullets() -> Options {
+    Options {
+        extra_list_markers: vec!['•', '‣'],
+        ..Options::default()
+    }
+}
+
+fn blocks(text: &str, opts: Options) -> Vec<(BlockKind, String)> {
+    let whole = collect_final_blocks(chunk_whole(text), opts.clone());
+    assert_eq!(collect_final_blocks(chunk_lines(text), opts.clone()), whole);
+    assert_eq!(collect_final_blocks(chunk_chars(text), opts.clone()), whole);
+    for trial in 0..8 {
+        let chunks = chunk_pseudo_random(text, "extra_list_markers", trial, 5);
+        assert_eq!(collect_final_blocks(chunks, opts.clone()), whole);
+    }
+    whole
+}
+
+const DOC: &str = "Shopping:\n• milk\n• bread\n  ‣ rye\n\n• eggs\n\nDone\n";
+
+#[test]
+fn unicode_bullets_form_a_list_when_enabled() {
+    assert_eq!(
+        blocks(DOC, bullets()),
+        vec![
+            (BlockKind::Paragraph, "Shopping:\n".to_string()),
+            (
+                BlockKind::List,
+                "• milk\n• bread\n  ‣ rye\n\n• eggs\n\n".to_string()
+            ),
+            (BlockKind::Paragraph, "Done\n".to_string()),
+        ]
+    );
+}
+
+#[test]
+fn unicode_bullets_are_paragraph_text_by_default() {
+    assert_eq!(
+        blocks(DOC, Options::default()),
+        vec![
+            (
+                BlockKind::Paragraph,
+                "Shopping:\n• milk\n• bread\n  ‣ rye\n\n".to_string()
+            ),
+            (BlockKind::Paragraph, "• eggs\n\n".to_string()),
+            (BlockKind::Paragraph, "Done\n".to_string()),
+        ]
+    );
+}
+
+#[test]
+fn marker_needs_whitespace_after_it() {
+    assert_eq!(
+        blocks("•not a list\n", bullets()),
+        vec![(BlockKind::Paragraph, "•not a list\n".to_string())]
+    );
+}
+
+#[test]
+fn pending_bullet_reports_a_list() {
+    let mut s = MdStream::new(bullets());
+    let u = s.append("• first item");
+    assert_eq!(u.pending.unwrap().kind, BlockKind::List);
+}
+
+#[test]
+fn ascii_and_unicode_bullets_mix() {
+    assert_eq!(
+        blocks("- a\n• b\n* c\n", bullets()),
+        vec![(BlockKind::List, "- a\n• b\n* c\n".to_string())]
+    );
+}
@@ -0,0 +1,81 @@
+use mdstream::pending::{TerminatorOptions, terminate_markdown};
+use mdstream::{BlockKind, MdStream, Options};
+
+const HEADERS: &[&str] = &[
+    "```python {.line-numbers startFrom=\"10\"}",
+    "```{r, echo=FALSE, fig.cap=\"*A* _plot_\"}",
+    "~~~ {#snippet .haskell data-label=\"`x`\"}",
+];
+
+fn header_prefixes(header: &str) -> impl Iterator<Item = &str> {
+    (1..=header.len())
+        .filter(|&n| header.is_char_boundary(n))
+        .map(|n| &header[..n])
+        .filter(|p| p.contains('{'))
+}
+
+#[test]
+fn terminator_leaves_partial_attribute_headers_untouched() {
+    let opts = TerminatorOptions::default();
+    for header in HEADERS {
+        for prefix in header_prefixes(header) {
+            assert_eq!(terminate_markdown(prefix, &opts), prefix, "{prefix:?}");
+            let after_intro = format!("Intro\n\n{prefix}");
+            assert_eq!(terminate_markdown(&after_intro, &opts), after_intro);
+        }
+    }
+}
+
+#[test]
+fn pending_fence_header_display_is_not_repaired() {
+    for header in HEADERS {
+        for prefix in header_prefixes(header) {
+            let mut s = MdStream::new(Options::default());
+            let pending = s.append(prefix).pending.expect("pending");
+            assert_eq!(pending.kind, BlockKind::CodeFence, "{prefix:?}");
+            let display = pending.display.as_deref().unwrap_or(&pending.raw);
+            assert_eq!(display.trim_end(), prefix.trim_end(), "{prefix:?}");
+
+            // The borrowed path adds a synthetic closing fence, but the header line stays as typed.
+            let mut s = MdStream::new(Options::default());
+            let u = s.append_ref(prefix);
+            let pending = u.pending.expect("pending");
+            let display = pending.display.unwrap_or(pending.raw);
+            let first_line = display.split('\n').next().unwrap_or_default();
+            assert_eq!(first_line.trim_end(), prefix.trim_end(), "{prefix:?}");
+        }
+    }
+}
+
+#[test]
+fn streamed_attribute_header_commits_whole_fence() {
+    for header in HEADERS {
+        let fence: String = header
+            .chars()
+            .take_while(|&c| c == '`' || c == '~')
+            .collect();
+        let doc = format!("{header}\nlet a = `b` * c;\n{fence}\n\nAfter\n");
+        let mut s = MdStream::new(Options {
+            commit_with_display: true,
+            ..Default::default()
+        });
+        let mut committed = Vec::new();
+        for c in doc.chars() {
+            let u = s.append(&c.to_string());
+            committed.extend(u.committed);
+            if let Some(p) = u.pending {
+                if p.raw.starts_with(&fence) {
+                    assert_eq!(p.kind, BlockKind::CodeFence);
+                }
+            }
+        }
+        committed.extend(s.finalize().committed);
+        assert_eq!(committed.len(), 2, "{doc:?}");
+        assert_eq!(committed[0].kind, BlockKind::CodeFence);
+        assert_eq!(
+            committed[0].raw,
+            format!("{header}\nlet a = `b` * c;\n{fence}\n")
+        );
+        assert_eq!(committed[0].display, None);
+    }
+}
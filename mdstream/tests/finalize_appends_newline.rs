@@ -0,0 +1,95 @@
+mod support;
+
+use mdstream::{BlockKind, MdStream, Options};
+use support::{chunk_chars, chunk_whole, collect_final_blocks};
+
+fn opts() -> Options {
+    Options {
+        finalize_appends_newline: true,
+        ..Default::default()
+    }
+}
+
+fn kinds_and_raws(blocks: &[mdstream::Block]) -> Vec<(BlockKind, &str)> {
+    blocks.iter().map(|b| (b.kind, b.raw.as_str())).collect()
+}
+
+#[test]
+fn unterminated_table_delimiter_row_is_recognized() {
+    let text = "Intro\n| a | b |\n|---|---|";
+
+    let default_blocks = collect_final_blocks(chunk_whole(text), Options::default());
+    assert_eq!(
+        default_blocks.last().map(|b| b.0),
+        Some(BlockKind::Paragraph)
+    );
+
+    let blocks = collect_final_blocks(chunk_whole(text), opts());
+    let with_newline = collect_final_blocks(chunk_whole(&format!("{text}\n")), Options::default());
+    assert_eq!(blocks, with_newline);
+    assert_eq!(blocks.last().map(|b| b.0), Some(BlockKind::Table));
+}
+
+#[test]
+fn unterminated_table_row_commits_with_newline() {
+    let text = "| a | b |\n|---|---|\n| 1 | 2 |";
+    let blocks = collect_final_blocks(chunk_chars(text), opts());
+    assert_eq!(
+        blocks,
+        vec![(
+            BlockKind::Table,
+            "| a | b |\n|---|---|\n| 1 | 2 |\n".to_string()
+        )]
+    );
+}
+
+#[test]
+fn concatenated_raw_gains_exactly_one_newline() {
+    let text = "- a\n- b\n\nlast line";
+    let mut s = MdStream::new(opts());
+    let mut blocks = s.append(text).committed;
+    blocks.extend(s.finalize().committed);
+    let joined: String = blocks.iter().map(|b| b.raw.as_str()).collect();
+    assert_eq!(joined, format!("{text}\n"));
+}
+
+#[test]
+fn no_extra_newline_when_input_already_ends_with_one() {
+    for text in ["para\n", "para\r\n", "para\r", ""] {
+        let mut s = MdStream::new(opts());
+        let mut blocks = s.append(text).committed;
+        blocks.extend(s.finalize().committed);
+        let joined: String = blocks.iter().map(|b| b.raw.as_str()).collect();
+        let expected = if text.is_empty() { "" } else { "para\n" };
+        assert_eq!(joined, expected, "input {text:?}");
+    }
+}
+
+#[test]
+fn finalize_twice_appends_once() {
+    let mut s = MdStream::new(opts());
+    s.append("tail");
+    let u = s.finalize();
+    assert_eq!(
+        kinds_and_raws(&u.committed),
+        vec![(BlockKind::Paragraph, "tail\n")]
+    );
+    let u = s.finalize();
+    assert!(u.committed.is_empty());
+    assert!(u.pending.is_none());
+}
+
+#[test]
+fn held_back_terminator_prefix_is_flushed_before_the_newline() {
+    let mut s = MdStream::new(Options {
+        message_terminator: Some("<|eot|>".to_string()),
+        ..opts()
+    });
+    s.append("tail <|eo");
+    let u = s.finalize();
+    assert!(u.finished_messages.is_empty());
+    assert_eq!(
+        kinds_and_raws(&u.committed),
+        vec![(BlockKind::Paragraph, "tail <|eo\n")]
+    );
+}
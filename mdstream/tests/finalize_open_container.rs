@@ -0,0 +1,82 @@
+use mdstream::{BlockKind, BlockStatus, DocumentState, MdStream, Options, TagBoundaryPlugin};
+
+fn stream(keep_open: bool) -> MdStream {
+    MdStream::new(Options {
+        commit_on_finalize_returns_pending_if_open_container: keep_open,
+        ..Default::default()
+    })
+    .with_boundary_plugin(TagBoundaryPlugin::thinking())
+}
+
+const CUT_OFF: &str = "Intro\n\n<thinking>\nstep one\n\nstep two";
+
+#[test]
+fn unterminated_container_is_committed_by_default() {
+    let mut s = stream(false);
+    s.append(CUT_OFF);
+    assert!(s.has_open_container());
+
+    let u = s.finalize();
+    assert!(u.pending.is_none());
+    let last = u.committed.last().expect("committed");
+    assert_eq!(last.kind, BlockKind::Unknown);
+    assert_eq!(last.raw, "<thinking>\nstep one\n\nstep two");
+    assert!(!s.has_open_container());
+}
+
+#[test]
+fn unterminated_container_stays_pending_when_enabled() {
+    let mut s = stream(true);
+    let mut state = DocumentState::new();
+    state.apply(s.append(CUT_OFF));
+
+    let u = s.finalize();
+    assert!(u.committed.is_empty());
+    let pending = u.pending.clone().expect("pending");
+    assert_eq!(pending.status, BlockStatus::Pending);
+    assert_eq!(pending.raw, "<thinking>\nstep one\n\nstep two");
+    state.apply(u);
+    assert_eq!(state.committed().len(), 1);
+    assert_eq!(
+        state.pending().map(|b| b.raw.as_str()),
+        Some(pending.raw.as_str())
+    );
+    assert!(s.has_open_container());
+
+    // Finalizing again keeps reporting the same pending block.
+    assert_eq!(s.finalize().pending, Some(pending));
+    let r = s.finalize_ref();
+    assert!(r.committed.is_empty());
+    assert_eq!(
+        r.pending.map(|p| p.raw),
+        Some("<thinking>\nstep one\n\nstep two")
+    );
+
+    // The container can still be closed later.
+    let u = s.append("\n</thinking>\n");
+    assert_eq!(
+        u.committed.last().map(|b| b.raw.as_str()),
+        Some("<thinking>\nstep one\n\nstep two\n</thinking>\n")
+    );
+    assert!(!s.has_open_container());
+}
+
+#[test]
+fn open_tag_without_newline_counts_as_open_container() {
+    let mut s = stream(true);
+    s.append("<thinking>");
+    assert!(s.has_open_container());
+    let u = s.finalize();
+    assert!(u.committed.is_empty());
+    assert_eq!(u.pending.map(|b| b.raw), Some("<thinking>".to_string()));
+}
+
+#[test]
+fn closed_container_and_plain_text_finalize_normally() {
+    let mut s = stream(true);
+    s.append("<thinking>\nx\n</thinking>\n\ntail");
+    assert!(!s.has_open_container());
+    let u = s.finalize();
+    assert!(u.pending.is_none());
+    assert_eq!(u.committed.last().map(|b| b.raw.as_str()), Some("tail"));
+}
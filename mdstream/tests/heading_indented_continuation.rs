@@ -0,0 +1,102 @@
+mod support;
+
+use mdstream::{BlockKind, MdStream, Options};
+use support::{chunk_chars, chunk_lines, chunk_pseudo_random, chunk_whole, collect_final_blocks};
+
+use BlockKind::{Heading, List, Paragraph};
+
+fn absorbing() -> Options {
+    Options {
+        heading_absorbs_indented_continuation: true,
+        ..Default::default()
+    }
+}
+
+fn blocks(text: &str, opts: Options) -> Vec<(BlockKind, String)> {
+    let whole = collect_final_blocks(chunk_whole(text), opts.clone());
+    assert_eq!(collect_final_blocks(chunk_lines(text), opts.clone()), whole);
+    assert_eq!(collect_final_blocks(chunk_chars(text), opts.clone()), whole);
+    for trial in 0..8 {
+        assert_eq!(
+            collect_final_blocks(chunk_pseudo_random(text, text, trial, 4), opts.clone()),
+            whole,
+            "{text:?} trial {trial}"
+        );
+    }
+    whole
+}
+
+fn owned(expected: &[(BlockKind, &str)]) -> Vec<(BlockKind, String)> {
+    expected.iter().map(|(k, r)| (*k, r.to_string())).collect()
+}
+
+#[test]
+fn indented_lines_stay_in_the_heading_block() {
+    let text = "# Task\n    id: 42\n\tstatus: open\nBody\n";
+    assert_eq!(
+        blocks(text, absorbing()),
+        owned(&[
+            (Heading, "# Task\n    id: 42\n\tstatus: open\n"),
+            (Paragraph, "Body\n"),
+        ])
+    );
+    // Off by default: the heading is a single line.
+    assert_eq!(
+        blocks(text, Options::default()),
+        owned(&[
+            (Heading, "# Task\n"),
+            (Paragraph, "    id: 42\n\tstatus: open\nBody\n"),
+        ])
+    );
+}
+
+#[test]
+fn blank_or_shallow_lines_end_the_heading() {
+    for (text, expected) in [
+        (
+            "# A\n\n    code-ish\n",
+            &[(Heading, "# A\n"), (Paragraph, "    code-ish\n")][..],
+        ),
+        (
+            "# A\n  - item\n",
+            &[(Heading, "# A\n"), (List, "  - item\n")][..],
+        ),
+        (
+            "# A\n## B\n",
+            &[(Heading, "# A\n"), (Heading, "## B\n")][..],
+        ),
+        ("# A\n    meta", &[(Heading, "# A\n    meta")][..]),
+    ] {
+        assert_eq!(blocks(text, absorbing()), owned(expected), "{text:?}");
+    }
+}
+
+#[test]
+fn common_documents_split_the_same_with_the_option() {
+    let text = "# Title\n\nIntro text.\n\n## Section\nBody\n\n- a\n- b\n\n### End\n";
+    assert_eq!(blocks(text, absorbing()), blocks(text, Options::default()));
+}
+
+#[test]
+fn heading_is_pending_until_the_next_line_decides() {
+    let mut s = MdStream::new(absorbing());
+    let u = s.append("# Title\n");
+    assert!(u.committed.is_empty());
+    assert_eq!(u.pending.map(|b| b.kind), Some(Heading));
+
+    // A whitespace-only line could still become indented content.
+    let u = s.append("    ");
+    assert!(u.committed.is_empty());
+    let u = s.append("meta\nText");
+    assert_eq!(
+        u.committed
+            .iter()
+            .map(|b| (b.kind, b.raw.as_str()))
+            .collect::<Vec<_>>(),
+        vec![(Heading, "# Title\n    meta\n")]
+    );
+    assert_eq!(
+        u.pending.map(|b| (b.kind, b.raw)),
+        Some((Paragraph, "Text".to_string()))
+    );
+}
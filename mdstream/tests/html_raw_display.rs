@@ -0,0 +1,65 @@
+use mdstream::{BlockKind, MdStream, Options};
+
+fn pending_display(opts: Options, chunks: &[&str]) -> (BlockKind, String) {
+    let mut s = MdStream::new(opts);
+    let mut last = None;
+    for chunk in chunks {
+        last = s.append(chunk).pending;
+    }
+    let p = last.expect("pending");
+    (p.kind, p.display.expect("display"))
+}
+
+#[test]
+fn html_block_pending_display_is_not_terminated() {
+    let (kind, display) = pending_display(Options::default(), &["<div>*text*"]);
+    assert_eq!(kind, BlockKind::HtmlBlock);
+    assert_eq!(display, "<div>*text*");
+
+    for chunks in [
+        &["<div>\n*text"][..],
+        &["<div>\n", "**bo", "ld `co"][..],
+        &["<di", "v>\n[link](http://ex"][..],
+    ] {
+        let raw: String = chunks.concat();
+        let (kind, display) = pending_display(Options::default(), chunks);
+        assert_eq!(kind, BlockKind::HtmlBlock);
+        assert_eq!(display, raw, "chunks: {chunks:?}");
+    }
+}
+
+#[test]
+fn html_block_terminated_when_option_is_off() {
+    let opts = Options {
+        keep_html_raw_text_verbatim: false,
+        ..Default::default()
+    };
+    let (kind, display) = pending_display(opts, &["<div>\n**bold"]);
+    assert_eq!(kind, BlockKind::HtmlBlock);
+    assert_eq!(display, "<div>\n**bold**");
+}
+
+#[test]
+fn committed_html_block_keeps_no_display() {
+    let mut s = MdStream::new(Options {
+        commit_with_display: true,
+        ..Default::default()
+    });
+    s.append("<div>\n*open\n</div>\n\n");
+    let u = s.append("after\n");
+    let html = s
+        .snapshot_blocks()
+        .into_iter()
+        .find(|b| b.kind == BlockKind::HtmlBlock)
+        .expect("html block");
+    assert_eq!(html.raw, "<div>\n*open\n</div>\n");
+    assert_eq!(html.display, None);
+    assert_eq!(u.pending.expect("pending").raw, "after\n");
+}
+
+#[test]
+fn paragraph_after_html_block_is_still_terminated() {
+    let (kind, display) = pending_display(Options::default(), &["<div>x</div>\n\n**bold"]);
+    assert_eq!(kind, BlockKind::Paragraph);
+    assert_eq!(display, "**bold**");
+}
@@ -58,3 +58,15 @@ fn new_footnote_definition_ends_previous_one() {
     assert!(u3.pending.is_some());
     assert_eq!(u3.pending.as_ref().unwrap().raw, "[^2]: new\n");
 }
+
+#[test]
+fn invalidate_mode_skips_footnote_detection() {
+    let mut s = MdStream::new(opts_invalidate());
+    let u = s.append("See note[^1].\n\n[^1]: The note.\n");
+    assert!(!u.reset);
+    assert!(!s.footnotes_detected());
+
+    let mut single = MdStream::new(Options::default());
+    single.append("See note[^1].\n");
+    assert!(single.footnotes_detected());
+}
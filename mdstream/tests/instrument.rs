@@ -0,0 +1,107 @@
+#![cfg(feature = "std")]
+
+use std::sync::{Arc, Mutex};
+
+use mdstream::{AppendTiming, MdStream, Options};
+
+fn recording_stream() -> (MdStream, Arc<Mutex<Vec<AppendTiming>>>) {
+    let timings = Arc::new(Mutex::new(Vec::new()));
+    let sink = Arc::clone(&timings);
+    let opts = Options {
+        instrument: Some(Arc::new(move |t| sink.lock().unwrap().push(t))),
+        ..Default::default()
+    };
+    (MdStream::new(opts), timings)
+}
+
+#[test]
+fn records_one_timing_per_append() {
+    let (mut s, timings) = recording_stream();
+    s.append("# Title\nPara");
+    s.append("graph\n\nNext");
+    s.append(" line");
+    s.finalize();
+
+    let timings = timings.lock().unwrap();
+    assert_eq!(timings.len(), 3);
+    let lines: Vec<usize> = timings.iter().map(|t| t.lines_processed).collect();
+    assert_eq!(lines, vec![1, 2, 0]);
+    let committed: Vec<usize> = timings.iter().map(|t| t.committed).collect();
+    assert_eq!(committed, vec![1, 1, 0]);
+    let bytes: Vec<usize> = timings.iter().map(|t| t.chunk_bytes).collect();
+    assert_eq!(bytes, vec![12, 11, 5]);
+    assert!(timings.iter().all(|t| t.termination_nanos <= t.total_nanos));
+}
+
+#[test]
+fn append_ref_reports_the_same_counts() {
+    let (mut owned, owned_timings) = recording_stream();
+    let (mut borrowed, borrowed_timings) = recording_stream();
+    for chunk in ["- a\n- b\n", "\nText\n\n", "```rs\nfn x() {}\n"] {
+        owned.append(chunk);
+        borrowed.append_ref(chunk);
+    }
+
+    let counts = |t: &Arc<Mutex<Vec<AppendTiming>>>| {
+        t.lock()
+            .unwrap()
+            .iter()
+            .map(|t| (t.chunk_bytes, t.lines_processed, t.committed))
+            .collect::<Vec<_>>()
+    };
+    assert_eq!(counts(&owned_timings), counts(&borrowed_timings));
+    assert_eq!(
+        counts(&owned_timings),
+        vec![(8, 2, 0), (7, 3, 1), (16, 2, 1)]
+    );
+}
+
+#[test]
+fn whitespace_only_chunks_skip_boundary_checks_where_safe() {
+    let (mut s, timings) = recording_stream();
+    let chunks = [
+        "```rs\nfn x() {\n", // 0: opens a fence
+        "    ",              // 1: indentation inside the fence
+        "\t",                // 2
+        "y();\n}\n```\n",    // 3: closes the fence
+        "Para\n",            // 4
+        "  ",                // 5: blank tail line after a paragraph line
+        "\n- item",          // 6
+        " ",                 // 7: trailing space after content is re-checked
+        "\n",                // 8
+    ];
+    for chunk in chunks {
+        s.append(chunk);
+    }
+    let fast: Vec<usize> = timings
+        .lock()
+        .unwrap()
+        .iter()
+        .enumerate()
+        .filter(|(_, t)| t.whitespace_fast_path)
+        .map(|(i, _)| i)
+        .collect();
+    assert_eq!(fast, vec![1, 2, 5]);
+
+    let mut whole = MdStream::new(Options::default());
+    whole.append(&chunks.concat());
+    let mut a = s.snapshot_blocks();
+    let mut b = whole.snapshot_blocks();
+    for block in a.iter_mut().chain(b.iter_mut()) {
+        block.display = None;
+    }
+    assert_eq!(a, b);
+}
+
+#[test]
+fn whitespace_fast_path_keeps_code_fence_display_current() {
+    let (mut s, _) = recording_stream();
+    s.append("```\nx\n");
+    let u = s.append("  ");
+    let pending = u.pending.expect("pending");
+    assert_eq!(pending.raw, "```\nx\n  ");
+
+    let mut whole = MdStream::new(Options::default());
+    let expected = whole.append("```\nx\n  ").pending.expect("pending");
+    assert_eq!(pending.display, expected.display);
+}
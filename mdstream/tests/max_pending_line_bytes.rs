@@ -0,0 +1,124 @@
+use mdstream::{BlockKind, MdStream, Options};
+
+fn limited(limit: usize) -> Options {
+    Options {
+        max_pending_line_bytes: Some(limit),
+        ..Default::default()
+    }
+}
+
+#[test]
+fn giant_newline_free_line_keeps_pending_snapshot_bounded() {
+    const LIMIT: usize = 1024;
+    let chunk = "lorem ipsum **dolor** sit amet é ".repeat(64);
+    let mut s = MdStream::new(limited(LIMIT));
+    let mut expected = String::new();
+
+    for _ in 0..400 {
+        expected.push_str(&chunk);
+        let u = s.append(&chunk);
+        assert!(u.committed.is_empty());
+        let pending = u.pending.expect("pending");
+        assert!(pending.raw.len() <= LIMIT);
+        assert!(expected.ends_with(&pending.raw));
+        let display = pending.display.expect("display");
+        assert!(display.len() <= LIMIT + 16);
+
+        let borrowed = s.append_ref("");
+        assert!(borrowed.pending.expect("pending").raw.len() <= LIMIT);
+    }
+    assert!(expected.len() > 500 * LIMIT);
+
+    let u = s.finalize();
+    assert_eq!(u.committed.len(), 1);
+    assert_eq!(u.committed[0].raw, expected);
+}
+
+#[test]
+fn limit_only_applies_to_an_over_long_last_line() {
+    // The block is longer than the limit, but no line is.
+    let mut s = MdStream::new(limited(12));
+    let u = s.append("- item one\n- item two");
+    assert_eq!(u.pending.expect("pending").raw, "- item one\n- item two");
+
+    let u = s.append("\n\nplain paragraph text");
+    assert_eq!(u.committed[0].raw, "- item one\n- item two\n\n");
+    assert_eq!(u.pending.expect("pending").raw, "ragraph text");
+
+    let u = s.append("\n");
+    assert_eq!(u.pending.expect("pending").raw, "plain paragraph text\n");
+}
+
+#[test]
+fn giant_last_line_of_a_multi_line_block_is_bounded() {
+    const LIMIT: usize = 256;
+    let mut s = MdStream::new(limited(LIMIT));
+    s.append("```rust\n");
+    let chunk = "let x = 1; ".repeat(32);
+    let mut line = String::new();
+    for _ in 0..200 {
+        line.push_str(&chunk);
+        let u = s.append(&chunk);
+        let pending = u.pending.expect("pending");
+        assert_eq!(pending.kind, BlockKind::CodeFence);
+        assert!(pending.raw.len() <= LIMIT);
+        assert!(line.ends_with(&pending.raw));
+        let display = pending.display.expect("display");
+        assert_eq!(display, format!("```rust\n{}\n```\n", pending.raw));
+    }
+
+    let u = s.append("\n```\n");
+    assert_eq!(u.committed.len(), 1);
+    assert_eq!(u.committed[0].raw, format!("```rust\n{line}\n```\n"));
+}
+
+#[test]
+fn bounded_code_fence_display_keeps_the_fence() {
+    // Fed the same chunks, owned and borrowed appends must show the cut tail as code.
+    let chunks = ["y".repeat(40), " see *a `b".to_string()];
+    let mut owned = MdStream::new(limited(16));
+    let mut borrowed = MdStream::new(limited(16));
+    owned.append("```\n");
+    borrowed.append_ref("```\n");
+    for chunk in &chunks {
+        let pending = owned.append(chunk).pending.expect("pending");
+        let pending_ref = borrowed.append_ref(chunk).pending.expect("pending");
+        assert_eq!(pending_ref.raw, pending.raw);
+        assert_eq!(pending_ref.display, pending.display.as_deref());
+    }
+
+    let pending = owned.append("").pending.expect("pending");
+    assert_eq!(pending.kind, BlockKind::CodeFence);
+    assert_eq!(pending.raw, "yyyyyy see *a `b");
+    assert_eq!(
+        pending.display.as_deref(),
+        Some("```\nyyyyyy see *a `b\n```\n")
+    );
+
+    // Once the line ends, the whole block is the snapshot again.
+    let pending = owned.append("\n").pending.expect("pending");
+    assert_eq!(pending.raw, format!("```\n{}\n", chunks.concat()));
+    let pending_ref = borrowed.append_ref("\n").pending.expect("pending");
+    assert_eq!(pending_ref.raw, pending.raw);
+    assert!(
+        pending_ref
+            .display
+            .expect("display")
+            .starts_with(&pending.raw)
+    );
+}
+
+#[test]
+fn cut_respects_char_boundaries() {
+    let mut s = MdStream::new(limited(5));
+    let u = s.append("ééééé");
+    assert_eq!(u.pending.expect("pending").raw, "éé");
+}
+
+#[test]
+fn no_limit_keeps_full_pending_line() {
+    let text = "x".repeat(100_000);
+    let mut s = MdStream::new(Options::default());
+    let u = s.append(&text);
+    assert_eq!(u.pending.expect("pending").raw, text);
+}
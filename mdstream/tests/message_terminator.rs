@@ -0,0 +1,94 @@
+use mdstream::{MdStream, Options};
+
+fn eot_opts() -> Options {
+    Options {
+        message_terminator: Some("<|eot|>".to_string()),
+        ..Default::default()
+    }
+}
+
+fn raws(blocks: &[mdstream::Block]) -> Vec<&str> {
+    blocks.iter().map(|b| b.raw.as_str()).collect()
+}
+
+#[test]
+fn sentinel_in_one_chunk_finishes_message_and_resets() {
+    let mut s = MdStream::new(eot_opts());
+    let u = s.append("Hello **world\n\nSecond<|eot|>Next message");
+    assert!(u.reset);
+    assert!(u.committed.is_empty());
+    assert_eq!(u.finished_messages.len(), 1);
+    assert_eq!(
+        raws(&u.finished_messages[0]),
+        vec!["Hello **world\n\n", "Second"]
+    );
+    let pending = u.pending.expect("pending");
+    assert_eq!(pending.raw, "Next message");
+    assert_eq!(s.snapshot_blocks().len(), 1);
+}
+
+#[test]
+fn sentinel_split_across_chunks() {
+    let mut s = MdStream::new(eot_opts());
+    let u1 = s.append("Answer<|eo");
+    assert!(!u1.reset);
+    assert_eq!(u1.pending.expect("pending").raw, "Answer");
+
+    let u2 = s.append("t|>More");
+    assert!(u2.reset);
+    assert_eq!(u2.finished_messages.len(), 1);
+    assert_eq!(raws(&u2.finished_messages[0]), vec!["Answer"]);
+    assert_eq!(u2.pending.expect("pending").raw, "More");
+}
+
+#[test]
+fn held_back_prefix_is_flushed_when_not_a_sentinel() {
+    let mut s = MdStream::new(eot_opts());
+    s.append("a <|e");
+    let u = s.append("x|> b");
+    assert!(!u.reset);
+    assert_eq!(u.pending.expect("pending").raw, "a <|ex|> b");
+
+    let mut s = MdStream::new(eot_opts());
+    s.append("tail <|eo");
+    let u = s.finalize();
+    assert!(u.finished_messages.is_empty());
+    assert_eq!(raws(&u.committed), vec!["tail <|eo"]);
+}
+
+#[test]
+fn multiple_sentinels_in_one_chunk() {
+    let mut s = MdStream::new(eot_opts());
+    let u = s.append("one<|eot|>two<|eot|>three");
+    assert!(u.reset);
+    assert_eq!(u.finished_messages.len(), 2);
+    assert_eq!(raws(&u.finished_messages[0]), vec!["one"]);
+    assert_eq!(raws(&u.finished_messages[1]), vec!["two"]);
+    assert_eq!(u.pending.expect("pending").raw, "three");
+
+    let u = s.finalize();
+    assert_eq!(raws(&u.committed), vec!["three"]);
+}
+
+#[test]
+fn append_ref_reports_finished_messages() {
+    let mut a = MdStream::new(eot_opts());
+    let mut b = MdStream::new(eot_opts());
+    for chunk in ["x\n\ny<|", "eot|>z"] {
+        let owned = a.append(chunk);
+        let borrowed = b.append_ref(chunk).to_owned();
+        assert_eq!(owned.reset, borrowed.reset);
+        assert_eq!(owned.committed, borrowed.committed);
+        assert_eq!(owned.finished_messages, borrowed.finished_messages);
+        assert_eq!(owned.pending, borrowed.pending);
+    }
+}
+
+#[test]
+fn no_terminator_leaves_sentinel_text_in_place() {
+    let mut s = MdStream::new(Options::default());
+    let u = s.append("one<|eot|>two");
+    assert!(!u.reset);
+    assert!(u.finished_messages.is_empty());
+    assert_eq!(u.pending.expect("pending").raw, "one<|eot|>two");
+}
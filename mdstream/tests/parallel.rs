@@ -0,0 +1,59 @@
+#![cfg(feature = "rayon")]
+
+use mdstream::parallel::{parse_blocks_parallel, parse_blocks_parallel_with};
+use mdstream::{Block, MdStream, Options, TagBoundaryPlugin};
+
+fn documents() -> Vec<String> {
+    (0..200)
+        .map(|i| match i % 4 {
+            0 => format!("# Doc {i}\n\nSome **bold** text.\n"),
+            1 => format!("- item {i}\n- next\n\n```rs\nlet x = {i};\n```\n"),
+            2 => format!("| a | b |\n| --- | --- |\n| {i} | x |\n\ntail [^1]\n\n[^1]: note\n"),
+            _ => format!("<thinking>\nstep {i}\n\nmore\n</thinking>\n\nanswer {i}"),
+        })
+        .collect()
+}
+
+fn sequential(docs: &[&str], make: impl Fn() -> MdStream) -> Vec<Vec<Block>> {
+    docs.iter()
+        .map(|doc| make().parse_complete(doc).collect())
+        .collect()
+}
+
+#[test]
+fn parallel_results_match_sequential() {
+    let owned = documents();
+    let docs: Vec<&str> = owned.iter().map(String::as_str).collect();
+    let opts = Options {
+        commit_with_display: true,
+        ..Default::default()
+    };
+
+    let parallel = parse_blocks_parallel(&docs, opts.clone());
+    assert_eq!(parallel.len(), docs.len());
+    assert_eq!(parallel, sequential(&docs, || MdStream::new(opts.clone())));
+}
+
+#[test]
+fn factory_configures_plugins_per_document() {
+    let owned = documents();
+    let docs: Vec<&str> = owned.iter().map(String::as_str).collect();
+    let make =
+        || MdStream::new(Options::default()).with_boundary_plugin(TagBoundaryPlugin::thinking());
+
+    let parallel = parse_blocks_parallel_with(&docs, make);
+    assert_eq!(parallel, sequential(&docs, make));
+    assert_eq!(
+        parallel[3].first().map(|b| b.raw.as_str()),
+        Some("<thinking>\nstep 3\n\nmore\n</thinking>\n")
+    );
+}
+
+#[test]
+fn empty_input_yields_no_documents() {
+    assert!(parse_blocks_parallel(&[], Options::default()).is_empty());
+    assert_eq!(
+        parse_blocks_parallel(&[""], Options::default()),
+        vec![Vec::new()]
+    );
+}
@@ -0,0 +1,35 @@
+mod support;
+
+use mdstream::{BlockKind, MdStream, Options, parse_blocks};
+
+const DOC: &str = "# Title\n\nIntro paragraph.\n\n- a\n- b\n\n```rs\nfn main() {}\n```\n\n| a | b |\n|---|---|\n| 1 | 2 |\n\nTail";
+
+#[test]
+fn parse_blocks_matches_append_and_finalize() {
+    let expected = support::collect_final_blocks(support::chunk_whole(DOC), Options::default());
+    let got: Vec<(BlockKind, String)> = parse_blocks(DOC, Options::default())
+        .map(|b| (b.kind, b.raw))
+        .collect();
+    assert_eq!(got, expected);
+    assert_eq!(got.last().map(|(k, _)| *k), Some(BlockKind::Paragraph));
+}
+
+#[test]
+fn parse_complete_uses_configured_stream() {
+    let mut s = MdStream::streamdown_defaults();
+    let blocks: Vec<_> = s.parse_complete("Para\n\nSee [link](http://").collect();
+    assert_eq!(blocks.len(), 2);
+    assert_eq!(blocks[0].raw, "Para\n\n");
+    assert_eq!(blocks[1].raw, "See [link](http://");
+}
+
+#[test]
+fn parse_complete_drops_blocks_invalidated_by_reset() {
+    let text = "A\n\nB\n\nSee note[^1].\n\n[^1]: The note.\n";
+    let expected = support::collect_final_raw(support::chunk_whole(text), Options::default());
+    let got: Vec<String> = parse_blocks(text, Options::default())
+        .map(|b| b.raw)
+        .collect();
+    assert_eq!(got, expected);
+    assert_eq!(got.concat(), text);
+}
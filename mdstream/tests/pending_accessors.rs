@@ -0,0 +1,49 @@
+mod support;
+
+use mdstream::{MdStream, Options};
+use support::{chunk_chars, chunk_pseudo_random};
+
+const DOC: &str = "# Title\n\nSome **bold text and `code\n\n- item *one\n- item two\n\n```rust\nfn main() {\n```\n\n| a | b |\n|---|---|\n| 1 | 2\n\nSee [^1] and [link](http\n\n[^1]: note\n";
+
+fn assert_accessors_match(opts: Options, chunks: Vec<String>) {
+    let mut s = MdStream::new(opts);
+    for chunk in &chunks {
+        let pending = s.append(chunk).pending;
+        assert_eq!(s.pending_raw(), pending.as_ref().map(|p| p.raw.as_str()));
+        let display = pending.and_then(|p| p.display);
+        assert_eq!(s.pending_display(), display);
+        // Repeated calls return the cached display.
+        assert_eq!(s.pending_display(), display);
+    }
+    s.finalize();
+    assert_eq!(s.pending_raw(), None);
+    assert_eq!(s.pending_display(), None);
+}
+
+#[test]
+fn accessors_match_update_pending() {
+    assert_accessors_match(Options::default(), chunk_chars(DOC));
+    for trial in 0..4 {
+        let chunks = chunk_pseudo_random(DOC, "pending_accessors", trial, 9);
+        assert_accessors_match(Options::default(), chunks);
+    }
+}
+
+#[test]
+fn accessors_match_without_pending_display() {
+    let opts = Options {
+        compute_pending_display: false,
+        ..Options::default()
+    };
+    assert_accessors_match(opts, chunk_chars(DOC));
+}
+
+#[test]
+fn pending_display_without_a_preceding_update() {
+    let mut s = MdStream::new(Options::default());
+    assert_eq!(s.pending_raw(), None);
+    assert_eq!(s.pending_display(), None);
+    let _ = s.append_ref("Some *emph");
+    assert_eq!(s.pending_raw(), Some("Some *emph"));
+    assert_eq!(s.pending_display().as_deref(), Some("Some *emph*"));
+}
@@ -0,0 +1,87 @@
+use mdstream::{BlockKind, MdStream, Options};
+
+fn opts(max_lines: usize) -> Options {
+    Options {
+        pending_code_fence_display_max_lines: Some(max_lines),
+        ..Default::default()
+    }
+}
+
+fn body(lines: usize) -> String {
+    (1..=lines).map(|i| format!("line {i}\n")).collect()
+}
+
+#[test]
+fn long_pending_fence_display_shows_marker_and_tail() {
+    let mut s = MdStream::new(opts(5));
+    let text = format!("```rust\n{}", body(100));
+    let pending = s.append(&text).pending.expect("pending");
+    assert_eq!(pending.kind, BlockKind::CodeFence);
+    assert_eq!(pending.raw, text);
+
+    let display = pending.display.expect("display");
+    assert!(
+        display.starts_with("```rust\n\u{2026} 95 more lines \u{2026}\n"),
+        "{display:?}"
+    );
+    assert!(
+        display.contains("line 96\nline 97\nline 98\nline 99\nline 100\n"),
+        "{display:?}"
+    );
+    assert!(!display.contains("line 95\n"), "{display:?}");
+}
+
+#[test]
+fn truncation_follows_chunked_appends() {
+    let mut s = MdStream::new(opts(3));
+    s.append("```\n");
+    let mut last = None;
+    for i in 1..=10 {
+        last = s.append(&format!("line {i}\n")).pending;
+    }
+    let display = last.expect("pending").display.expect("display");
+    assert!(
+        display.starts_with("```\n\u{2026} 7 more lines \u{2026}\nline 8\nline 9\nline 10\n"),
+        "{display:?}"
+    );
+
+    // The partial last line counts as a line.
+    let display = s.append("line 1").pending.unwrap().display.unwrap();
+    assert!(
+        display.contains("\u{2026} 8 more lines \u{2026}\nline 9\nline 10\nline 1"),
+        "{display:?}"
+    );
+}
+
+#[test]
+fn short_fences_and_committed_blocks_are_untouched() {
+    let mut plain = MdStream::new(Options::default());
+    let mut s = MdStream::new(opts(5));
+    let text = format!("```\n{}", body(5));
+    assert_eq!(
+        s.append(&text).pending.unwrap().display,
+        plain.append(&text).pending.unwrap().display
+    );
+
+    let mut s = MdStream::new(Options {
+        commit_with_display: true,
+        ..opts(2)
+    });
+    let u = s.append(&format!("```\n{}```\n", body(10)));
+    assert_eq!(u.committed.len(), 1);
+    assert_eq!(u.committed[0].raw, format!("```\n{}```\n", body(10)));
+    assert!(
+        u.committed[0]
+            .display
+            .as_deref()
+            .is_none_or(|d| !d.contains('\u{2026}'))
+    );
+}
+
+#[test]
+fn other_pending_blocks_are_not_truncated() {
+    let mut s = MdStream::new(opts(1));
+    let pending = s.append("a\nb\nc\nd").pending.unwrap();
+    assert_eq!(pending.kind, BlockKind::Paragraph);
+    assert_eq!(pending.display.as_deref(), Some("a\nb\nc\nd"));
+}
@@ -10,6 +10,7 @@ fn parses_committed_blocks_and_pending_display() {
     let mut adapter = PulldownAdapter::new(PulldownAdapterOptions {
         pulldown: PulldownOptions::ENABLE_TABLES | PulldownOptions::ENABLE_STRIKETHROUGH,
         prefer_display_for_pending: true,
+        max_reference_definitions: None,
     });
 
     let u1 = stream.append("Hello\n\n**bold");
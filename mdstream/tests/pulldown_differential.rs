@@ -0,0 +1,206 @@
+#![cfg(feature = "pulldown")]
+
+mod support;
+
+use mdstream::{BlockKind, FootnotesMode, Options};
+use pulldown_cmark::{Event, Options as PulldownOptions, Parser, Tag};
+use support::{chunk_chars, chunk_whole, collect_final_blocks};
+
+fn pulldown_blocks(text: &str) -> Vec<(BlockKind, String)> {
+    let mut opts = PulldownOptions::empty();
+    opts.insert(PulldownOptions::ENABLE_TABLES);
+    opts.insert(PulldownOptions::ENABLE_STRIKETHROUGH);
+    opts.insert(PulldownOptions::ENABLE_TASKLISTS);
+    opts.insert(PulldownOptions::ENABLE_FOOTNOTES);
+    let mut out = Vec::new();
+    let mut depth = 0usize;
+    for (event, range) in Parser::new_ext(text, opts).into_offset_iter() {
+        match event {
+            Event::Start(tag) => {
+                if depth == 0 {
+                    let kind = match tag {
+                        Tag::Paragraph => BlockKind::Paragraph,
+                        Tag::Heading { .. } => BlockKind::Heading,
+                        Tag::CodeBlock(_) => BlockKind::CodeFence,
+                        Tag::List(_) => BlockKind::List,
+                        Tag::BlockQuote(_) => BlockKind::BlockQuote,
+                        Tag::Table(_) => BlockKind::Table,
+                        Tag::HtmlBlock => BlockKind::HtmlBlock,
+                        Tag::FootnoteDefinition(_) => BlockKind::FootnoteDefinition,
+                        _ => BlockKind::Unknown,
+                    };
+                    out.push((kind, text[range].to_string()));
+                }
+                depth += 1;
+            }
+            Event::End(_) => depth -= 1,
+            Event::Rule if depth == 0 => {
+                out.push((BlockKind::ThematicBreak, text[range].to_string()))
+            }
+            _ => {}
+        }
+    }
+    out
+}
+
+fn mdstream_blocks(text: &str) -> Vec<(BlockKind, String)> {
+    collect_final_blocks(chunk_whole(text), Options::default())
+}
+
+fn trimmed(blocks: Vec<(BlockKind, String)>) -> Vec<(BlockKind, String)> {
+    blocks
+        .into_iter()
+        .map(|(kind, raw)| (kind, raw.trim().to_string()))
+        .collect()
+}
+
+fn kinds(blocks: &[(BlockKind, String)]) -> Vec<BlockKind> {
+    blocks.iter().map(|(kind, _)| *kind).collect()
+}
+
+/// Documents whose top-level blocks (kind and trimmed text) match pulldown-cmark exactly.
+const ALIGNED: &[&str] = &[
+    include_str!("fixtures/streamdown_bench/basic_multiple_blocks_10.md"),
+    include_str!("fixtures/streamdown_bench/code_multiple_code_blocks.md"),
+    include_str!("fixtures/streamdown_bench/html_multiple_blocks.md"),
+    include_str!("fixtures/streamdown_bench/html_nested.md"),
+    include_str!("fixtures/streamdown_bench/table_simple.md"),
+    "para\n# h\npara\n",
+    "para\n####### seven\npara\n",
+    "para\n#tag\npara\n",
+    "para\n***\npara\n",
+    "para\n```\ncode\n```\npara\n",
+    "para\n> quote\npara\n",
+    "> quote\npara\n",
+    "- a\n- b\npara\n",
+    "- a\n\n- b\n\npara\n",
+    "- a\n\n  cont\n\npara\n",
+    "para\n- a\n",
+    "para\n1. a\n",
+    // Only ordered items starting at 1 interrupt a paragraph.
+    "para\n2. a\n",
+    "Title\n===\n\nSub\n---\n",
+    // Setext underlines vs thematic breaks.
+    "a\n--\nb\n",
+    "a\n=\nb\n",
+    "a\n  ---  \nb\n",
+    "a\n\n---\nb\n",
+    "a\n- - -\nb\n",
+    "a\n= =\nb\n",
+    "a\nb\n---\nc\n",
+    "a\nb\n===\nc\n",
+    "a\n---",
+    "para\n    lazy\n",
+    "<div>\nhtml\n</div>\n\npara\n",
+    "<!-- c -->\npara\n",
+    "| a | b |\n|---|---|\n| 1 | 2 |\npara\n",
+    "```\nunclosed\n",
+    "~~~\na\n~~~\n",
+    "# h1\n## h2\n",
+    "---\n---\n",
+    "> ```\n> code\n> ```\n",
+    "- [ ] task\n- [x] done\n",
+    "***\n- a\n",
+    "- a\n***\n- b\n",
+    "a  \nb\n",
+    "<pre>\n\ncode\n\n</pre>\n",
+    "  - a\n  - b\n",
+    "\n\n\npara\n\n\n",
+    // Fences nested in list items stay in the list.
+    "- a\n  ```\n  # not heading\n  - not item\n\n  code\n  ```\n- b\n\npara\n",
+    "- ```\n  code\n  ```\n\npara\n",
+    "1. a\n   ```\n   x\n   ```\n2. b\n",
+    "- a\n  ```\n  code\n```\npara\n",
+];
+
+#[test]
+fn top_level_blocks_match_pulldown() {
+    for doc in ALIGNED {
+        assert_eq!(
+            trimmed(mdstream_blocks(doc)),
+            trimmed(pulldown_blocks(doc)),
+            "{doc:?}"
+        );
+        // Streaming the same document must not change the split.
+        assert_eq!(
+            collect_final_blocks(chunk_chars(doc), Options::default()),
+            mdstream_blocks(doc),
+            "{doc:?}"
+        );
+    }
+}
+
+/// Known, intentional divergences: (document, mdstream kinds, pulldown kinds).
+///
+/// - Containers are grouped conservatively: lists switching marker type and blockquotes
+///   separated by a blank line stay one block, so a renderer never sees a container split early.
+/// - Indented code blocks are not detected; they read as paragraphs.
+/// - Reference definitions produce no pulldown events but stay a paragraph-like block in mdstream.
+/// - With the default `FootnotesMode::SingleBlock`, a document with footnotes is one `Unknown` block.
+/// - HTML blocks do not interrupt paragraphs (telling block tags from inline tags needs the whole
+///   CommonMark tag list).
+/// - A single `-` or `- ` under a paragraph is not a setext underline (it may still become a list
+///   marker while streaming).
+const DIVERGENT: &[(&str, &[BlockKind], &[BlockKind])] = &[
+    (
+        "1. a\n2. b\n\n- c\n",
+        &[BlockKind::List],
+        &[BlockKind::List, BlockKind::List],
+    ),
+    (
+        "- a\n* b\n",
+        &[BlockKind::List],
+        &[BlockKind::List, BlockKind::List],
+    ),
+    (
+        "> a\n>\n> b\n\n> c\n",
+        &[BlockKind::BlockQuote],
+        &[BlockKind::BlockQuote, BlockKind::BlockQuote],
+    ),
+    (
+        "    indented code\n\npara\n",
+        &[BlockKind::Paragraph, BlockKind::Paragraph],
+        &[BlockKind::CodeFence, BlockKind::Paragraph],
+    ),
+    (
+        "[ref]: https://example.com\n\npara [ref]\n",
+        &[BlockKind::Paragraph, BlockKind::Paragraph],
+        &[BlockKind::Paragraph],
+    ),
+    (
+        "para\n\n[^1]: note\n\nmore\n",
+        &[BlockKind::Unknown],
+        &[
+            BlockKind::Paragraph,
+            BlockKind::FootnoteDefinition,
+            BlockKind::Paragraph,
+        ],
+    ),
+    (
+        "text\n<div>\n",
+        &[BlockKind::Paragraph],
+        &[BlockKind::Paragraph, BlockKind::HtmlBlock],
+    ),
+    ("para\n-\n", &[BlockKind::Paragraph], &[BlockKind::Heading]),
+];
+
+#[test]
+fn documented_divergences_from_pulldown() {
+    for (doc, ours, theirs) in DIVERGENT {
+        assert_eq!(kinds(&mdstream_blocks(doc)), *ours, "{doc:?}");
+        assert_eq!(kinds(&pulldown_blocks(doc)), *theirs, "{doc:?}");
+    }
+}
+
+#[test]
+fn footnotes_align_in_invalidate_mode() {
+    let doc = "para\n\n[^1]: note\n    more\n\nafter\n";
+    let opts = Options {
+        footnotes: FootnotesMode::Invalidate,
+        ..Default::default()
+    };
+    assert_eq!(
+        trimmed(collect_final_blocks(chunk_whole(doc), opts)),
+        trimmed(pulldown_blocks(doc))
+    );
+}
@@ -43,3 +43,123 @@ fn pulldown_adapter_reparses_invalidated_blocks_with_reference_definitions() {
         "block should be re-parsed with definitions"
     );
 }
+
+fn link_dest(events: &[Event<'static>]) -> Option<String> {
+    events.iter().find_map(|e| match e {
+        Event::Start(Tag::Link { dest_url, .. }) => Some(dest_url.to_string()),
+        _ => None,
+    })
+}
+
+fn pending(raw: &str) -> mdstream::Block {
+    let mut s = MdStream::new(Options::default());
+    s.append(raw).pending.expect("pending")
+}
+
+#[test]
+fn forget_block_drops_its_reference_definitions() {
+    let mut s = MdStream::new(Options::default());
+    let mut a = PulldownAdapter::new(PulldownAdapterOptions::default());
+
+    let mut u = s.append("[old]: https://old.example\n\n[ref]: https://a.example\n\n");
+    u.merge(s.append("[ref]: https://b.example\n\nNext\n"));
+    a.apply_update(&u);
+    let ids: Vec<_> = u.committed.iter().map(|b| b.id).collect();
+    assert_eq!(ids.len(), 3);
+    assert_eq!(a.reference_definition_count(), 2);
+    let probe = pending("See [ref] and [old]");
+    assert_eq!(
+        link_dest(&a.parse_pending(&probe)).as_deref(),
+        Some("https://b.example")
+    );
+
+    // Forgetting the latest definition falls back to the earlier one.
+    assert!(a.forget_block(ids[2]));
+    assert!(a.committed_events(ids[2]).is_none());
+    assert_eq!(
+        link_dest(&a.parse_pending(&pending("See [ref]"))).as_deref(),
+        Some("https://a.example")
+    );
+
+    a.forget_block(ids[1]);
+    assert_eq!(a.reference_definition_count(), 1);
+    assert!(!contains_link(&a.parse_pending(&pending("See [ref]"))));
+    assert!(contains_link(&a.parse_pending(&pending("See [old]"))));
+
+    a.forget_block(ids[0]);
+    assert_eq!(a.reference_definition_count(), 0);
+    assert!(!a.forget_block(ids[0]));
+}
+
+#[test]
+fn max_reference_definitions_evicts_oldest() {
+    let mut s = MdStream::new(Options::default());
+    let mut a = PulldownAdapter::new(PulldownAdapterOptions {
+        max_reference_definitions: Some(2),
+        ..Default::default()
+    });
+    let mut u = s.append("[a]: https://a.example\n\n[b]: https://b.example\n\n");
+    u.merge(s.append("[c]: https://c.example\n\nNext\n"));
+    a.apply_update(&u);
+    assert_eq!(a.reference_definition_count(), 2);
+    assert!(!contains_link(&a.parse_pending(&pending("See [a]"))));
+    assert!(contains_link(&a.parse_pending(&pending("See [b]"))));
+    assert!(contains_link(&a.parse_pending(&pending("See [c]"))));
+}
+
+/// `(usage label, definition label, should match)`.
+const LABEL_CORPUS: &[(&str, &str, bool)] = &[
+    ("Foo Bar", "foo bar", true),
+    ("foo   bar", "FOO bar", true),
+    ("foo\tbar", "foo bar", true),
+    (" padded ", "padded", true),
+    ("ΑΓΩ", "αγω", true),
+    ("Straße", "STRASSE", true),
+    ("ﬁle", "FILE", true),
+    ("Ünïcödé", "üNÏCÖDÉ", true),
+    ("foo bar", "foobar", false),
+    ("café", "cafe", false),
+];
+
+#[test]
+fn stream_invalidation_and_adapter_definitions_agree_on_labels() {
+    for &(usage, def, matches) in LABEL_CORPUS {
+        let opts = Options {
+            reference_definitions: ReferenceDefinitionsMode::Invalidate,
+            ..Default::default()
+        };
+        let mut s = MdStream::new(opts);
+        let mut a = PulldownAdapter::new(PulldownAdapterOptions::default());
+        let mut u = s.append(&format!("See [{usage}].\n\n"));
+        u.merge(s.append(&format!("[{def}]: https://example.com\n\n")));
+        u.merge(s.append("Next\n"));
+        a.apply_update(&u);
+
+        let usage_id = u.committed[0].id;
+        let events = a.committed_events(usage_id).expect("events");
+        assert_eq!(
+            u.invalidated.contains(&usage_id),
+            matches,
+            "stream invalidation for {usage:?} / {def:?}"
+        );
+        assert_eq!(
+            contains_link(events),
+            matches,
+            "adapter resolution for {usage:?} / {def:?}"
+        );
+    }
+}
+
+#[test]
+fn adapter_keys_definitions_by_normalized_label() {
+    let mut s = MdStream::new(Options::default());
+    let mut a = PulldownAdapter::new(PulldownAdapterOptions::default());
+    let u = s
+        .append("[Straße  Eins]: https://a.example\n\n[STRASSE eins]: https://b.example\n\nNext\n");
+    a.apply_update(&u);
+    assert_eq!(a.reference_definition_count(), 1);
+    assert_eq!(
+        link_dest(&a.parse_pending(&pending("See [strasse eins]"))).as_deref(),
+        Some("https://b.example")
+    );
+}
@@ -1,3 +1,5 @@
+#![cfg(feature = "std")]
+
 use mdstream::FootnotesMode;
 use mdstream::{MdStream, Options, ReferenceDefinitionsMode};
 
@@ -62,6 +64,26 @@ fn invalidation_normalizes_reference_labels() {
     );
 }
 
+#[test]
+fn invalidation_case_folds_non_ascii_labels() {
+    let opts = Options {
+        reference_definitions: ReferenceDefinitionsMode::Invalidate,
+        ..Default::default()
+    };
+
+    for (usage, definition) in [("STRASSE", "Straße"), ("ΣΟΦΟΣ", "σοφος"), ("ﬁle", "FILE")]
+    {
+        let mut s = MdStream::new(opts.clone());
+        s.append(&format!("See [{usage}].\n\n"));
+        let u = s.append(&format!("[{definition}]: https://example.com\n\nNext\n"));
+        assert_eq!(
+            u.invalidated,
+            vec![mdstream::BlockId(1)],
+            "usage={usage} definition={definition}"
+        );
+    }
+}
+
 #[test]
 fn footnote_definitions_do_not_trigger_reference_invalidations() {
     // Avoid SingleBlock footnote transitions affecting this test.
@@ -95,3 +117,129 @@ fn reference_definitions_inside_code_fences_do_not_trigger_invalidations() {
     let u = s.append("```text\n[ref]: https://example.com\n```\n\nNext\n");
     assert!(u.invalidated.is_empty());
 }
+
+#[test]
+fn max_reference_labels_evicts_oldest_labels() {
+    let opts = Options {
+        reference_definitions: ReferenceDefinitionsMode::Invalidate,
+        max_reference_labels: Some(2),
+        ..Default::default()
+    };
+    let mut s = MdStream::new(opts);
+
+    let mut committed = 0usize;
+    // Many distinct labels; only the two most recent stay indexed.
+    for i in 0..100 {
+        committed += s.append(&format!("See [label{i}].\n\n")).committed.len();
+    }
+    // Definition for an evicted label: parsing continues, but no invalidation is emitted.
+    committed += s.append("[label0]: https://a.example\n\n").committed.len();
+    let u = s.append("Next\n\n");
+    committed += u.committed.len();
+    assert!(u.invalidated.is_empty());
+
+    // Definition for a label that is still indexed invalidates its block.
+    committed += s.append("[label99]: https://b.example\n\n").committed.len();
+    let u = s.append("Tail\n");
+    committed += u.committed.len();
+    assert_eq!(u.invalidated, vec![mdstream::BlockId(100)]);
+
+    committed += s.finalize().committed.len();
+    assert_eq!(committed, 104);
+}
+
+#[test]
+fn reemit_invalidated_copies_blocks_using_a_late_definition() {
+    let opts = Options {
+        reference_definitions: ReferenceDefinitionsMode::Invalidate,
+        reemit_invalidated: true,
+        ..Default::default()
+    };
+    let mut s = MdStream::new(opts);
+    let mut state = mdstream::DocumentState::new();
+
+    state.apply(s.append("See [ref].\n\nPlain.\n\n"));
+    let u = s.append("[ref]: https://example.com\n\n");
+    assert!(u.reemitted.is_empty());
+    state.apply(u);
+
+    let u = s.append("Next\n");
+    assert_eq!(u.invalidated, vec![mdstream::BlockId(1)]);
+    assert_eq!(u.reemitted.len(), 1);
+    assert_eq!(u.reemitted[0].id, mdstream::BlockId(1));
+    assert_eq!(u.reemitted[0].raw, "See [ref].\n\n");
+    assert_eq!(u.reemitted[0].status, mdstream::BlockStatus::Committed);
+    state.apply(u);
+    state.apply(s.finalize());
+    assert_eq!(state.committed().len(), 4);
+    assert_eq!(state.committed()[0].raw, "See [ref].\n\n");
+}
+
+#[test]
+fn reemitted_blocks_are_off_by_default_and_merge_by_id() {
+    let opts = Options {
+        reference_definitions: ReferenceDefinitionsMode::Invalidate,
+        ..Default::default()
+    };
+    let mut s = MdStream::new(opts);
+    s.append("See [ref].\n\n[ref]: https://example.com\n\n");
+    let u = s.append("Next\n");
+    assert_eq!(u.invalidated, vec![mdstream::BlockId(1)]);
+    assert!(u.reemitted.is_empty());
+
+    let opts = Options {
+        reference_definitions: ReferenceDefinitionsMode::Invalidate,
+        reemit_invalidated: true,
+        ..Default::default()
+    };
+    let mut s = MdStream::new(opts);
+    let mut merged = s.append("See [a] and [b].\n\n[a]: https://a.example\n\n");
+    merged.merge(s.append("[b]: https://b.example\n\n"));
+    merged.merge(s.append("Next\n"));
+    assert_eq!(merged.invalidated, vec![mdstream::BlockId(1)]);
+    assert_eq!(merged.reemitted.len(), 1);
+}
+
+#[test]
+fn invalidation_max_lookback_skips_old_users() {
+    let opts = Options {
+        reference_definitions: ReferenceDefinitionsMode::Invalidate,
+        invalidation_max_lookback: Some(10),
+        ..Default::default()
+    };
+    let mut s = MdStream::new(opts);
+
+    // Every fifth of 50 paragraphs uses the label (ids 1, 6, ..., 46).
+    for i in 0..50 {
+        let text = if i % 5 == 0 {
+            format!("Uses [shared] {i}.\n\n")
+        } else {
+            format!("Plain {i}.\n\n")
+        };
+        s.append(&text);
+    }
+    s.append("[shared]: https://example.com\n\n");
+    let u = s.append("Tail\n");
+    assert_eq!(u.committed.len(), 1);
+
+    // When the definition commits, the ten most recent committed blocks are ids 41..=50; the
+    // older users (ids 1..=36) are not invalidated.
+    assert_eq!(
+        u.invalidated,
+        vec![mdstream::BlockId(41), mdstream::BlockId(46)]
+    );
+}
+
+#[test]
+fn invalidation_max_lookback_zero_disables_invalidation() {
+    let opts = Options {
+        reference_definitions: ReferenceDefinitionsMode::Invalidate,
+        invalidation_max_lookback: Some(0),
+        ..Default::default()
+    };
+    let mut s = MdStream::new(opts);
+    s.append("See [ref].\n\n[ref]: https://example.com\n\n");
+    let u = s.append("Next\n");
+    assert_eq!(u.committed.len(), 1);
+    assert!(u.invalidated.is_empty());
+}
@@ -0,0 +1,18 @@
+#![cfg(not(feature = "std"))]
+
+use mdstream::{MdStream, Options, ReferenceDefinitionsMode};
+
+#[test]
+fn invalidate_mode_reports_nothing_without_std() {
+    let opts = Options {
+        reference_definitions: ReferenceDefinitionsMode::Invalidate,
+        ..Default::default()
+    };
+    let mut s = MdStream::new(opts);
+    s.append("See [ref].\n\n");
+    s.append("[ref]: https://example.com\n");
+    s.append("\n");
+    let u = s.append("Next\n");
+    assert!(!u.committed.is_empty());
+    assert!(u.invalidated.is_empty());
+}
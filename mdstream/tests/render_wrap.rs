@@ -0,0 +1,56 @@
+#![cfg(feature = "render")]
+
+use mdstream::BlockKind;
+use mdstream::render::{display_width, wrap_block_display, wrap_line};
+
+#[test]
+fn prose_wraps_at_width() {
+    assert_eq!(
+        wrap_block_display(BlockKind::Paragraph, "abcdefgh\n\nij\n", 3),
+        vec!["abc", "def", "gh", "", "ij"]
+    );
+}
+
+#[test]
+fn wide_cjk_chars_count_two_columns() {
+    let lines = wrap_block_display(BlockKind::Paragraph, "你好世界abc", 5);
+    assert_eq!(lines, vec!["你好", "世界a", "bc"]);
+    assert!(lines.iter().all(|l| display_width(l) <= 5));
+
+    // A wide char never gets split, even when it is wider than the remaining room.
+    assert_eq!(wrap_line("a你", 2), vec!["a", "你"]);
+    assert_eq!(wrap_line("你", 1), vec!["你"]);
+}
+
+#[test]
+fn zero_width_chars_stay_with_their_base() {
+    // `e` + combining acute accent, and a ZWJ family emoji.
+    assert_eq!(wrap_line("abe\u{301}c", 3), vec!["abe\u{301}", "c"]);
+    let family = "\u{1f468}\u{200d}\u{1f469}\u{200d}\u{1f467}";
+    assert_eq!(
+        wrap_line(&format!("a{family}b"), 3),
+        vec![format!("a{family}"), "b".to_string()]
+    );
+}
+
+#[test]
+fn code_and_tables_are_not_wrapped() {
+    let code = "```rust\nlet a_very_long_identifier = 1;\n```\n";
+    assert_eq!(
+        wrap_block_display(BlockKind::CodeFence, code, 10),
+        vec!["```rust", "let a_very_long_identifier = 1;", "```"]
+    );
+    let table = "| column a | column b |\n|---|---|\n";
+    assert_eq!(
+        wrap_block_display(BlockKind::Table, table, 5),
+        vec!["| column a | column b |", "|---|---|"]
+    );
+}
+
+#[test]
+fn zero_width_disables_wrapping() {
+    assert_eq!(
+        wrap_block_display(BlockKind::Paragraph, "abcdef", 0),
+        vec!["abcdef"]
+    );
+}
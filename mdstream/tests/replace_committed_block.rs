@@ -0,0 +1,76 @@
+use mdstream::{BlockId, BlockKind, DocumentState, MdStream, Options, ReplaceError};
+
+#[test]
+fn valid_edit_replaces_the_block_in_place() {
+    let mut s = MdStream::new(Options::default());
+    let mut doc = DocumentState::new();
+    doc.apply(s.append("First para\n\nSecond para\n\nTail"));
+    let id = doc.committed()[0].id;
+
+    let u = s
+        .replace_committed_block(id, "# Edited title\n")
+        .expect("single block edit");
+    assert_eq!(u.invalidated, vec![id]);
+    assert!(u.committed.is_empty());
+    assert_eq!(u.pending.as_ref().map(|b| b.raw.as_str()), Some("Tail"));
+
+    let edited = s.committed_block_by_id(id).unwrap();
+    assert_eq!(edited.kind, BlockKind::Heading);
+    assert_eq!(edited.raw, "# Edited title\n");
+
+    doc.apply(u);
+    let raws: Vec<&str> = doc.committed().iter().map(|b| b.raw.as_str()).collect();
+    assert_eq!(raws, vec!["# Edited title\n", "Second para\n\n"]);
+
+    // Streaming continues normally after the edit.
+    let u = s.append("\n\nMore\n");
+    assert_eq!(u.committed[0].raw, "Tail\n\n");
+}
+
+#[test]
+fn multi_block_edit_is_rejected() {
+    let mut s = MdStream::new(Options::default());
+    s.append("Para\n\nNext\n\n");
+    let before = s.committed_block_by_id(BlockId(1)).cloned();
+
+    let err = s
+        .replace_committed_block(BlockId(1), "One\n\nTwo\n")
+        .unwrap_err();
+    assert_eq!(err, ReplaceError::NotSingleBlock { blocks: 2 });
+    assert_eq!(
+        s.replace_committed_block(BlockId(1), "  \n"),
+        Err(ReplaceError::NotSingleBlock { blocks: 0 })
+    );
+    assert_eq!(s.committed_block_by_id(BlockId(1)).cloned(), before);
+
+    assert_eq!(
+        s.replace_committed_block(BlockId(99), "x\n"),
+        Err(ReplaceError::UnknownBlock(BlockId(99)))
+    );
+}
+
+#[cfg(feature = "std")]
+#[test]
+fn edits_update_reference_invalidation() {
+    use mdstream::ReferenceDefinitionsMode;
+
+    let opts = Options {
+        reference_definitions: ReferenceDefinitionsMode::Invalidate,
+        ..Default::default()
+    };
+    let mut s = MdStream::new(opts);
+    s.append("See [a].\n\nSee [b].\n\n[a]: https://a.example\n\nTail\n");
+    // Blocks: 1 uses `a`, 2 uses `b`, 3 defines `a`.
+
+    // Redefining the block as `b` invalidates users of the old and the new label.
+    let u = s
+        .replace_committed_block(BlockId(3), "[b]: https://b.example\n\n")
+        .unwrap();
+    assert_eq!(u.invalidated, vec![BlockId(3), BlockId(1), BlockId(2)]);
+
+    // Block 1 no longer uses `a`: a later definition of `a` leaves it alone.
+    s.replace_committed_block(BlockId(1), "No refs here.\n\n")
+        .unwrap();
+    let u = s.append("\n[a]: https://a2.example\n\nEnd\n");
+    assert!(u.invalidated.is_empty(), "{:?}", u.invalidated);
+}
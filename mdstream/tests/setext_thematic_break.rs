@@ -0,0 +1,94 @@
+mod support;
+
+use mdstream::{BlockKind, MdStream, Options};
+
+use BlockKind::{Heading, List, Paragraph, ThematicBreak};
+
+/// Setext underline vs thematic break vs list marker, and the blocks they must produce.
+const CASES: &[(&str, &[(BlockKind, &str)])] = &[
+    // A single `-` may still be a list marker, so it never makes a setext heading.
+    ("a\n-", &[(Paragraph, "a\n-")]),
+    ("a\n-\n", &[(Paragraph, "a\n-\n")]),
+    ("a\n-\nb", &[(Paragraph, "a\n-\nb")]),
+    ("a\n--", &[(Heading, "a\n--")]),
+    ("a\n---", &[(Heading, "a\n---")]),
+    ("a\n---\nb\n", &[(Heading, "a\n---\n"), (Paragraph, "b\n")]),
+    ("a\n=\nb\n", &[(Heading, "a\n=\n"), (Paragraph, "b\n")]),
+    (
+        "a\n  ---  \nb\n",
+        &[(Heading, "a\n  ---  \n"), (Paragraph, "b\n")],
+    ),
+    (
+        "a\nb\n---\nc\n",
+        &[(Heading, "a\nb\n---\n"), (Paragraph, "c\n")],
+    ),
+    // A blank line in between makes it a thematic break.
+    ("a\n\n---", &[(Paragraph, "a\n\n"), (ThematicBreak, "---")]),
+    (
+        "a\n\n---\nb\n",
+        &[
+            (Paragraph, "a\n\n"),
+            (ThematicBreak, "---\n"),
+            (Paragraph, "b\n"),
+        ],
+    ),
+    // Internal spaces: not an underline, but a thematic break that interrupts the paragraph.
+    (
+        "a\n- - -\nb\n",
+        &[
+            (Paragraph, "a\n"),
+            (ThematicBreak, "- - -\n"),
+            (Paragraph, "b\n"),
+        ],
+    ),
+    ("a\n= =\nb\n", &[(Paragraph, "a\n= =\nb\n")]),
+    ("a\n- b\n", &[(Paragraph, "a\n"), (List, "- b\n")]),
+];
+
+#[test]
+fn setext_and_thematic_breaks_are_chunking_invariant() {
+    for (input, expected) in CASES {
+        let expected: Vec<(BlockKind, String)> =
+            expected.iter().map(|(k, r)| (*k, r.to_string())).collect();
+        let mut strategies = vec![
+            support::chunk_whole(input),
+            support::chunk_lines(input),
+            support::chunk_chars(input),
+        ];
+        for trial in 0..8 {
+            strategies.push(support::chunk_pseudo_random(input, input, trial, 3));
+        }
+        for chunks in strategies {
+            assert_eq!(
+                support::collect_final_blocks(chunks.clone(), Options::default()),
+                expected,
+                "input {input:?}, chunks {chunks:?}"
+            );
+        }
+    }
+}
+
+#[test]
+fn pending_kind_agrees_with_terminator_setext_protection() {
+    // (streamed text, pending kind, pending display)
+    let cases = [
+        ("a\n-", Paragraph, "a\n-\u{200B}"),
+        ("a\n--", Paragraph, "a\n--\u{200B}"),
+        ("a\n---", Heading, "a\n---"),
+        ("a\n==", Paragraph, "a\n==\u{200B}"),
+        ("a\n===", Heading, "a\n==="),
+        ("a\nb\n----", Heading, "a\nb\n----"),
+        ("a\n---x", Paragraph, "a\n---x"),
+    ];
+    for (text, kind, display) in cases {
+        let mut s = MdStream::new(Options::default());
+        let mut pending = None;
+        for c in text.chars() {
+            pending = s.append(&c.to_string()).pending;
+        }
+        let pending = pending.expect("pending");
+        assert_eq!(pending.raw, text);
+        assert_eq!(pending.kind, kind, "{text:?}");
+        assert_eq!(pending.display.as_deref(), Some(display), "{text:?}");
+    }
+}
@@ -128,3 +128,221 @@ fn blockquote_lazy_continuation_is_stable_chunking_invariance() {
     );
     assert_eq!(blocks_whole[1].1, "After\n");
 }
+
+#[test]
+fn blockquote_with_embedded_code_fence_commits_as_one_block_chunking_invariance() {
+    let markdown =
+        "> Example:\n> ```md\n> # not a heading\n>\n> - not a list\n> ```\n> - item\n\nAfter\n";
+
+    let opts = Options::default();
+    let blocks_whole = support::collect_final_blocks(support::chunk_whole(markdown), opts.clone());
+    let blocks_chars = support::collect_final_blocks(support::chunk_chars(markdown), opts.clone());
+    let blocks_rand = support::collect_final_blocks(
+        support::chunk_pseudo_random(markdown, "blockquote_embedded_code_fence", 0, 8),
+        opts.clone(),
+    );
+
+    assert_eq!(blocks_chars, blocks_whole);
+    assert_eq!(blocks_rand, blocks_whole);
+
+    assert_eq!(blocks_whole.len(), 2);
+    assert_eq!(blocks_whole[0].0, BlockKind::BlockQuote);
+    assert_eq!(
+        blocks_whole[0].1,
+        "> Example:\n> ```md\n> # not a heading\n>\n> - not a list\n> ```\n> - item\n\n"
+    );
+    assert_eq!(blocks_whole[1].1, "After\n");
+}
+
+#[test]
+fn unquoted_line_ends_blockquote_with_open_code_fence_chunking_invariance() {
+    let markdown = "> ```\n> code\nnot code\n";
+
+    let opts = Options::default();
+    let blocks_whole = support::collect_final_blocks(support::chunk_whole(markdown), opts.clone());
+    let blocks_chars = support::collect_final_blocks(support::chunk_chars(markdown), opts.clone());
+
+    assert_eq!(blocks_chars, blocks_whole);
+    assert_eq!(
+        blocks_whole,
+        vec![
+            (BlockKind::BlockQuote, "> ```\n> code\n".to_string()),
+            (BlockKind::Paragraph, "not code\n".to_string()),
+        ]
+    );
+}
+
+#[test]
+fn unquoted_blank_line_ends_blockquote_with_open_code_fence_chunking_invariance() {
+    let markdown = "> ```\n> code\n\n> more\n> ```\n";
+
+    let opts = Options::default();
+    let blocks_whole = support::collect_final_blocks(support::chunk_whole(markdown), opts.clone());
+    let blocks_lines = support::collect_final_blocks(support::chunk_lines(markdown), opts.clone());
+    let blocks_chars = support::collect_final_blocks(support::chunk_chars(markdown), opts.clone());
+
+    assert_eq!(blocks_lines, blocks_whole);
+    assert_eq!(blocks_chars, blocks_whole);
+    assert_eq!(
+        blocks_whole,
+        vec![
+            (BlockKind::BlockQuote, "> ```\n> code\n\n".to_string()),
+            (BlockKind::BlockQuote, "> more\n> ```\n".to_string()),
+        ]
+    );
+}
+
+#[test]
+fn list_ends_after_two_blank_lines_chunking_invariance() {
+    let markdown = "- a\n\n\n- b";
+
+    let opts = Options::default();
+    let blocks_whole = support::collect_final_blocks(support::chunk_whole(markdown), opts.clone());
+    let blocks_lines = support::collect_final_blocks(support::chunk_lines(markdown), opts.clone());
+    let blocks_chars = support::collect_final_blocks(support::chunk_chars(markdown), opts.clone());
+    let blocks_rand = support::collect_final_blocks(
+        support::chunk_pseudo_random(markdown, "list_two_blank_lines", 0, 40),
+        opts.clone(),
+    );
+
+    assert_eq!(blocks_lines, blocks_whole);
+    assert_eq!(blocks_chars, blocks_whole);
+    assert_eq!(blocks_rand, blocks_whole);
+
+    assert_eq!(
+        blocks_whole,
+        vec![
+            (BlockKind::List, "- a\n\n\n".to_string()),
+            (BlockKind::List, "- b".to_string()),
+        ]
+    );
+}
+
+#[test]
+fn list_blank_line_limit_keeps_indented_continuation_and_is_configurable() {
+    let markdown = "- a\n\n\n  still a\n\n\n- b\n";
+    let blocks = support::collect_final_raw(support::chunk_chars(markdown), Options::default());
+    assert_eq!(blocks, vec!["- a\n\n\n  still a\n\n\n", "- b\n"]);
+
+    let opts = Options {
+        list_blank_line_limit: 2,
+        ..Default::default()
+    };
+    let blocks = support::collect_final_raw(support::chunk_lines("- a\n\n\n- b\n"), opts);
+    assert_eq!(blocks, vec!["- a\n\n\n- b\n"]);
+}
+
+#[test]
+fn blockquote_ends_after_two_blank_lines() {
+    let markdown = "> a\n\n> b\n\n\n> c\n";
+    let blocks = support::collect_final_raw(support::chunk_chars(markdown), Options::default());
+    assert_eq!(blocks, vec!["> a\n\n> b\n\n\n", "> c\n"]);
+}
+
+#[test]
+fn unterminated_last_line_kind_is_chunking_invariant() {
+    let markdown = "a\n\n- b";
+    let opts = Options::default();
+    let blocks_whole = support::collect_final_blocks(support::chunk_whole(markdown), opts.clone());
+    let blocks_chars = support::collect_final_blocks(support::chunk_chars(markdown), opts);
+    assert_eq!(blocks_chars, blocks_whole);
+    assert_eq!(blocks_whole[1], (BlockKind::List, "- b".to_string()));
+}
+
+fn final_blocks_all_chunkings(markdown: &str, opts: Options) -> Vec<(BlockKind, String)> {
+    let blocks_whole = support::collect_final_blocks(support::chunk_whole(markdown), opts.clone());
+    let blocks_lines = support::collect_final_blocks(support::chunk_lines(markdown), opts.clone());
+    let blocks_chars = support::collect_final_blocks(support::chunk_chars(markdown), opts.clone());
+    let blocks_rand = support::collect_final_blocks(
+        support::chunk_pseudo_random(markdown, "list_continuation_min_indent", 0, 8),
+        opts,
+    );
+    assert_eq!(blocks_lines, blocks_whole);
+    assert_eq!(blocks_chars, blocks_whole);
+    assert_eq!(blocks_rand, blocks_whole);
+    blocks_whole
+}
+
+#[test]
+fn list_continuation_min_indent_chunking_invariance() {
+    let two = "- item\n\n  two-space para\n";
+    let four = "- item\n\n    four-space para\n";
+    let threshold = |n| Options {
+        list_continuation_min_indent: n,
+        ..Default::default()
+    };
+
+    // Default threshold (2): both indented paragraphs continue the item.
+    assert_eq!(
+        final_blocks_all_chunkings(two, threshold(2)),
+        vec![(BlockKind::List, two.to_string())]
+    );
+    assert_eq!(
+        final_blocks_all_chunkings(four, threshold(2)),
+        vec![(BlockKind::List, four.to_string())]
+    );
+
+    // Threshold 4: the 2-space paragraph is a separate block.
+    assert_eq!(
+        final_blocks_all_chunkings(two, threshold(4)),
+        vec![
+            (BlockKind::List, "- item\n\n".to_string()),
+            (BlockKind::Paragraph, "  two-space para\n".to_string()),
+        ]
+    );
+    assert_eq!(
+        final_blocks_all_chunkings(four, threshold(4)),
+        vec![(BlockKind::List, four.to_string())]
+    );
+}
+
+#[test]
+fn blockquote_continuation_ignores_list_indent_threshold() {
+    let markdown = "> quote\n\n    indented\n";
+    let opts = Options {
+        list_continuation_min_indent: 4,
+        ..Default::default()
+    };
+    let blocks = final_blocks_all_chunkings(markdown, opts);
+    assert_eq!(blocks.len(), 2);
+    assert_eq!(
+        blocks[0],
+        (BlockKind::BlockQuote, "> quote\n\n".to_string())
+    );
+}
+
+#[test]
+fn list_with_embedded_code_fence_commits_as_one_block_chunking_invariance() {
+    let markdown =
+        "- Example:\n  ```md\n  # not a heading\n\n  - not a list\n  ```\n- item\n\nAfter\n";
+    let blocks = final_blocks_all_chunkings(markdown, Options::default());
+    assert_eq!(
+        blocks,
+        vec![
+            (
+                BlockKind::List,
+                "- Example:\n  ```md\n  # not a heading\n\n  - not a list\n  ```\n- item\n\n"
+                    .to_string()
+            ),
+            (BlockKind::Paragraph, "After\n".to_string()),
+        ]
+    );
+}
+
+#[test]
+fn only_ordered_items_starting_at_one_interrupt_a_paragraph() {
+    let blocks = final_blocks_all_chunkings("Year\n2024. was long\n", Options::default());
+    assert_eq!(
+        blocks,
+        vec![(BlockKind::Paragraph, "Year\n2024. was long\n".to_string())]
+    );
+
+    let blocks = final_blocks_all_chunkings("Steps:\n1. first\n", Options::default());
+    assert_eq!(
+        blocks,
+        vec![
+            (BlockKind::Paragraph, "Steps:\n".to_string()),
+            (BlockKind::List, "1. first\n".to_string()),
+        ]
+    );
+}
@@ -97,3 +97,61 @@ fn streamdown_benchmark_multiple_html_blocks_chunking_invariance() {
     assert!(blocks_whole[2].1.contains("</section>"));
     assert!(blocks_whole[3].1.contains("More markdown"));
 }
+
+#[test]
+fn trailing_text_after_closed_tags_keeps_html_block_until_blank_line() {
+    let markdown = "<div>x</div> trailing text\nnext line\n# not a heading\n\nAfter\n";
+    let expected = vec![
+        (
+            BlockKind::HtmlBlock,
+            "<div>x</div> trailing text\nnext line\n# not a heading\n\n".to_string(),
+        ),
+        (BlockKind::Paragraph, "After\n".to_string()),
+    ];
+    for chunks in [
+        support::chunk_whole(markdown),
+        support::chunk_lines(markdown),
+        support::chunk_chars(markdown),
+    ] {
+        assert_eq!(
+            support::collect_final_blocks(chunks, Options::default()),
+            expected
+        );
+    }
+
+    // A multi-line block whose closing line has trailing text behaves the same.
+    let blocks = support::collect_final_blocks(
+        support::chunk_whole("<div>\nx\n</div> tail\nmore\n\nAfter\n"),
+        Options::default(),
+    );
+    assert_eq!(
+        blocks,
+        vec![
+            (
+                BlockKind::HtmlBlock,
+                "<div>\nx\n</div> tail\nmore\n\n".to_string()
+            ),
+            (BlockKind::Paragraph, "After\n".to_string()),
+        ]
+    );
+}
+
+#[test]
+fn html_block_ending_at_closing_tag_still_commits_on_that_line() {
+    let mut s = mdstream::MdStream::new(Options::default());
+    let u = s.append("<div>x</div>");
+    assert!(u.committed.is_empty());
+    // The rest of the line decides whether the block ends here.
+    let u = s.append(" more\nnext");
+    assert!(u.committed.is_empty());
+    assert_eq!(
+        u.pending.map(|b| (b.kind, b.raw)),
+        Some((BlockKind::HtmlBlock, "<div>x</div> more\nnext".to_string()))
+    );
+
+    let mut s = mdstream::MdStream::new(Options::default());
+    let u = s.append("<div>x</div>  \nnext");
+    assert_eq!(u.committed.len(), 1);
+    assert_eq!(u.committed[0].raw, "<div>x</div>  \n");
+    assert_eq!(u.pending.map(|b| b.raw), Some("next".to_string()));
+}
@@ -57,3 +57,18 @@ fn streamdown_benchmark_large_table_chunking_invariance() {
             .contains("| C991 | C992 | C993 | C994 | C995 |")
     );
 }
+
+#[test]
+fn pipe_in_code_span_or_escaped_is_not_a_table() {
+    let opts = Options::default();
+    for markdown in ["Call `f(a|b)` now\n:-:\n", "Either a \\| b\n:-:\n"] {
+        let blocks = support::collect_final_blocks(support::chunk_chars(markdown), opts.clone());
+        assert_eq!(blocks.len(), 1, "{markdown:?}");
+        assert_eq!(blocks[0].0, BlockKind::Paragraph, "{markdown:?}");
+    }
+
+    let markdown = "| `a|b` | c |\n|:-|-:|\n| 1 | 2 |\n";
+    let blocks = support::collect_final_blocks(support::chunk_chars(markdown), opts);
+    assert_eq!(blocks.len(), 1);
+    assert_eq!(blocks[0].0, BlockKind::Table);
+}
@@ -0,0 +1,65 @@
+use mdstream::{BlockKind, MdStream, Options};
+
+#[test]
+fn header_and_delimiter_are_a_pending_table() {
+    let mut s = MdStream::new(Options::default());
+    let u = s.append("| A | B |\n|---|---|\n");
+    assert!(u.committed.is_empty());
+    let pending = u.pending.expect("pending table");
+    assert_eq!(pending.kind, BlockKind::Table);
+    assert_eq!(pending.raw, "| A | B |\n|---|---|\n");
+    let id = pending.id;
+
+    // Data rows stay in the same block.
+    for row in ["| 1 | 2 |\n", "| 3 ", "| 4 |\n"] {
+        let u = s.append(row);
+        assert!(u.committed.is_empty());
+        let pending = u.pending.expect("pending table");
+        assert_eq!((pending.kind, pending.id), (BlockKind::Table, id));
+    }
+
+    let u = s.finalize();
+    assert_eq!(u.committed.len(), 1);
+    assert_eq!(u.committed[0].id, id);
+    assert_eq!(u.committed[0].kind, BlockKind::Table);
+    assert_eq!(
+        u.committed[0].raw,
+        "| A | B |\n|---|---|\n| 1 | 2 |\n| 3 | 4 |\n"
+    );
+}
+
+#[test]
+fn incomplete_delimiter_row_already_reports_a_table() {
+    let text = "| A | B |\n|---|---|\n";
+    let mut s = MdStream::new(Options::default());
+    let mut kinds = Vec::new();
+    for ch in text.chars() {
+        let u = s.append(&ch.to_string());
+        kinds.push(u.pending.map(|p| p.kind));
+    }
+    // Paragraph through the header row and the lone `|` of the delimiter row, a table from the
+    // first `-` on.
+    let header_len = "| A | B |\n|".len();
+    assert!(
+        kinds[..header_len]
+            .iter()
+            .all(|k| *k == Some(BlockKind::Paragraph))
+    );
+    assert!(
+        kinds[header_len..]
+            .iter()
+            .all(|k| *k == Some(BlockKind::Table))
+    );
+}
+
+#[test]
+fn delimiter_without_pipe_is_not_yet_a_table() {
+    // `---` under a line is a setext underline until the line proves otherwise.
+    let mut s = MdStream::new(Options::default());
+    let u = s.append("A | B\n---");
+    assert_eq!(u.pending.unwrap().kind, BlockKind::Heading);
+
+    let mut s = MdStream::new(Options::default());
+    let u = s.append("A | B\n-");
+    assert_eq!(u.pending.unwrap().kind, BlockKind::Paragraph);
+}
@@ -0,0 +1,59 @@
+use mdstream::pending::{TerminatorOptions, terminate_markdown};
+
+fn ansi(text: &str) -> String {
+    let opts = TerminatorOptions {
+        ignore_ansi: true,
+        ..TerminatorOptions::default()
+    };
+    terminate_markdown(text, &opts)
+}
+
+fn plain(text: &str) -> String {
+    terminate_markdown(text, &TerminatorOptions::default())
+}
+
+#[test]
+fn markers_inside_escape_sequences_are_not_balanced() {
+    // `~` and `_` are valid CSI final bytes, `*` an intermediate byte.
+    for text in [
+        "Press \x1b[3~ to delete",
+        "Keys \x1b[3~~\x1b[2~ here",
+        "Mode \x1b[?25_ set",
+        "Odd \x1b[1*x sequence",
+        "Tick \x1b[1` shown",
+    ] {
+        assert_eq!(ansi(text), text, "{text:?}");
+    }
+    // Without the option the markers are closed.
+    assert_ne!(plain("Mode \x1b[?25_ set"), "Mode \x1b[?25_ set");
+    assert_ne!(plain("Tick \x1b[1` shown"), "Tick \x1b[1` shown");
+}
+
+#[test]
+fn real_markers_around_escapes_are_still_closed() {
+    assert_eq!(
+        ansi("Some **\x1b[1;31mred bold"),
+        "Some **\x1b[1;31mred bold**"
+    );
+    assert_eq!(ansi("Run `ls \x1b[0m --color"), "Run `ls \x1b[0m --color`");
+    assert_eq!(ansi("\x1b[3~ and *emph"), "\x1b[3~ and *emph*");
+}
+
+#[test]
+fn sequence_cut_off_at_the_end_is_opaque() {
+    assert_eq!(ansi("Status: \x1b[1;3"), "Status: \x1b[1;3");
+    assert_eq!(ansi("Up \x1b[1_"), "Up \x1b[1_");
+}
+
+#[test]
+fn ignore_ansi_output_is_idempotent() {
+    for text in [
+        "Some **\x1b[1;31mred bold",
+        "\x1b[3~ and *emph",
+        "Keys \x1b[3~~\x1b[2~ here ~~strike",
+        "é\x1b[0m *ü",
+    ] {
+        let once = ansi(text);
+        assert_eq!(ansi(&once), once, "{text:?}");
+    }
+}
@@ -0,0 +1,44 @@
+use mdstream::pending::{TerminatorOptions, terminate_markdown};
+
+fn emoji_opts() -> TerminatorOptions {
+    TerminatorOptions {
+        emoji_shortcodes: true,
+        ..TerminatorOptions::default()
+    }
+}
+
+#[test]
+fn hides_trailing_incomplete_shortcode() {
+    let opts = emoji_opts();
+    assert_eq!(terminate_markdown("Hi :smi", &opts), "Hi");
+    assert_eq!(terminate_markdown(":thumbs_up", &opts), "");
+    assert_eq!(
+        terminate_markdown("Nice **work :+1", &opts),
+        "Nice **work**"
+    );
+}
+
+#[test]
+fn leaves_complete_shortcodes_and_other_colons_alone() {
+    let opts = emoji_opts();
+    for text in [
+        "Hi :smile:",
+        "Hi :smile: there",
+        "See http://x:8080",
+        "Meet at 12:3",
+        "Note:",
+        "Hi :",
+        "Run `a :smi",
+        "```\nlet x = :smi",
+    ] {
+        let expected = terminate_markdown(text, &TerminatorOptions::default());
+        assert_eq!(terminate_markdown(text, &opts), expected, "{text:?}");
+    }
+}
+
+#[test]
+fn off_by_default() {
+    let opts = TerminatorOptions::default();
+    assert!(!opts.emoji_shortcodes);
+    assert_eq!(terminate_markdown("Hi :smi", &opts), "Hi :smi");
+}
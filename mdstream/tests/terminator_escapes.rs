@@ -0,0 +1,42 @@
+use mdstream::pending::{TerminatorOptions, terminate_markdown};
+
+fn term(text: &str) -> String {
+    terminate_markdown(text, &TerminatorOptions::default())
+}
+
+#[test]
+fn escaped_markers_are_not_balanced() {
+    for text in [
+        r"\~~x",
+        r"\`x",
+        r"\*x",
+        r"\**x",
+        r"\__x",
+        r"\***x",
+        r"\$$x",
+        r"a \`b` c \`",
+    ] {
+        assert_eq!(term(text), text, "{text:?}");
+    }
+}
+
+#[test]
+fn unescaped_markers_still_close() {
+    assert_eq!(term("~~x"), "~~x~~");
+    assert_eq!(term("`x"), "`x`");
+    assert_eq!(term("*x"), "*x*");
+    assert_eq!(term("**x"), "**x**");
+}
+
+#[test]
+fn escaped_backslash_does_not_escape_the_marker() {
+    assert_eq!(term(r"\\~~x"), r"\\~~x~~");
+    assert_eq!(term(r"\\`x"), r"\\`x`");
+    assert_eq!(term(r"\\**x"), r"\\**x**");
+}
+
+#[test]
+fn backslash_inside_code_span_is_literal() {
+    // `\` inside a code span does not escape the closing backtick.
+    assert_eq!(term(r"`a\` and `b"), r"`a\` and `b`");
+}
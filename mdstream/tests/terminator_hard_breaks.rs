@@ -0,0 +1,61 @@
+mod support;
+
+use mdstream::pending::{TerminatorOptions, terminate_markdown};
+use mdstream::{MdStream, Options};
+
+#[test]
+fn one_trailing_space_is_trimmed_but_hard_breaks_are_kept() {
+    let opts = TerminatorOptions::default();
+    assert_eq!(terminate_markdown("line ", &opts), "line");
+    assert_eq!(terminate_markdown("line  ", &opts), "line  ");
+    assert_eq!(terminate_markdown("line   ", &opts), "line   ");
+    assert_eq!(terminate_markdown("line  \n", &opts), "line  \n");
+    assert_eq!(terminate_markdown("line  \nnext", &opts), "line  \nnext");
+    assert_eq!(terminate_markdown("line  \nnext ", &opts), "line  \nnext");
+}
+
+#[test]
+fn hard_break_composes_with_setext_protection() {
+    let opts = TerminatorOptions::default();
+    assert_eq!(terminate_markdown("line  \n-", &opts), "line  \n-\u{200b}");
+    assert_eq!(terminate_markdown("line  \n- ", &opts), "line  \n-\u{200b}");
+    assert_eq!(
+        terminate_markdown("Title  \n==", &opts),
+        "Title  \n==\u{200b}"
+    );
+}
+
+#[test]
+fn hard_break_pending_display_is_stable_across_chunkings() {
+    let markdown = "line  \nnext  \nlast\n";
+    for chunks in [
+        support::chunk_whole(markdown),
+        support::chunk_lines(markdown),
+        support::chunk_chars(markdown),
+        support::chunk_pseudo_random(markdown, "hard_breaks", 1, 3),
+    ] {
+        let mut s = MdStream::new(Options::default());
+        let mut committed = Vec::new();
+        for chunk in &chunks {
+            let u = s.append(chunk);
+            committed.extend(u.committed);
+            let Some(pending) = u.pending else {
+                continue;
+            };
+            let display = pending.display.expect("display");
+            // Once typed, a hard break never disappears from the display.
+            for line in ["line  \n", "next  \n"] {
+                if pending.raw.contains(line) {
+                    assert!(display.contains(line), "chunks={chunks:?} {display:?}");
+                }
+            }
+            // A trailing "  " being typed is shown as-is (never as a single space).
+            if pending.raw.ends_with("  ") {
+                assert!(display.ends_with("  "), "chunks={chunks:?} {display:?}");
+            }
+        }
+        committed.extend(s.finalize().committed);
+        let raws: Vec<&str> = committed.iter().map(|b| b.raw.as_str()).collect();
+        assert_eq!(raws, vec![markdown], "chunks={chunks:?}");
+    }
+}
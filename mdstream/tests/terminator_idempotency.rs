@@ -0,0 +1,115 @@
+use mdstream::pending::{TerminatorOptions, terminate_markdown};
+
+fn presets() -> Vec<(&'static str, TerminatorOptions)> {
+    vec![
+        ("default", TerminatorOptions::default()),
+        ("conservative", TerminatorOptions::conservative()),
+        ("aggressive", TerminatorOptions::aggressive()),
+        ("none", TerminatorOptions::none()),
+    ]
+}
+
+const CASES: &[&str] = &[
+    "Some **bold",
+    "Some *italic",
+    "Some __bold",
+    "Some _italic",
+    "Some ***bold italic",
+    "Some ~~strike",
+    "Some `code",
+    "Some **bold *nested `code ~~strike $$x ",
+    "Inline $$x + y",
+    "$$\nx^2\n",
+    "$$x$$ and $$y",
+    "See [link](http://exa",
+    "See [link](",
+    "See [link",
+    "See [link]",
+    "![img](http://x",
+    "![img",
+    "Nested [a [b](c",
+    "[link](streamdown:incomplete-link)",
+    "Title\n-",
+    "Title\n=",
+    "Title\n--",
+    "Heading\n---",
+    "here is a list\n-",
+    "- item **bold",
+    "> quote *em",
+    "```rs\nfn main() {",
+    "```",
+    "``",
+    "~~~\ncode",
+    "Mixed `code **bold",
+    "Emoji :smi",
+    "Emoji :smile: done",
+    "trailing single space ",
+    "trailing two spaces  ",
+    "<div>html",
+    "a * b",
+    "2 * 3 * 4",
+    "snake_case_word",
+    "**",
+    "*",
+    "_",
+    "~",
+    "[",
+    "$",
+    "Math $x",
+    "| a | b |\n| -",
+    "\\*escaped",
+    "Some **bold** and *",
+];
+
+fn corpus() -> Vec<String> {
+    let mut out: Vec<String> = CASES.iter().map(|s| s.to_string()).collect();
+    for doc in [
+        include_str!("fixtures/streamdown_bench/mixed_content_realistic.md"),
+        include_str!("fixtures/streamdown_bench/math_complex.md"),
+        include_str!("fixtures/streamdown_bench/basic_multiple_blocks_10.md"),
+        include_str!("fixtures/streamdown_bench/code_multiple_code_blocks.md"),
+        include_str!("fixtures/streamdown_bench/math_with_split_delimiters.md"),
+        include_str!("fixtures/streamdown_bench/html_nested.md"),
+        include_str!("fixtures/streamdown_bench/table_simple.md"),
+    ] {
+        // Every char-boundary prefix, as a streaming renderer would see it.
+        for (i, _) in doc.char_indices().skip(1) {
+            out.push(doc[..i].to_string());
+        }
+    }
+    out
+}
+
+#[test]
+fn terminate_markdown_is_idempotent_for_all_presets() {
+    let corpus = corpus();
+    let mut failures = Vec::new();
+    for (name, opts) in presets() {
+        for text in &corpus {
+            let once = terminate_markdown(text, &opts);
+            let twice = terminate_markdown(&once, &opts);
+            if once != twice {
+                failures.push(format!("{name}: {text:?} -> {once:?} -> {twice:?}"));
+            }
+        }
+    }
+    assert!(
+        failures.is_empty(),
+        "{} non-idempotent cases:\n{}",
+        failures.len(),
+        failures[..failures.len().min(40)].join("\n")
+    );
+}
+
+#[test]
+fn second_pass_recognizes_its_own_closers() {
+    let opts = TerminatorOptions::default();
+
+    let once = terminate_markdown("Nested [a [b](c", &opts);
+    assert_eq!(once, "Nested [a [b](streamdown:incomplete-link)");
+    assert_eq!(terminate_markdown(&once, &opts), once);
+
+    let once = terminate_markdown("**bold *italic `code ~~strike", &opts);
+    assert_eq!(once, "**bold *italic `code ~~strike*`~~");
+    assert_eq!(terminate_markdown(&once, &opts), once);
+}
@@ -0,0 +1,71 @@
+use mdstream::pending::{TerminatorOptions, terminate_markdown};
+
+fn term(text: &str) -> String {
+    let opts = TerminatorOptions {
+        nested_emphasis: true,
+        ..TerminatorOptions::default()
+    };
+    terminate_markdown(text, &opts)
+}
+
+/// Mixed-emphasis tails and their expected closers (innermost first).
+const CORPUS: &[(&str, &str)] = &[
+    ("*a **b _c", "*a **b _c_***"),
+    ("**a *b _c", "**a *b _c_***"),
+    ("_a **b *c", "_a **b *c***_"),
+    ("**a _b *c", "**a _b *c*_**"),
+    ("*a _b **c", "*a _b **c**_*"),
+    ("_a *b **c", "_a *b **c***_"),
+    ("__a *b **c", "__a *b **c***__"),
+    ("**a __b *c", "**a __b *c*__**"),
+    ("***a _b", "***a _b_***"),
+    ("_a ***b", "_a ***b***_"),
+    ("*a _b", "*a _b_*"),
+    ("__a *b", "__a *b*__"),
+    // Closed inner spans do not count.
+    ("*a **b** _c", "*a **b** _c_*"),
+    ("**a *b* _c", "**a *b* _c_**"),
+    // Escaped and word-internal markers are text.
+    ("*a \\*b _c", "*a \\*b _c_*"),
+    ("**a snake_case *c", "**a snake_case *c***"),
+    // An opener still being typed is left alone.
+    ("*a **b _", "*a **b _***"),
+    // Closers go before trailing newlines so they do not start a new line.
+    ("*a **b _c\n", "*a **b _c_***\n"),
+];
+
+#[test]
+fn nested_openers_close_in_reverse_order() {
+    for (input, expected) in CORPUS {
+        assert_eq!(term(input), *expected, "{input:?}");
+    }
+}
+
+#[test]
+fn nested_emphasis_termination_is_idempotent() {
+    for (input, _) in CORPUS {
+        let once = term(input);
+        assert_eq!(term(&once), once, "{input:?}");
+    }
+}
+
+#[test]
+fn streaming_prefixes_are_idempotent() {
+    let text = "**bold *italic _under and more_ text* done** and __x *y__ z*";
+    for end in 1..=text.len() {
+        let once = term(&text[..end]);
+        assert_eq!(term(&once), once, "{:?}", &text[..end]);
+    }
+}
+
+#[test]
+fn nested_emphasis_is_off_by_default() {
+    let opts = TerminatorOptions::default();
+    assert!(!opts.nested_emphasis);
+    assert_eq!(
+        terminate_markdown("**bold and *italic", &opts),
+        "**bold and *italic*"
+    );
+    assert!(TerminatorOptions::aggressive().nested_emphasis);
+    assert_eq!(term("**bold and *italic"), "**bold and *italic***");
+}
@@ -0,0 +1,54 @@
+use mdstream::pending::{TerminatorOptions, terminate_markdown};
+use mdstream::{MdStream, Options};
+
+fn term(text: &str) -> String {
+    terminate_markdown(text, &TerminatorOptions::default())
+}
+
+/// Tails whose open emphasis sits before a blank line, and their expected displays.
+const CORPUS: &[(&str, &str)] = &[
+    ("**bold\n\nnew para", "**bold\n\nnew para"),
+    ("*it\n\nnew para", "*it\n\nnew para"),
+    ("__bold\n\nnew para", "__bold\n\nnew para"),
+    ("_it\n\nnew para", "_it\n\nnew para"),
+    ("***both\n\nnew para", "***both\n\nnew para"),
+    ("**bold\n\n", "**bold\n\n"),
+    // Whitespace-only lines are blank lines too.
+    ("**bold\n  \nnew para", "**bold\n  \nnew para"),
+    // The last paragraph is still balanced on its own.
+    ("**bold\n\nnew *para", "**bold\n\nnew *para*"),
+    ("a\n\n**b\nc", "a\n\n**b\nc**"),
+    // A single newline does not end the paragraph.
+    ("**bold\nsame para", "**bold\nsame para**"),
+    // Blank lines inside fenced code or math blocks are not paragraph breaks.
+    (
+        "**x\n```\na\n\nb\n```\nmore",
+        "**x\n```\na\n\nb\n```\nmore**",
+    ),
+    ("**x\n$$\na\n\nb\n$$\nmore", "**x\n$$\na\n\nb\n$$\nmore**"),
+];
+
+#[test]
+fn emphasis_is_not_closed_across_a_blank_line() {
+    for (input, expected) in CORPUS {
+        assert_eq!(term(input), *expected, "input: {input:?}");
+    }
+}
+
+#[test]
+fn paragraph_break_corpus_is_idempotent() {
+    for (input, _) in CORPUS {
+        let once = term(input);
+        assert_eq!(term(&once), once, "input: {input:?}");
+    }
+}
+
+#[test]
+fn pending_list_display_does_not_bleed_bold_into_the_next_item() {
+    let mut s = MdStream::new(Options::default());
+    s.append("- **first\n\n");
+    let u = s.append("- second");
+    let pending = u.pending.expect("pending");
+    assert_eq!(pending.raw, "- **first\n\n- second");
+    assert_eq!(pending.display.as_deref(), Some("- **first\n\n- second"));
+}
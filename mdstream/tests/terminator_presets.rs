@@ -0,0 +1,35 @@
+use mdstream::pending::{TerminatorOptions, terminate_markdown};
+
+#[test]
+fn none_preset_is_identity() {
+    let opts = TerminatorOptions::none();
+    for text in [
+        "Some **bold *nested `code ~~strike $$x ",
+        "here is a list\n-",
+        "See [link](http://exa",
+        "![img](http://x",
+        "trailing single space ",
+        "```rs\nfn main() {",
+    ] {
+        assert_eq!(terminate_markdown(text, &opts), text);
+    }
+}
+
+#[test]
+fn conservative_preset_leaves_emphasis_alone() {
+    let opts = TerminatorOptions::conservative();
+    assert_eq!(terminate_markdown("Some **bold", &opts), "Some **bold");
+    assert_eq!(terminate_markdown("Some `code", &opts), "Some `code`");
+    assert_eq!(
+        terminate_markdown("See [link](http://exa", &opts),
+        "See [link](streamdown:incomplete-link)"
+    );
+}
+
+#[test]
+fn aggressive_preset_closes_everything() {
+    let opts = TerminatorOptions::aggressive();
+    assert_eq!(terminate_markdown("Some **bold", &opts), "Some **bold**");
+    assert_eq!(terminate_markdown("Some ~~gone", &opts), "Some ~~gone~~");
+    assert_eq!(terminate_markdown("Hi :smi", &opts), "Hi");
+}
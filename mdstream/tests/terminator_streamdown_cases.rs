@@ -95,6 +95,20 @@ fn inline_code_and_triple_backticks() {
     );
 }
 
+#[test]
+fn fence_opening_in_progress_is_left_untouched() {
+    let opts = TerminatorOptions::default();
+    for text in [
+        "```rus",
+        "```",
+        "~~~py",
+        "Intro\n\n~~~py",
+        "Intro\n   ```rust_x",
+    ] {
+        assert_eq!(terminate_markdown(text, &opts), text);
+    }
+}
+
 #[test]
 fn strikethrough_and_katex() {
     let opts = TerminatorOptions::default();
@@ -173,6 +187,10 @@ fn streamdown_component_incomplete_markdown_disabled() {
         inline_code: false,
         strikethrough: false,
         katex_block: false,
+        emoji_shortcodes: false,
+        strict_flanking: false,
+        nested_emphasis: false,
+        ignore_ansi: false,
         incomplete_link_url: TerminatorOptions::default().incomplete_link_url,
         window_bytes: TerminatorOptions::default().window_bytes,
     };
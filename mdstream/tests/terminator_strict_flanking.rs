@@ -0,0 +1,58 @@
+use mdstream::pending::{TerminatorOptions, terminate_markdown};
+
+fn lenient(text: &str) -> String {
+    terminate_markdown(text, &TerminatorOptions::default())
+}
+
+fn strict(text: &str) -> String {
+    let opts = TerminatorOptions {
+        strict_flanking: true,
+        ..TerminatorOptions::default()
+    };
+    terminate_markdown(text, &opts)
+}
+
+/// Tails the lenient passes close although CommonMark would not open emphasis there:
+/// `(text, lenient, strict)`.
+const AMBIGUOUS: &[(&str, &str, &str)] = &[
+    // Surrounded by whitespace: neither left- nor right-flanking.
+    ("2 * 3 = 6", "2 * 3 = 6*", "2 * 3 = 6"),
+    // Preceded by a letter and followed by punctuation: not left-flanking.
+    ("foo*\"bar\"", "foo*\"bar\"*", "foo*\"bar\""),
+    ("foo**\"bar\"", "foo**\"bar\"**", "foo**\"bar\""),
+    ("x_(y", "x_(y_", "x_(y"),
+];
+
+/// Tails both modes close the same way.
+const AGREE: &[(&str, &str)] = &[
+    ("*note", "*note*"),
+    ("(*note", "(*note*"),
+    ("a **bold", "a **bold**"),
+    ("_it", "_it_"),
+    ("done *x*", "done *x*"),
+    ("`*code`", "`*code`"),
+];
+
+#[test]
+fn strict_flanking_leaves_non_openers_alone() {
+    for (text, want_lenient, want_strict) in AMBIGUOUS {
+        assert_eq!(lenient(text), *want_lenient, "lenient {text:?}");
+        assert_eq!(strict(text), *want_strict, "strict {text:?}");
+    }
+}
+
+#[test]
+fn strict_flanking_still_closes_valid_openers() {
+    for (text, want) in AGREE {
+        assert_eq!(lenient(text), *want, "lenient {text:?}");
+        assert_eq!(strict(text), *want, "strict {text:?}");
+    }
+    // Strict mode closes from the delimiter stack, like `nested_emphasis`.
+    assert_eq!(strict("*a **b _c"), "*a **b _c_***");
+}
+
+#[test]
+fn strict_flanking_is_off_by_default() {
+    assert!(!TerminatorOptions::default().strict_flanking);
+    assert!(!TerminatorOptions::aggressive().strict_flanking);
+}
@@ -0,0 +1,44 @@
+use mdstream::pending::{TerminatorOptions, terminate_markdown};
+use mdstream::{MdStream, Options};
+
+fn long_code(lines: usize) -> String {
+    "let x = a * b; // *emphasis* lookalike\n".repeat(lines)
+}
+
+#[test]
+fn code_fence_opened_before_the_window_is_left_alone() {
+    let opts = TerminatorOptions::default();
+    let text = format!("Intro\n\n```rust\n{}let y = *p", long_code(600));
+    assert!(text.len() > opts.window_bytes);
+    assert_eq!(terminate_markdown(&text, &opts), text);
+}
+
+#[test]
+fn prose_after_a_fence_closed_inside_the_window_is_still_terminated() {
+    let opts = TerminatorOptions::default();
+    let text = format!("```rust\n{}```\n\nSome *emph", long_code(600));
+    assert!(text.len() > opts.window_bytes);
+    assert_eq!(terminate_markdown(&text, &opts), format!("{text}*"));
+}
+
+#[test]
+fn small_window_cutting_into_a_fence() {
+    let opts = TerminatorOptions {
+        window_bytes: 16,
+        ..TerminatorOptions::default()
+    };
+    let open = "```\nfirst line of code\nsecond * line\nthird *";
+    assert_eq!(terminate_markdown(open, &opts), open);
+
+    let closed = "```\nfirst line of code\n```\nafter *x";
+    assert_eq!(terminate_markdown(closed, &opts), format!("{closed}*"));
+}
+
+#[test]
+fn list_with_long_nested_fence_keeps_code_display() {
+    let code = long_code(600).replace('\n', "\n  ");
+    let text = format!("- item\n\n  ```\n  {code}*p");
+    let mut s = MdStream::new(Options::default());
+    let pending = s.append(&text).pending.expect("pending list");
+    assert_eq!(pending.display.as_deref(), Some(text.as_str()));
+}
@@ -0,0 +1,50 @@
+#![cfg(feature = "trace")]
+
+use std::sync::{Arc, Mutex};
+
+use mdstream::{MdStream, ModeChange, ModeChangeSite, Options};
+
+fn traced(chunks: &[&str]) -> Vec<ModeChange> {
+    let events = Arc::new(Mutex::new(Vec::new()));
+    let sink = Arc::clone(&events);
+    let opts = Options {
+        trace: Some(Arc::new(move |c: &ModeChange| {
+            sink.lock().unwrap().push(c.clone())
+        })),
+        ..Default::default()
+    };
+    let mut s = MdStream::new(opts);
+    for chunk in chunks {
+        s.append(chunk);
+    }
+    s.finalize();
+    events.lock().unwrap().clone()
+}
+
+#[test]
+fn reports_mode_transitions_for_a_known_document() {
+    use ModeChangeSite::{Commit, StartMode, UpdateMode};
+
+    let events = traced(&["# Title\nA | B\n--- | ---\n", "\n```rs\ncode\n```\n"]);
+    let got: Vec<(ModeChangeSite, usize, &str, &str)> = events
+        .iter()
+        .map(|e| (e.site, e.line_index, e.line.as_str(), e.to.as_str()))
+        .collect();
+    let fence = "CodeFence { fence_char: '`', fence_len: 3 }";
+    assert_eq!(
+        got,
+        vec![
+            (StartMode, 0, "# Title", "Heading"),
+            (Commit, 0, "# Title", "Unknown"),
+            (StartMode, 1, "A | B", "Paragraph"),
+            (UpdateMode, 2, "--- | ---", "Table"),
+            (Commit, 3, "", "Unknown"),
+            (StartMode, 4, "```rs", fence),
+            (Commit, 6, "```", "Unknown"),
+        ]
+    );
+    // Each event starts from the mode the previous one switched to.
+    for pair in events.windows(2) {
+        assert_eq!(pair[0].to, pair[1].from);
+    }
+}
@@ -0,0 +1,119 @@
+use mdstream::{BlockKind, MdStream, Options, TypographyTransformer};
+
+fn apply(kind: BlockKind, text: &str) -> String {
+    TypographyTransformer::default()
+        .apply(kind, text)
+        .unwrap_or_else(|| text.to_string())
+}
+
+#[test]
+fn prose_gets_curly_quotes_dashes_and_ellipses() {
+    assert_eq!(
+        apply(BlockKind::Paragraph, "She said \"hi\" -- it's 'fine'...\n"),
+        "She said “hi” — it’s ‘fine’…\n"
+    );
+    assert_eq!(apply(BlockKind::Heading, "# \"Title\"\n"), "# “Title”\n");
+    assert_eq!(apply(BlockKind::List, "- \"a\"\n- b's\n"), "- “a”\n- b’s\n");
+    assert_eq!(apply(BlockKind::BlockQuote, "> \"q\"\n"), "> “q”\n");
+}
+
+#[test]
+fn code_and_other_kinds_are_untouched() {
+    let fence = "```py\nprint(\"x\") -- ...\n```\n";
+    assert_eq!(
+        TypographyTransformer::default().apply(BlockKind::CodeFence, fence),
+        None
+    );
+    for kind in [
+        BlockKind::Table,
+        BlockKind::HtmlBlock,
+        BlockKind::MathBlock,
+        BlockKind::ThematicBreak,
+    ] {
+        assert_eq!(
+            TypographyTransformer::default().apply(kind, "\"a\" -- b\n"),
+            None
+        );
+    }
+
+    assert_eq!(
+        apply(BlockKind::Paragraph, "Run `echo \"hi\" -- x` now, \"ok\"\n"),
+        "Run `echo \"hi\" -- x` now, “ok”\n"
+    );
+    assert_eq!(
+        apply(
+            BlockKind::List,
+            "- \"a\"\n\n  ```sh\n  a -- \"b\"\n  ```\n- \"c\"\n"
+        ),
+        "- “a”\n\n  ```sh\n  a -- \"b\"\n  ```\n- “c”\n"
+    );
+}
+
+#[test]
+fn links_html_escapes_and_rules_are_preserved() {
+    assert_eq!(
+        apply(
+            BlockKind::Paragraph,
+            "[\"x\"](https://a.example/a--b) <https://b.example/c--d> \\\"raw\\\" <!-- c -->\n"
+        ),
+        "[“x”](https://a.example/a--b) <https://b.example/c--d> \\\"raw\\\" <!-- c -->\n"
+    );
+    assert_eq!(
+        apply(BlockKind::Heading, "\"Title\"\n---\n"),
+        "“Title”\n---\n"
+    );
+}
+
+#[test]
+fn replacements_are_configurable() {
+    let t = TypographyTransformer {
+        smart_quotes: false,
+        dashes: true,
+        ellipses: false,
+    };
+    assert_eq!(
+        t.apply(BlockKind::Paragraph, "\"a\" -- b...\n").as_deref(),
+        Some("\"a\" — b...\n")
+    );
+    assert_eq!(t.apply(BlockKind::Paragraph, "plain\n"), None);
+}
+
+#[test]
+fn streaming_pending_and_committed_display() {
+    let mut s = MdStream::new(Options {
+        commit_with_display: true,
+        ..Default::default()
+    });
+    s.push_pending_transformer(TypographyTransformer::default());
+
+    let u = s.append("He said \"stop\n");
+    let pending = u.pending.expect("pending");
+    assert_eq!(pending.raw, "He said \"stop\n");
+    assert_eq!(pending.display.as_deref(), Some("He said “stop\n"));
+
+    let u = s.append("now\" -- ok\n\n```\n\"code\" -- x\n");
+    assert_eq!(u.committed.len(), 1);
+    assert_eq!(u.committed[0].raw, "He said \"stop\nnow\" -- ok\n\n");
+    assert_eq!(
+        u.committed[0].display.as_deref(),
+        Some("He said “stop\nnow” — ok\n\n")
+    );
+    let pending = u.pending.expect("code pending");
+    assert_eq!(pending.kind, BlockKind::CodeFence);
+    assert!(
+        pending
+            .display
+            .as_deref()
+            .unwrap_or(&pending.raw)
+            .contains("\"code\" -- x")
+    );
+
+    let u = s.finalize();
+    assert_eq!(u.committed[0].kind, BlockKind::CodeFence);
+    assert!(
+        u.committed[0]
+            .display
+            .as_deref()
+            .is_none_or(|d| d.contains("\"code\" -- x"))
+    );
+}
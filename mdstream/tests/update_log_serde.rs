@@ -0,0 +1,51 @@
+#![cfg(feature = "serde")]
+
+use mdstream::{MdStream, Options, UpdateLog};
+
+#[test]
+fn update_log_round_trips_and_replays() {
+    let mut s = MdStream::new(Options::default());
+    let mut log = UpdateLog::new();
+    for chunk in [
+        "# Title\n\nSome **bo",
+        "ld** text\n\n```rs\nfn main() {",
+        "}\n```\n",
+    ] {
+        log.push(s.append(chunk));
+    }
+    log.push(s.finalize());
+
+    let json = serde_json::to_string(&log).unwrap();
+    let restored: UpdateLog = serde_json::from_str(&json).unwrap();
+    assert_eq!(restored, log);
+
+    let state = restored.replay();
+    let raws: Vec<&str> = state.blocks().map(|b| b.raw.as_str()).collect();
+    assert_eq!(
+        raws,
+        vec![
+            "# Title\n",
+            "Some **bold** text\n\n",
+            "```rs\nfn main() {}\n```\n"
+        ]
+    );
+}
+
+#[test]
+fn pending_display_survives_serialization() {
+    let mut s = MdStream::new(Options::default());
+    let u = s.append("Some **bold");
+    let pending = u.pending.clone().unwrap();
+    assert_eq!(pending.display.as_deref(), Some("Some **bold**"));
+
+    let json = serde_json::to_string(&u).unwrap();
+    assert!(!json.contains("\"diagnostics\""));
+    let back: mdstream::Update = serde_json::from_str(&json).unwrap();
+    assert_eq!(back, u);
+
+    // `display` may be omitted entirely (e.g. committed blocks).
+    let block: mdstream::Block =
+        serde_json::from_str(r#"{"id":1,"status":"Committed","kind":"Paragraph","raw":"Hi\n"}"#)
+            .unwrap();
+    assert!(block.display.is_none());
+}
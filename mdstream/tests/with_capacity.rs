@@ -0,0 +1,69 @@
+use mdstream::{AnalyzedStream, Block, CodeFenceAnalyzer, MdStream, Options};
+
+fn large_fixture() -> String {
+    let parts = [
+        include_str!("fixtures/streamdown_bench/basic_many_blocks_100.md"),
+        include_str!("fixtures/streamdown_bench/code_multiple_code_blocks.md"),
+        include_str!("fixtures/streamdown_bench/table_large_100_rows.md"),
+        include_str!("fixtures/streamdown_bench/mixed_content_realistic.md"),
+    ];
+    let mut out = String::new();
+    for _ in 0..8 {
+        for part in parts {
+            out.push_str(part);
+            out.push_str("\n\n");
+        }
+    }
+    out
+}
+
+fn collect(s: &mut MdStream, text: &str) -> Vec<Block> {
+    let mut blocks = Vec::new();
+    let chars: Vec<char> = text.chars().collect();
+    for chunk in chars.chunks(97) {
+        let chunk: String = chunk.iter().collect();
+        blocks.extend(s.append(&chunk).committed);
+    }
+    blocks.extend(s.finalize().committed);
+    blocks
+}
+
+#[test]
+fn md_stream_with_capacity_matches_new() {
+    let text = large_fixture();
+    let lines = text.lines().count();
+
+    let expected = collect(&mut MdStream::new(Options::default()), &text);
+    let got = collect(
+        &mut MdStream::with_capacity(Options::default(), text.len(), lines),
+        &text,
+    );
+    assert!(expected.len() > 100);
+    assert_eq!(got, expected);
+
+    // An undersized hint is still only a hint.
+    let got = collect(
+        &mut MdStream::with_capacity(Options::default(), 1, 1),
+        &text,
+    );
+    assert_eq!(got, expected);
+}
+
+#[test]
+fn analyzed_stream_with_capacity_matches_new() {
+    let text = large_fixture();
+    let mut a = AnalyzedStream::new(Options::default(), CodeFenceAnalyzer);
+    let mut b = AnalyzedStream::with_capacity(Options::default(), CodeFenceAnalyzer, 512);
+
+    let mut ua = a.append(&text);
+    let mut ub = b.append(&text);
+    let fa = a.finalize();
+    let fb = b.finalize();
+    ua.update.committed.extend(fa.update.committed);
+    ub.update.committed.extend(fb.update.committed);
+    assert_eq!(ua.update.committed, ub.update.committed);
+
+    for block in &ua.update.committed {
+        assert_eq!(a.meta_for(block.id), b.meta_for(block.id));
+    }
+}